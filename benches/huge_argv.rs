@@ -0,0 +1,57 @@
+//! Parses command lines of growing length, like the file lists given by `xargs` or shell globs.
+//! Run with `cargo bench`, the time per argument should stay flat as the command line grows.
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use clarg::{Arg, ArgParser};
+
+const ROUNDS: u32 = 5;
+
+fn parser() -> ArgParser {
+    ArgParser::new("Count lines of files.")
+        .arg(Arg::boolean("verbose", Some('v'), "Verbose execution"))
+        .arg(Arg::integer("jobs", Some('j'), false, "Number of threads"))
+        .arg(Arg::string("exclude", Some('e'), false, "Patterns to skip").multiple(true))
+        .arg(
+            Arg::string("files", None, false, "Files to examine")
+                .positional(true)
+                .multiple(true),
+        )
+}
+
+/// Best time of a few rounds parsing `args`.
+fn measure(parser: &ArgParser, args: &[String]) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            let parsed = parser.try_parse_from(args).expect("valid command line");
+            black_box(parsed);
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+fn report(name: &str, count: usize, elapsed: Duration) {
+    let per_arg = elapsed.as_nanos() / count as u128;
+    println!("{name:<24} {count:>8} args {elapsed:>12.2?} {per_arg:>6} ns/arg");
+}
+
+fn main() {
+    let parser = parser();
+    for count in [10_000, 40_000, 160_000] {
+        let files: Vec<String> = (0..count).map(|i| format!("src/file_{i}.rs")).collect();
+        report("positionals", count, measure(&parser, &files));
+
+        let mut args = vec!["-v".to_owned(), "--jobs".to_owned(), "4".to_owned()];
+        args.push("--exclude".to_owned());
+        args.extend((0..count).map(|i| format!("target/{i}/*")));
+        report("multi-value option", count, measure(&parser, &args));
+
+        let mut args = files.clone();
+        args.insert(0, "--".to_owned());
+        report("after separator", count, measure(&parser, &args));
+    }
+}
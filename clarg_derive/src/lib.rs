@@ -0,0 +1,427 @@
+//! Derive macro for [clarg](https://crates.io/crates/clarg), declaring a command line interface as a struct.
+//!
+//! Enabled through the `derive` feature of `clarg`.
+//! # Example
+//! ```no_run
+//! use clarg::ClArgs;
+//!
+//! /// Find duplicate files.
+//! #[derive(ClArgs)]
+//! struct Args {
+//!     /// Directory to examine
+//!     #[arg(short = 'p')]
+//!     path: String,
+//!     /// verbose execution
+//!     #[arg(short = 'V')]
+//!     verbose: bool,
+//!     /// Number of threads
+//!     #[arg(default = "4")]
+//!     threads: i32,
+//!     /// Extensions to include
+//!     #[arg(long = "ext")]
+//!     extensions: Vec<String>,
+//!     /// Output file
+//!     output: Option<String>,
+//! }
+//!
+//! let args = Args::parse();
+//! ```
+//!
+//! Field types decide the kind of argument:
+//! * `bool` fields are boolean flags.
+//! * `Option<T>` fields are optional.
+//! * `Vec<T>` fields accept multiple values.
+//! * Any other type is required unless it has a `default`.
+//!
+//! Integer and floating point types are validated as such, `i128` and `u128` over their whole range, any other type is parsed with its `FromStr` implementation.
+//! Doc comments become the descriptions of the fields and the program.
+//!
+//! Supported field attributes: `#[arg(short = 'c', long = "name", required, hidden, default = "value", value_name = "DIR", help = "text")]`.
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Derives `parser()`, `parse()` and `from_map()` for a struct with named fields.
+#[proc_macro_derive(ClArgs, attributes(arg))]
+pub fn derive_clargs(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(code) => code.parse().expect("generated code is valid"),
+        Err(message) => format!("compile_error!({message:?});")
+            .parse()
+            .expect("compile_error is valid"),
+    }
+}
+
+/// Shape of a field type relevant to the argument it produces.
+enum FieldType {
+    Flag,
+    Optional(String),
+    Many(String),
+    Single(String),
+}
+
+/// An argument described by a struct field.
+struct Field {
+    name: String,
+    ty: FieldType,
+    long: String,
+    short: Option<char>,
+    required: bool,
+    hidden: bool,
+    default: Option<String>,
+    value_name: Option<String>,
+    help: String,
+}
+
+fn expand(input: TokenStream) -> Result<String, String> {
+    let mut tokens = input.into_iter().peekable();
+    let mut description = Vec::new();
+    let mut name = None;
+    let mut body = None;
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == '#' => {
+                if let Some(TokenTree::Group(group)) = tokens.next() {
+                    if let Some(doc) = doc_comment(group.stream()) {
+                        description.push(doc);
+                    }
+                }
+            }
+            TokenTree::Ident(ident) if ident.to_string() == "struct" => {
+                name = match tokens.next() {
+                    Some(TokenTree::Ident(name)) => Some(name.to_string()),
+                    _ => return Err("ClArgs expects a struct name.".to_owned()),
+                };
+                match tokens.next() {
+                    Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                        body = Some(group.stream());
+                    }
+                    Some(TokenTree::Punct(p)) if p.as_char() == '<' => {
+                        return Err("ClArgs does not support generic structs.".to_owned())
+                    }
+                    _ => return Err("ClArgs only supports structs with named fields.".to_owned()),
+                }
+                break;
+            }
+            TokenTree::Ident(ident)
+                if ident.to_string() == "enum" || ident.to_string() == "union" =>
+            {
+                return Err("ClArgs only supports structs with named fields.".to_owned())
+            }
+            _ => {}
+        }
+    }
+    let (Some(name), Some(body)) = (name, body) else {
+        return Err("ClArgs only supports structs with named fields.".to_owned());
+    };
+    let fields = parse_fields(body)?;
+
+    let mut code = format!("impl {name} {{\n");
+    code.push_str("    /// Builds the argument parser describing this struct.\n");
+    code.push_str("    pub fn parser() -> ::clarg::ArgParser {\n");
+    code.push_str(&format!(
+        "        ::clarg::ArgParser::new({:?})\n",
+        description.join(" ")
+    ));
+    for field in &fields {
+        code.push_str(&format!("            .arg({})\n", arg_expression(field)));
+    }
+    code.push_str("    }\n\n");
+    code.push_str("    /// Parses the command line arguments, exiting on invalid input like `ArgParser::parse`.\n");
+    code.push_str("    pub fn parse() -> Self {\n");
+    code.push_str("        Self::from_map(&Self::parser().parse())\n");
+    code.push_str("    }\n\n");
+    code.push_str("    /// Builds the struct from arguments parsed by `parser()`.\n");
+    code.push_str("    pub fn from_map(map: &::clarg::ArgMap) -> Self {\n");
+    code.push_str("        Self {\n");
+    for field in &fields {
+        let value = match &field.ty {
+            FieldType::Flag => format!("map.get::<bool>({:?}).unwrap_or(false)", field.long),
+            FieldType::Optional(ty) => {
+                format!("::clarg::__derive::optional::<{ty}>(map, {:?})", field.long)
+            }
+            FieldType::Many(ty) => {
+                format!("::clarg::__derive::many::<{ty}>(map, {:?})", field.long)
+            }
+            FieldType::Single(ty) => {
+                format!("::clarg::__derive::required::<{ty}>(map, {:?})", field.long)
+            }
+        };
+        code.push_str(&format!("            {}: {},\n", field.name, value));
+    }
+    code.push_str("        }\n    }\n}\n");
+    Ok(code)
+}
+
+/// Expression building the `clarg::Arg` for a field.
+fn arg_expression(field: &Field) -> String {
+    let (kind, multiple) = match &field.ty {
+        FieldType::Flag => ("Boolean", false),
+        FieldType::Optional(ty) | FieldType::Single(ty) => (kind_of(ty), false),
+        FieldType::Many(ty) => (kind_of(ty), true),
+    };
+    let mut arg = format!(
+        "::clarg::Arg::new({:?}).kind(::clarg::ArgKind::{kind}).help({:?})",
+        field.long, field.help
+    );
+    if let Some(short) = field.short {
+        arg.push_str(&format!(".short({short:?})"));
+    }
+    if field.required {
+        arg.push_str(".required(true)");
+    }
+    if multiple {
+        arg.push_str(".multiple(true)");
+    }
+    if field.hidden {
+        arg.push_str(".hidden(true)");
+    }
+    if let Some(default) = &field.default {
+        arg.push_str(&format!(".default_value({default:?})"));
+    }
+    if let Some(name) = &field.value_name {
+        arg.push_str(&format!(".value_name({name:?})"));
+    }
+    // beyond the 64 bits integer arguments take by default.
+    let wide = match &field.ty {
+        FieldType::Flag => false,
+        FieldType::Optional(ty) | FieldType::Single(ty) | FieldType::Many(ty) => {
+            matches!(ty.as_str(), "i128" | "u128")
+        }
+    };
+    if wide {
+        arg = format!("::clarg::__derive::wide({arg})");
+    }
+    arg
+}
+
+/// Kind of argument validating values of a type.
+fn kind_of(ty: &str) -> &'static str {
+    match ty {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => "Integer",
+        "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => "Unsigned",
+        "f32" | "f64" => "Float",
+        "PathBuf" | "std::path::PathBuf" | "::std::path::PathBuf" => "Path",
+        "IpAddr" | "std::net::IpAddr" | "::std::net::IpAddr" => "IpAddr",
+        "SocketAddr" | "std::net::SocketAddr" | "::std::net::SocketAddr" => "SocketAddr",
+        "Regex" | "regex::Regex" | "::regex::Regex" => "Regex",
+        "Url" | "url::Url" | "::url::Url" => "Url",
+        "Version" | "semver::Version" | "::semver::Version" => "SemVer",
+        "Uuid" | "uuid::Uuid" | "::uuid::Uuid" => "Uuid",
+        _ => "String",
+    }
+}
+
+fn parse_fields(body: TokenStream) -> Result<Vec<Field>, String> {
+    let mut fields = Vec::new();
+    let mut tokens = body.into_iter().peekable();
+    while tokens.peek().is_some() {
+        let mut docs = Vec::new();
+        let mut attributes = Vec::new();
+        // attributes and visibility
+        let name = loop {
+            match tokens.next() {
+                Some(TokenTree::Punct(p)) if p.as_char() == '#' => {
+                    if let Some(TokenTree::Group(group)) = tokens.next() {
+                        let stream = group.stream();
+                        if let Some(doc) = doc_comment(stream.clone()) {
+                            docs.push(doc);
+                        } else if let Some(arguments) = arg_attribute(stream) {
+                            attributes.push(arguments);
+                        }
+                    }
+                }
+                Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                    if let Some(TokenTree::Group(_)) = tokens.peek() {
+                        tokens.next();
+                    }
+                }
+                Some(TokenTree::Ident(ident)) => break ident.to_string(),
+                _ => return Err("ClArgs only supports structs with named fields.".to_owned()),
+            }
+        };
+        match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == ':' => {}
+            _ => return Err(format!("Expected a type for field `{name}`.")),
+        }
+        // type tokens, up to the next comma outside angle brackets.
+        let mut ty = Vec::new();
+        let mut depth = 0;
+        for token in tokens.by_ref() {
+            if let TokenTree::Punct(p) = &token {
+                match p.as_char() {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    ',' if depth == 0 => break,
+                    _ => {}
+                }
+            }
+            ty.push(token);
+        }
+
+        let ty = field_type(&ty);
+        let mut field = Field {
+            long: name.trim_start_matches("r#").replace('_', "-"),
+            required: matches!(ty, FieldType::Single(_)),
+            name,
+            ty,
+            short: None,
+            hidden: false,
+            default: None,
+            value_name: None,
+            help: docs.join(" "),
+        };
+        for arguments in attributes {
+            apply_attribute(&mut field, arguments)?;
+        }
+        if field.default.is_some() {
+            field.required = false;
+        }
+        fields.push(field);
+    }
+    Ok(fields)
+}
+
+/// Classifies the tokens of a field type.
+fn field_type(tokens: &[TokenTree]) -> FieldType {
+    let text: String = tokens
+        .iter()
+        .map(|token| token.to_string())
+        .collect::<Vec<_>>()
+        .join("");
+    let inner = |wrapper: &str| {
+        text.strip_prefix(wrapper)
+            .and_then(|rest| rest.strip_prefix('<'))
+            .and_then(|rest| rest.strip_suffix('>'))
+            .map(str::to_owned)
+    };
+    if text == "bool" {
+        FieldType::Flag
+    } else if let Some(ty) = inner("Option") {
+        FieldType::Optional(ty)
+    } else if let Some(ty) = inner("Vec") {
+        FieldType::Many(ty)
+    } else {
+        FieldType::Single(text)
+    }
+}
+
+/// Applies the content of an `#[arg(...)]` attribute to a field.
+fn apply_attribute(field: &mut Field, arguments: TokenStream) -> Result<(), String> {
+    let tokens: Vec<TokenTree> = arguments.into_iter().collect();
+    for item in tokens.split(|token| matches!(token, TokenTree::Punct(p) if p.as_char() == ',')) {
+        match item {
+            [] => {}
+            [TokenTree::Ident(key)] if key.to_string() == "required" => field.required = true,
+            [TokenTree::Ident(key)] if key.to_string() == "hidden" => field.hidden = true,
+            [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(value)]
+                if eq.as_char() == '=' =>
+            {
+                let value = value.to_string();
+                match key.to_string().as_str() {
+                    "short" => {
+                        let mut chars = value
+                            .strip_prefix('\'')
+                            .and_then(|v| v.strip_suffix('\''))
+                            .unwrap_or_default()
+                            .chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(c), None) => field.short = Some(c),
+                            _ => return Err(format!("`short` expects a character, got {value}.")),
+                        }
+                    }
+                    "long" => field.long = unquote(&value)?,
+                    "default" => field.default = Some(unquote(&value)?),
+                    "value_name" => field.value_name = Some(unquote(&value)?),
+                    "help" => field.help = unquote(&value)?,
+                    other => return Err(format!("Unknown `arg` attribute `{other}`.")),
+                }
+            }
+            _ => {
+                let text: Vec<String> = item.iter().map(|t| t.to_string()).collect();
+                return Err(format!("Unsupported `arg` attribute `{}`.", text.join(" ")));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Content of an `arg(...)` attribute.
+fn arg_attribute(stream: TokenStream) -> Option<TokenStream> {
+    let mut tokens = stream.into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group)))
+            if ident.to_string() == "arg" && group.delimiter() == Delimiter::Parenthesis =>
+        {
+            Some(group.stream())
+        }
+        _ => None,
+    }
+}
+
+/// Text of a `doc = "..."` attribute, the form doc comments take.
+fn doc_comment(stream: TokenStream) -> Option<String> {
+    let mut tokens = stream.into_iter();
+    match (tokens.next(), tokens.next(), tokens.next()) {
+        (
+            Some(TokenTree::Ident(ident)),
+            Some(TokenTree::Punct(eq)),
+            Some(TokenTree::Literal(text)),
+        ) if ident.to_string() == "doc" && eq.as_char() == '=' => unquote(&text.to_string())
+            .ok()
+            .map(|doc| doc.trim().to_owned()),
+        _ => None,
+    }
+}
+
+/// Value of a string literal as written in the source.
+fn unquote(literal: &str) -> Result<String, String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw[hashes..raw.len() - hashes]
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .map(str::to_owned)
+            .ok_or_else(|| format!("Expected a string, got {literal}."));
+    }
+    let inner = literal
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| format!("Expected a string, got {literal}."))?;
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('0') => result.push('\0'),
+            Some(c @ ('\\' | '"' | '\'')) => result.push(c),
+            Some('\n') => {
+                // line continuation skips the leading whitespace of the next line.
+                while chars.clone().next().is_some_and(char::is_whitespace) {
+                    chars.next();
+                }
+            }
+            Some('u') => {
+                let code: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                let code = code.trim_start_matches('{');
+                let c = u32::from_str_radix(code, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| format!("Invalid unicode escape in {literal}."))?;
+                result.push(c);
+            }
+            Some('x') => {
+                let code: String = chars.by_ref().take(2).collect();
+                let c = u8::from_str_radix(&code, 16)
+                    .map_err(|_| format!("Invalid escape in {literal}."))?;
+                result.push(char::from(c));
+            }
+            _ => return Err(format!("Invalid escape in {literal}.")),
+        }
+    }
+    Ok(result)
+}
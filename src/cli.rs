@@ -0,0 +1,81 @@
+//! Frozen definition of a command line interface, see [`Cli`].
+use std::{ffi::OsStr, ops::Deref, sync::Arc};
+
+use crate::{ArgMap, ArgParser, ParseReport};
+
+/// Definition of a command line interface that is complete and validated, built with
+/// [`ArgParser::build`].
+///
+/// Unlike [`ArgParser`], it can't be changed anymore and parses any number of times:
+/// every parsing method takes `&self`. Clones share the definition, so they are cheap and
+/// can be handed to threads. The read-only methods of [`ArgParser`], like
+/// [`ArgParser::write_help`] or [`ArgParser::generate_completions`], are available through
+/// [`Deref`], and [`crate::graph::render_dot`] or [`crate::schema::render_schema`] accept `&cli`.
+///
+/// # Example
+/// ```
+/// use clarg::{Arg, ArgParser};
+///
+/// let cli = ArgParser::new("Find duplicate files.")
+///     .arg(Arg::integer("depth", Some('d'), false, "Maximum depth"))
+///     .build();
+/// let shallow = cli.try_parse_from(["--depth", "1"]).unwrap();
+/// let deep = cli.clone().try_parse_from(["--depth", "9"]).unwrap();
+/// assert_eq!(shallow.get::<i64>("depth"), Ok(1));
+/// assert_eq!(deep.get::<i64>("depth"), Ok(9));
+/// ```
+#[derive(Clone)]
+pub struct Cli {
+    parser: Arc<ArgParser>,
+}
+
+impl Cli {
+    pub(crate) fn new(parser: ArgParser) -> Cli {
+        Cli {
+            parser: Arc::new(parser),
+        }
+    }
+
+    /// Parse the command line like [`ArgParser::parse`], halting the execution on errors.
+    pub fn parse(&self) -> ArgMap {
+        self.parser
+            .try_parse()
+            .unwrap_or_else(|error| self.parser.exit_with(error))
+    }
+
+    /// Parse the given arguments like [`ArgParser::parse_from_os`], halting the execution on errors.
+    /// # Arguments
+    /// `args` Arguments to parse, not including the executable name.
+    pub fn parse_from_os<I, S>(&self, args: I) -> ArgMap
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.parser
+            .try_parse_from_os(args)
+            .unwrap_or_else(|error| self.parser.exit_with(error))
+    }
+
+    /// Parse a command line given as one string like [`ArgParser::parse_str`], halting the
+    /// execution on errors, e.g. for each line typed in a REPL.
+    /// # Arguments
+    /// `line` Arguments to parse, not including the executable name.
+    pub fn parse_str(&self, line: &str) -> ArgMap {
+        self.parser
+            .try_parse_str(line)
+            .unwrap_or_else(|error| self.parser.exit_with(error))
+    }
+
+    /// Parse the command line like [`ArgParser::parse_with_report`].
+    pub fn parse_with_report(&self) -> (ArgMap, ParseReport) {
+        self.parser.parse_and_report()
+    }
+}
+
+impl Deref for Cli {
+    type Target = ArgParser;
+
+    fn deref(&self) -> &ArgParser {
+        &self.parser
+    }
+}
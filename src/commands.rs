@@ -0,0 +1,309 @@
+//! Dispatch on a command given as first argument, like `cargo build` or `git commit`,
+//! see [`crate::commands!`].
+use std::{ffi::OsString, process::exit};
+
+use crate::{host::Stream, json, styled::Styled, suggest, ArgParser, ParseError};
+
+/// Declares an enum with one variant per command of the program, each holding the [`crate::ArgMap`]
+/// parsed by the parser of the command, for exhaustive `match` based dispatch.
+///
+/// Every variant names a function building the [`crate::ArgParser`] of the command.
+/// The command is the variant name in kebab case, e.g. `dry-run` for `DryRun`.
+/// The enum gets the following constructors:
+/// * `from_args()` parses the command line, printing the list of commands for `--help` or
+///   a missing or unknown command, and behaving like [`crate::ArgParser::parse`] afterwards.
+///   `--help <pattern>` lists the commands whose name, description, keywords or category
+///   contain the pattern, see [`crate::ArgParser::keywords`] and [`crate::ArgParser::category`].
+///   Former names of commands still run them with a warning, see
+///   [`crate::ArgParser::renamed_from`] and [`crate::ArgParser::deprecated_command`].
+/// * `try_from_args(args)` parses `args` without exiting, printing only deprecation warnings.
+/// * `commands()` lists the commands in declaration order.
+/// * `to_json()` describes every command like [`crate::ArgParser::to_json`], keyed by command.
+///
+/// # Example
+/// ```
+/// use clarg::{Arg, ArgParser};
+///
+/// fn build_parser() -> ArgParser {
+///     ArgParser::new("Compile the project.").arg(Arg::boolean("release", Some('r'), "Optimize"))
+/// }
+///
+/// fn test_parser() -> ArgParser {
+///     ArgParser::new("Run the tests.").arg(Arg::string("filter", None, false, "Tests to run"))
+/// }
+///
+/// clarg::commands! {
+///     /// Commands of the tool.
+///     pub enum Command {
+///         Build(build_parser),
+///         Test(test_parser),
+///     }
+/// }
+///
+/// match Command::try_from_args(["build", "--release"]).unwrap() {
+///     Command::Build(args) => assert!(args.get::<bool>("release").unwrap()),
+///     Command::Test(_) => unreachable!(),
+/// }
+/// ```
+#[macro_export]
+macro_rules! commands {
+    (
+        $(#[$attribute:meta])*
+        $visibility:vis enum $name:ident {
+            $($variant:ident($parser:path)),+ $(,)?
+        }
+    ) => {
+        $(#[$attribute])*
+        $visibility enum $name {
+            $($variant($crate::ArgMap),)+
+        }
+
+        impl $name {
+            /// Names of the commands, in declaration order.
+            pub fn commands() -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![$($crate::__commands::command_name(stringify!($variant)),)+]
+            }
+
+            /// Definition of every command as JSON, see `ArgParser::to_json`.
+            pub fn to_json() -> ::std::string::String {
+                let parsers = ::std::vec![$(
+                    ($crate::__commands::command_name(stringify!($variant)), $parser()),
+                )+];
+                $crate::__commands::to_json(parsers)
+            }
+
+            /// Parses the command line, exiting on invalid input like `ArgParser::parse`.
+            pub fn from_args() -> Self {
+                let parsers = ::std::vec![$(
+                    ($crate::__commands::command_name(stringify!($variant)), $parser()),
+                )+];
+                let (command, args) = $crate::__commands::split(&parsers, ::std::env::args_os().skip(1))
+                    .unwrap_or_else(|error| $crate::__commands::exit_with(error, &parsers));
+                $(
+                    if command == $crate::__commands::command_name(stringify!($variant)) {
+                        let parser = $crate::__commands::subcommand($parser(), &command);
+                        return $name::$variant(parser.parse_from_os(args));
+                    }
+                )+
+                unreachable!("`split` only returns known commands")
+            }
+
+            /// Parses the given arguments, not including the executable name, without exiting.
+            pub fn try_from_args<I, S>(args: I) -> ::std::result::Result<Self, $crate::ParseError>
+            where
+                I: ::std::iter::IntoIterator<Item = S>,
+                S: ::std::convert::AsRef<::std::ffi::OsStr>,
+            {
+                let parsers = ::std::vec![$(
+                    ($crate::__commands::command_name(stringify!($variant)), $parser()),
+                )+];
+                let (command, args) = $crate::__commands::split(&parsers, args)?;
+                $(
+                    if command == $crate::__commands::command_name(stringify!($variant)) {
+                        let parser = $crate::__commands::subcommand($parser(), &command);
+                        return parser.try_parse_from_os(args).map($name::$variant);
+                    }
+                )+
+                unreachable!("`split` only returns known commands")
+            }
+        }
+    };
+}
+
+/// Command for a variant name, in kebab case.
+pub fn command_name(variant: &str) -> String {
+    let mut name = String::new();
+    for (i, c) in variant.chars().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            name.push('-');
+        }
+        name.extend(c.to_lowercase());
+    }
+    name
+}
+
+/// Splits `args` into the command they start with and the arguments of the command.
+/// Former names of renamed commands give the new name, and a warning is printed for them and
+/// for deprecated commands, see [`ArgParser::renamed_from`] and [`ArgParser::deprecated_command`].
+/// # Errors
+/// [`ParseError::HelpRequested`] for `--help` or `-h` in place of a command, followed by a
+/// search pattern for `--help <pattern>`, [`ParseError::MissingCommand`] or [`ParseError::UnknownCommand`].
+pub fn split<I, S>(
+    parsers: &[(String, ArgParser)],
+    args: I,
+) -> Result<(String, Vec<OsString>), ParseError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let mut args = args
+        .into_iter()
+        .map(|arg| arg.as_ref().to_owned())
+        .peekable();
+    let Some(command) = args.next() else {
+        return Err(ParseError::MissingCommand {
+            commands: parsers.iter().map(|(name, _)| name.clone()).collect(),
+        });
+    };
+    let command = command.to_string_lossy().into_owned();
+    if command == "--help" || command == "-h" {
+        let search = args
+            .next_if(|value| !value.to_string_lossy().starts_with('-'))
+            .map(|value| value.to_string_lossy().into_owned());
+        return Err(ParseError::HelpRequested { search });
+    }
+    // a command named like the former name of another one takes precedence.
+    let found = parsers
+        .iter()
+        .find(|(name, _)| *name == command)
+        .or_else(|| {
+            parsers
+                .iter()
+                .find(|(_, parser)| parser.renamed_from.contains(&command))
+        });
+    let Some((name, parser)) = found else {
+        return Err(ParseError::UnknownCommand {
+            suggestion: suggestion(parsers, &command),
+            command,
+        });
+    };
+    let note = match &parser.command_deprecated {
+        Some(note) => Some(note.clone()),
+        None if *name != command => Some(format!("use `{} {name}` instead", parser.executable)),
+        None => None,
+    };
+    if let Some(note) = note {
+        parser.warn(&format!(
+            "`{} {command}` is deprecated, {note}",
+            parser.executable
+        ));
+    }
+    Ok((name.clone(), args.collect()))
+}
+
+/// Command to propose for the unknown `command`: the one having it as keyword,
+/// or the one whose name or keyword is closest to it.
+fn suggestion(parsers: &[(String, ArgParser)], command: &str) -> Option<String> {
+    let lowercase = command.to_lowercase();
+    if let Some((name, _)) = parsers.iter().find(|(_, parser)| {
+        parser
+            .keywords
+            .iter()
+            .any(|keyword| keyword.to_lowercase() == lowercase)
+    }) {
+        return Some(name.clone());
+    }
+    let candidates = parsers.iter().flat_map(|(name, parser)| {
+        std::iter::once(name.as_str()).chain(parser.keywords.iter().map(String::as_str))
+    });
+    let closest = suggest::closest(command, candidates)?;
+    parsers
+        .iter()
+        .find(|(name, parser)| name == closest || parser.keywords.iter().any(|k| k == closest))
+        .map(|(name, _)| name.clone())
+}
+
+/// Definitions of the commands as a JSON object keyed by command, see [`ArgParser::to_json`].
+pub fn to_json(parsers: Vec<(String, ArgParser)>) -> String {
+    let commands: Vec<String> = parsers
+        .into_iter()
+        .map(|(name, parser)| {
+            let definition = subcommand(parser, &name).to_json();
+            // nested one level deeper.
+            let definition = definition.trim_end().replace('\n', "\n  ");
+            format!("  {}: {definition}", json::string(&name))
+        })
+        .collect();
+    format!("{{\n{}\n}}\n", commands.join(",\n"))
+}
+
+/// Names the program of the parser of `command` after both, e.g. `myapp build`.
+pub fn subcommand(mut parser: ArgParser, command: &str) -> ArgParser {
+    parser.executable = format!("{} {command}", parser.executable);
+    parser
+}
+
+/// Prints the list of commands, preceded by `error` unless help was requested, and halts the execution.
+pub fn exit_with(error: ParseError, parsers: &[(String, ArgParser)]) -> ! {
+    // the parsers share the program name and output settings, the first one stands for the program.
+    let Some((_, program)) = parsers.first() else {
+        exit(1)
+    };
+    let width = parsers
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let search = match &error {
+        ParseError::HelpRequested { search } => search.as_deref().map(str::to_lowercase),
+        _ => None,
+    };
+    let shown = |(name, parser): &&(String, ArgParser)| match &search {
+        Some(pattern) => std::iter::once(name)
+            .chain([&parser.description])
+            .chain(&parser.keywords)
+            .chain(&parser.category)
+            .any(|text| text.to_lowercase().contains(pattern)),
+        None => true,
+    };
+    // commands without a category first, then one section per category in order of first use.
+    let mut categories: Vec<Option<&str>> = vec![None];
+    for (_, parser) in parsers {
+        if !categories.contains(&parser.category.as_deref()) {
+            categories.push(parser.category.as_deref());
+        }
+    }
+    let mut listing = Styled::new();
+    listing
+        .heading("Usage:")
+        .plain(" ")
+        .literal(&program.executable)
+        .plain(" ")
+        .placeholder("<COMMAND>")
+        .plain(" [OPTIONS]\n");
+    for category in categories {
+        let commands: Vec<&(String, ArgParser)> = parsers
+            .iter()
+            .filter(|(_, parser)| parser.category.as_deref() == category)
+            .filter(shown)
+            .collect();
+        if commands.is_empty() {
+            continue;
+        }
+        let title = category.unwrap_or("commands");
+        listing
+            .plain("\n")
+            .heading(format!("{title}:\n{}", "-".repeat(title.chars().count())))
+            .plain("\n");
+        for (name, parser) in commands {
+            let mut command = Styled::new();
+            command.literal(name);
+            let tag = match parser.command_deprecated {
+                Some(_) => " [deprecated]",
+                None => "",
+            };
+            listing
+                .plain("    ")
+                .padded(command, width)
+                .plain(format!("    {}{tag}\n", parser.description));
+        }
+    }
+    if let Some(pattern) = &search {
+        if !parsers.iter().any(|command| shown(&command)) {
+            listing.plain(format!("\nNo commands match `{pattern}`.\n"));
+        }
+    }
+    if let ParseError::HelpRequested { .. } = error {
+        program.halt_after(program.emit(&listing, Stream::Stdout), 0);
+    }
+    if program.emit_json_error(&error) {
+        program.halt(program.error_exit_code.into());
+    }
+    let mut message = Styled::new();
+    message
+        .error(program.error_line(&error))
+        .plain("\n")
+        .append(listing);
+    let _ = program.emit(&message, Stream::Stderr);
+    program.halt(program.error_exit_code.into())
+}
@@ -0,0 +1,26 @@
+//! Arguments following conventions shared by many command line tools, ready to be added to a parser.
+//!
+//! ```
+//! use clarg::{common, ArgParser};
+//!
+//! let args = ArgParser::new("Delete old backups.")
+//!     .arg(common::dry_run())
+//!     .try_parse_from(["-n".to_owned()])
+//!     .unwrap();
+//! assert!(args.is_dry_run());
+//! assert_eq!(args.unless_dry_run(|| "deleted"), None);
+//! ```
+use crate::Arg;
+
+/// Name of the argument added by [`dry_run`].
+pub const DRY_RUN: &str = "dry-run";
+
+/// `--dry-run`/`-n` flag asking to show what would be done without changing anything,
+/// checked with [`crate::ArgMap::is_dry_run`] or [`crate::ArgMap::unless_dry_run`].
+pub fn dry_run() -> Arg {
+    Arg::boolean(
+        DRY_RUN,
+        Some('n'),
+        "Show what would be done without changing anything",
+    )
+}
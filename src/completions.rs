@@ -0,0 +1,1024 @@
+//! Shell completion script generation.
+//!
+//! Scripts are generated from the parser definition so they always match the real interface.
+//! They are usually exposed through a hidden flag and installed by the user:
+//! ```no_run
+//! # use clarg::{completions::Shell, Arg, ArgParser};
+//! let parser = ArgParser::new("Find duplicate files.")
+//!     .arg(Arg::boolean("verbose", Some('v'), "verbose execution"))
+//!     .arg(Arg::string("format", Some('f'), false, "Output format").choices(&["json", "text"]));
+//! parser.generate_completions(Shell::Bash, &mut std::io::stdout()).unwrap();
+//! ```
+//!
+//! Other shells are supported by registering a [`CompletionBackend`] with [`register`],
+//! then generating its script by name with [`ArgParser::generate_completions_for`].
+use std::{
+    fmt, io,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use crate::{Arg, ArgKind, ArgParser, GroupKind};
+
+/// Shells for which completion scripts can be generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    PowerShell,
+    Nushell,
+    Elvish,
+}
+
+impl fmt::Display for Shell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::PowerShell => "powershell",
+            Shell::Nushell => "nushell",
+            Shell::Elvish => "elvish",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "powershell" | "pwsh" => Ok(Shell::PowerShell),
+            "nushell" | "nu" => Ok(Shell::Nushell),
+            "elvish" => Ok(Shell::Elvish),
+            _ => Err(format!("Unsupported shell `{s}`.")),
+        }
+    }
+}
+
+/// Kind of the values of an argument, to complete them with what the shell knows of, see
+/// [`crate::Arg::value_hint`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueHint {
+    /// Paths of files or directories.
+    FilePath,
+    /// Paths of directories.
+    DirPath,
+    /// Names of hosts, e.g. from `/etc/hosts` and `~/.ssh/known_hosts`.
+    Hostname,
+    /// Names of the users of the system.
+    Username,
+    /// A command, e.g. for `--exec`, completed with the commands in `PATH`.
+    CommandWithArgs,
+}
+
+/// Generator of completion scripts for a shell not supported by clarg itself.
+/// # Example
+/// ```
+/// # use clarg::completions::{self, CompletionBackend, CompletionOption};
+/// # use clarg::{Arg, ArgParser};
+/// # use std::io;
+/// struct Words;
+///
+/// impl CompletionBackend for Words {
+///     fn name(&self) -> &str {
+///         "words"
+///     }
+///
+///     fn generate(&self, program: &str, options: &[CompletionOption], out: &mut dyn io::Write) -> io::Result<()> {
+///         let names: Vec<String> = options.iter().flat_map(|option| option.names()).collect();
+///         writeln!(out, "{program}: {}", names.join(" "))
+///     }
+/// }
+///
+/// completions::register(Words);
+/// let parser = ArgParser::new("Find duplicate files.")
+///     .arg(Arg::boolean("json", None, "Format output as JSON"))
+///     .executable_name("dupes");
+/// let mut script = Vec::new();
+/// parser.generate_completions_for("words", &mut script).unwrap();
+/// assert_eq!(String::from_utf8(script).unwrap(), "dupes: --json --help -h\n");
+/// ```
+pub trait CompletionBackend: Send + Sync {
+    /// Name the backend is selected with, e.g. `xonsh`. Compared ignoring case.
+    fn name(&self) -> &str;
+
+    /// Writes the completion script of `program` accepting `options`.
+    /// # Errors
+    /// If writing to `out` fails.
+    fn generate(
+        &self,
+        program: &str,
+        options: &[CompletionOption],
+        out: &mut dyn io::Write,
+    ) -> io::Result<()>;
+}
+
+/// Backends added with [`register`].
+static BACKENDS: Mutex<Vec<Arc<dyn CompletionBackend>>> = Mutex::new(Vec::new());
+
+/// Adds a backend, replacing any registered backend of the same name.
+/// Built-in shells can't be replaced.
+pub fn register(backend: impl CompletionBackend + 'static) {
+    let mut backends = BACKENDS.lock().unwrap_or_else(|e| e.into_inner());
+    backends.retain(|known| !known.name().eq_ignore_ascii_case(backend.name()));
+    backends.push(Arc::new(backend));
+}
+
+/// Names of the shells completion scripts can be generated for, built-in ones first.
+pub fn backends() -> Vec<String> {
+    let mut names: Vec<String> = [
+        Shell::Bash,
+        Shell::Zsh,
+        Shell::Fish,
+        Shell::PowerShell,
+        Shell::Nushell,
+        Shell::Elvish,
+    ]
+    .iter()
+    .map(Shell::to_string)
+    .collect();
+    let backends = BACKENDS.lock().unwrap_or_else(|e| e.into_inner());
+    names.extend(backends.iter().map(|backend| backend.name().to_owned()));
+    names
+}
+
+/// Option as seen by the generators, including the built-in ones like `--help`.
+pub struct CompletionOption<'a> {
+    long: String,
+    short: Option<char>,
+    /// visible short aliases, see [`crate::Arg::short_alias`].
+    short_aliases: &'a [char],
+    description: &'a str,
+    kind: ArgKind,
+    takes_value: bool,
+    multiple: bool,
+    choices: &'a [String],
+    hint: Option<ValueHint>,
+    command: Option<&'a str>,
+    value_name: &'a str,
+    /// spellings of the options that can't be used along with this one.
+    conflicts: Vec<String>,
+}
+
+impl CompletionOption<'_> {
+    /// Every spelling of the option, e.g. `["--format", "-f"]`, short aliases included.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = vec![format!("--{}", self.long)];
+        let shorts = self.short.iter().chain(self.short_aliases);
+        names.extend(shorts.map(|c| format!("-{c}")));
+        names
+    }
+
+    /// Long name, without the leading dashes.
+    pub fn long(&self) -> &str {
+        &self.long
+    }
+
+    pub fn short(&self) -> Option<char> {
+        self.short
+    }
+
+    /// Other short names, see [`crate::Arg::short_alias`].
+    pub fn short_aliases(&self) -> &[char] {
+        self.short_aliases
+    }
+
+    pub fn description(&self) -> &str {
+        self.description
+    }
+
+    /// Kind of the value following the option, [`ArgKind::Boolean`] or [`ArgKind::Count`] for flags.
+    pub fn kind(&self) -> ArgKind {
+        self.kind
+    }
+
+    /// Whether the option is followed by a value.
+    pub fn takes_value(&self) -> bool {
+        self.takes_value
+    }
+
+    /// Whether the option can be repeated.
+    pub fn multiple(&self) -> bool {
+        self.multiple
+    }
+
+    /// Values accepted, empty when any value is.
+    pub fn choices(&self) -> &[String] {
+        self.choices
+    }
+
+    /// Whether only directories are valid values, see [`crate::Arg::must_be_dir`].
+    pub fn directories(&self) -> bool {
+        self.hint == Some(ValueHint::DirPath)
+    }
+
+    /// What the values are, see [`crate::Arg::value_hint`].
+    pub fn value_hint(&self) -> Option<ValueHint> {
+        self.hint
+    }
+
+    /// Shell command printing the candidate values one per line, see
+    /// [`crate::Arg::completion_command`].
+    pub fn command(&self) -> Option<&str> {
+        self.command
+    }
+
+    /// Name of the value, set with [`crate::Arg::value_name`], or the long name.
+    pub fn value_name(&self) -> &str {
+        self.value_name
+    }
+
+    /// Spellings of the options that can't be used along with this one, e.g. `["--csv", "-c"]`,
+    /// following [`crate::Arg::conflicts_with`] and exclusive groups. The option is no longer
+    /// offered once one of them is typed.
+    pub fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+}
+
+/// Writes the completion script for the shell named `shell`, built-in or registered.
+pub(crate) fn generate_named(
+    parser: &ArgParser,
+    shell: &str,
+    out: &mut dyn io::Write,
+) -> io::Result<()> {
+    if let Ok(shell) = Shell::from_str(shell) {
+        return generate(parser, shell, out);
+    }
+    let backend = BACKENDS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|backend| backend.name().eq_ignore_ascii_case(shell))
+        .cloned();
+    match backend {
+        Some(backend) => backend.generate(&parser.executable, &options(parser), out),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unsupported shell `{shell}`."),
+        )),
+    }
+}
+
+/// Writes the completion script for `shell` describing `parser`.
+pub(crate) fn generate(
+    parser: &ArgParser,
+    shell: Shell,
+    out: &mut dyn io::Write,
+) -> io::Result<()> {
+    let options = options(parser);
+    let name = &parser.executable;
+    let function = format!(
+        "_{}",
+        name.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>()
+    );
+    match shell {
+        Shell::Bash => bash(name, &function, &options, out),
+        Shell::Zsh => zsh(name, &function, &options, out),
+        Shell::Fish => fish(name, &options, out),
+        Shell::PowerShell => powershell(name, &options, out),
+        Shell::Nushell => nushell(name, &options, parser, out),
+        Shell::Elvish => elvish(name, &options, out),
+    }
+}
+
+/// Options of `parser` offered for completion.
+fn options(parser: &ArgParser) -> Vec<CompletionOption<'_>> {
+    let mut options: Vec<CompletionOption> = Vec::new();
+    for arg in parser
+        .args
+        .iter()
+        .filter(|arg| !arg.positional && !arg.hidden)
+    {
+        options.push(CompletionOption {
+            long: arg.long_name.clone(),
+            short: arg.short_name,
+            short_aliases: &arg.short_aliases,
+            description: &arg.description,
+            kind: arg.kind,
+            takes_value: !matches!(arg.kind, ArgKind::Boolean | ArgKind::Count),
+            multiple: arg.multiple,
+            choices: &arg.choices,
+            hint: hint(arg),
+            command: arg.completion_command.as_deref(),
+            value_name: arg.value_name.as_deref().unwrap_or(&arg.long_name),
+            conflicts: conflicts(parser, arg),
+        });
+        for alias in &arg.aliases {
+            options.push(CompletionOption {
+                long: alias.clone(),
+                short: None,
+                short_aliases: &[],
+                description: &arg.description,
+                kind: arg.kind,
+                takes_value: !matches!(arg.kind, ArgKind::Boolean | ArgKind::Count),
+                multiple: arg.multiple,
+                choices: &arg.choices,
+                hint: hint(arg),
+                command: arg.completion_command.as_deref(),
+                value_name: arg.value_name.as_deref().unwrap_or(&arg.long_name),
+                conflicts: conflicts(parser, arg),
+            });
+        }
+        if arg.negatable {
+            options.push(CompletionOption {
+                long: format!("no-{}", arg.long_name),
+                short: None,
+                short_aliases: &[],
+                description: &arg.description,
+                kind: ArgKind::Boolean,
+                takes_value: false,
+                multiple: false,
+                choices: &[],
+                hint: None,
+                command: None,
+                value_name: &arg.long_name,
+                conflicts: Vec::new(),
+            });
+        }
+    }
+    if parser.config_file.is_some() {
+        options.push(CompletionOption {
+            long: "profile".to_owned(),
+            short: None,
+            short_aliases: &[],
+            description: "Select a configuration profile",
+            kind: ArgKind::String,
+            takes_value: true,
+            multiple: false,
+            choices: &[],
+            hint: None,
+            command: None,
+            value_name: "profile",
+            conflicts: Vec::new(),
+        });
+    }
+    if !parser.presets.is_empty() {
+        options.push(CompletionOption {
+            long: "preset".to_owned(),
+            short: None,
+            short_aliases: &[],
+            description: "Select a preset of values",
+            kind: ArgKind::String,
+            takes_value: true,
+            multiple: false,
+            choices: &parser.presets,
+            hint: None,
+            command: None,
+            value_name: "preset",
+            conflicts: Vec::new(),
+        });
+    }
+    if parser.last_run.is_some() {
+        options.push(CompletionOption {
+            long: "again".to_owned(),
+            short: None,
+            short_aliases: &[],
+            description: "Repeat the options of the last run",
+            kind: ArgKind::Boolean,
+            takes_value: false,
+            multiple: false,
+            choices: &[],
+            hint: None,
+            command: None,
+            value_name: "again",
+            conflicts: Vec::new(),
+        });
+    }
+    if parser.version_long() {
+        options.push(CompletionOption {
+            long: "version".to_owned(),
+            short: parser.version_short(),
+            short_aliases: &[],
+            description: "Print version information",
+            kind: ArgKind::Boolean,
+            takes_value: false,
+            multiple: false,
+            choices: &[],
+            hint: None,
+            command: None,
+            value_name: "version",
+            conflicts: Vec::new(),
+        });
+    }
+    if parser.help_enabled {
+        options.push(CompletionOption {
+            long: "help".to_owned(),
+            short: Some('h'),
+            short_aliases: &parser.help_short_aliases,
+            description: "Print this help message",
+            kind: ArgKind::Boolean,
+            takes_value: false,
+            multiple: false,
+            choices: &[],
+            hint: None,
+            command: None,
+            value_name: "help",
+            conflicts: Vec::new(),
+        });
+    }
+    options
+}
+
+/// Hint of `arg`, directories for paths that must be one.
+fn hint(arg: &Arg) -> Option<ValueHint> {
+    let directories = arg.kind == ArgKind::Path && arg.must_be_dir;
+    arg.value_hint.or(directories.then_some(ValueHint::DirPath))
+}
+
+/// Spellings of the options conflicting with `arg`, in either direction or through an exclusive
+/// group, e.g. `["--csv", "-c"]`.
+fn conflicts(parser: &ArgParser, arg: &Arg) -> Vec<String> {
+    let exclusive = |other: &Arg| {
+        parser.groups.iter().any(|group| {
+            matches!(group.kind(), GroupKind::Exclusive)
+                && group.contains(&arg.long_name)
+                && group.contains(&other.long_name)
+        })
+    };
+    let mut names = Vec::new();
+    for other in parser.args.iter().filter(|other| {
+        !other.positional
+            && other.long_name != arg.long_name
+            && (arg.conflicts_with.contains(&other.long_name)
+                || other.conflicts_with.contains(&arg.long_name)
+                || exclusive(other))
+    }) {
+        names.push(format!("--{}", other.long_name));
+        names.extend(other.short_name.map(|c| format!("-{c}")));
+        names.extend(other.short_aliases.iter().map(|c| format!("-{c}")));
+        names.extend(other.aliases.iter().map(|alias| format!("--{alias}")));
+    }
+    names
+}
+
+fn bash(
+    name: &str,
+    function: &str,
+    options: &[CompletionOption],
+    out: &mut dyn io::Write,
+) -> io::Result<()> {
+    writeln!(out, "{function}() {{")?;
+    writeln!(out, "    local cur prev")?;
+    writeln!(out, "    cur=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+    writeln!(out, "    prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+    writeln!(out, "    case \"$prev\" in")?;
+    for option in options.iter().filter(|option| option.takes_value) {
+        let names: Vec<String> = option
+            .names()
+            .iter()
+            .map(|name| pattern_safe(name))
+            .collect();
+        writeln!(out, "        {})", names.join("|"))?;
+        if let (Some(command), true) = (option.command, option.choices.is_empty()) {
+            writeln!(
+                out,
+                "            COMPREPLY=($(compgen -W \"$({})\" -- \"$cur\"))",
+                silenced(command)
+            )?;
+        } else if let Some(hint) = option.hint {
+            let action = match hint {
+                ValueHint::FilePath => "-f",
+                ValueHint::DirPath => "-d",
+                ValueHint::Hostname => "-A hostname",
+                ValueHint::Username => "-u",
+                ValueHint::CommandWithArgs => "-c",
+            };
+            writeln!(
+                out,
+                "            COMPREPLY=($(compgen {action} -- \"$cur\"))"
+            )?;
+        } else if option.choices.is_empty() {
+            writeln!(out, "            COMPREPLY=($(compgen -f -- \"$cur\"))")?;
+        } else {
+            // `compgen -W` would expand the choices, they are matched as they are instead.
+            let choices: Vec<String> = option
+                .choices
+                .iter()
+                .map(|choice| posix_quote(choice))
+                .collect();
+            writeln!(out, "            local choices=({})", choices.join(" "))?;
+            writeln!(out, "            COMPREPLY=()")?;
+            writeln!(out, "            for choice in \"${{choices[@]}}\"; do [[ $choice == \"$cur\"* ]] && COMPREPLY+=(\"$choice\"); done")?;
+        }
+        writeln!(out, "            return 0")?;
+        writeln!(out, "            ;;")?;
+    }
+    writeln!(out, "    esac")?;
+    let words: Vec<String> = options
+        .iter()
+        .filter(|option| option.conflicts.is_empty())
+        .flat_map(|option| option.names())
+        .collect();
+    if options.iter().all(|option| option.conflicts.is_empty()) {
+        writeln!(
+            out,
+            "    COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+            words.join(" ")
+        )?;
+        writeln!(out, "}}")?;
+        return writeln!(out, "complete -F {function} {name}");
+    }
+    // options conflicting with one already typed are left out.
+    writeln!(out, "    local words=\"{}\"", words.join(" "))?;
+    for option in options.iter().filter(|option| !option.conflicts.is_empty()) {
+        let typed: Vec<String> = option
+            .conflicts
+            .iter()
+            .map(|name| format!("*\" {name} \"*"))
+            .collect();
+        writeln!(out, "    case \" ${{COMP_WORDS[*]:1:COMP_CWORD-1}} \" in")?;
+        writeln!(out, "        {}) ;;", typed.join("|"))?;
+        writeln!(
+            out,
+            "        *) words+=\" {}\" ;;",
+            option.names().join(" ")
+        )?;
+        writeln!(out, "    esac")?;
+    }
+    writeln!(out, "    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))")?;
+    writeln!(out, "}}")?;
+    writeln!(out, "complete -F {function} {name}")
+}
+
+fn zsh(
+    name: &str,
+    function: &str,
+    options: &[CompletionOption],
+    out: &mut dyn io::Write,
+) -> io::Result<()> {
+    writeln!(out, "#compdef {name}")?;
+    writeln!(out)?;
+    writeln!(out, "{function}() {{")?;
+    write!(out, "    _arguments -s")?;
+    for option in options {
+        // both spellings exclude each other unless the option can be repeated, and exclude
+        // the options conflicting with it.
+        let names = option.names();
+        let mut excluded = match names.len() {
+            1 => Vec::new(),
+            _ if option.multiple => Vec::new(),
+            _ => names.clone(),
+        };
+        excluded.extend(option.conflicts.iter().cloned());
+        let mut prefix = match excluded.is_empty() {
+            true => String::new(),
+            false => format!("({})", excluded.join(" ")),
+        };
+        if option.multiple {
+            prefix.push('*');
+        }
+        // short names first, as in `{-q,--quiet}`.
+        let names: Vec<String> = names[1..]
+            .iter()
+            .chain(&names[..1])
+            .map(|name| pattern_safe(name))
+            .collect();
+        let spec = match names.as_slice() {
+            [long] => long.clone(),
+            names => format!("{{{}}}", names.join(",")),
+        };
+        let value = if !option.takes_value {
+            String::new()
+        } else if let (Some(command), true) = (option.command, option.choices.is_empty()) {
+            format!(
+                ":{}:{{compadd -- ${{(f)\"$({})\"}}}}",
+                zsh_escape(option.value_name),
+                silenced(command).replace('\'', "'\\''")
+            )
+        } else if let Some(hint) = option.hint {
+            let action = match hint {
+                ValueHint::FilePath => "_files",
+                ValueHint::DirPath => "_directories",
+                ValueHint::Hostname => "_hosts",
+                ValueHint::Username => "_users",
+                ValueHint::CommandWithArgs => "_command_names -e",
+            };
+            format!(":{}:{action}", zsh_escape(option.value_name))
+        } else if option.choices.is_empty() {
+            format!(":{}:_files", zsh_escape(option.value_name))
+        } else {
+            let choices: Vec<String> = option
+                .choices
+                .iter()
+                .map(|choice| zsh_choice(choice))
+                .collect();
+            format!(":{}:({})", zsh_escape(option.value_name), choices.join(" "))
+        };
+        write!(
+            out,
+            " \\\n        {}{}'[{}]{}'",
+            shell_quote(&prefix),
+            spec,
+            zsh_escape(option.description),
+            value
+        )?;
+    }
+    writeln!(out)?;
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "if [ \"$funcstack[1]\" = \"{function}\" ]; then")?;
+    writeln!(out, "    {function} \"$@\"")?;
+    writeln!(out, "else")?;
+    writeln!(out, "    compdef {function} {name}")?;
+    writeln!(out, "fi")
+}
+
+fn fish(name: &str, options: &[CompletionOption], out: &mut dyn io::Write) -> io::Result<()> {
+    for option in options {
+        write!(out, "complete -c {name} -l {}", option.long)?;
+        for c in option.short.iter().chain(option.short_aliases) {
+            write!(out, " -s {}", pattern_safe(&c.to_string()))?;
+        }
+        write!(out, " -d '{}'", fish_escape(option.description))?;
+        if !option.conflicts.is_empty() {
+            let typed: Vec<String> = option
+                .conflicts
+                .iter()
+                .map(|name| match name.strip_prefix("--") {
+                    Some(long) => long.to_owned(),
+                    None => format!("-s {}", &name[1..]),
+                })
+                .collect();
+            write!(out, " -n 'not __fish_contains_opt {}'", typed.join(" "))?;
+        }
+        if let (Some(command), true) = (option.command, option.choices.is_empty()) {
+            // run by `sh`, the command is written for POSIX shells.
+            let substitution = format!("(sh -c {})", posix_quote(&silenced(command)));
+            write!(out, " -x -a '{}'", fish_escape(&substitution))?;
+        } else if let Some(hint) = option.hint {
+            let action = match hint {
+                ValueHint::FilePath => " -r -F",
+                ValueHint::DirPath => " -x -a '(__fish_complete_directories)'",
+                ValueHint::Hostname => " -x -a '(__fish_print_hostnames)'",
+                ValueHint::Username => " -x -a '(__fish_complete_users)'",
+                ValueHint::CommandWithArgs => " -x -a '(__fish_complete_command)'",
+            };
+            write!(out, "{action}")?;
+        } else if option.takes_value && option.choices.is_empty() {
+            write!(out, " -r")?;
+        } else if option.takes_value {
+            let choices: Vec<String> = option
+                .choices
+                .iter()
+                .map(|choice| format!("'{}'", fish_escape(choice)))
+                .collect();
+            write!(out, " -x -a '{}'", fish_escape(&choices.join(" ")))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn powershell(name: &str, options: &[CompletionOption], out: &mut dyn io::Write) -> io::Result<()> {
+    writeln!(out, "using namespace System.Management.Automation")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "Register-ArgumentCompleter -Native -CommandName '{}' -ScriptBlock {{",
+        powershell_escape(name)
+    )?;
+    writeln!(
+        out,
+        "    param($wordToComplete, $commandAst, $cursorPosition)"
+    )?;
+    writeln!(out, "    $words = @($commandAst.CommandElements | Where-Object {{ $_.Extent.EndOffset -lt $cursorPosition }} | ForEach-Object {{ $_.ToString() }})")?;
+    writeln!(
+        out,
+        "    $previous = if ($words.Count -gt 0) {{ $words[-1] }} else {{ '' }}"
+    )?;
+    writeln!(out, "    switch ($previous) {{")?;
+    for option in options.iter().filter(|option| option.takes_value) {
+        let names: Vec<String> = option
+            .names()
+            .iter()
+            .map(|name| format!("'{name}'"))
+            .collect();
+        writeln!(out, "        {{ $_ -cin {} }} {{", names.join(", "))?;
+        if let (Some(command), true) = (option.command, option.choices.is_empty()) {
+            writeln!(out, "            @(Invoke-Expression '{}' 2>$null) | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{ [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_) }}", powershell_escape(command))?;
+        } else if option.hint == Some(ValueHint::CommandWithArgs) {
+            writeln!(out, "            Get-Command \"$wordToComplete*\" | ForEach-Object {{ [CompletionResult]::new($_.Name, $_.Name, [CompletionResultType]::Command, $_.Name) }}")?;
+        } else if !option.choices.is_empty() {
+            let choices: Vec<String> = option
+                .choices
+                .iter()
+                .map(|choice| format!("'{}'", powershell_escape(choice)))
+                .collect();
+            writeln!(out, "            {} | Where-Object {{ $_ -like \"$wordToComplete*\" }} | ForEach-Object {{ [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_) }}", choices.join(", "))?;
+        }
+        // returning nothing falls back to path completion.
+        writeln!(out, "            return")?;
+        writeln!(out, "        }}")?;
+    }
+    writeln!(out, "    }}")?;
+    writeln!(out, "    @(")?;
+    for option in options {
+        // options conflicting with one already typed are left out.
+        let indent = match option.conflicts.is_empty() {
+            true => "        ",
+            false => {
+                let typed: Vec<String> = option
+                    .conflicts
+                    .iter()
+                    .map(|name| format!("'{name}'"))
+                    .collect();
+                writeln!(
+                    out,
+                    "        if (-not ($words | Where-Object {{ $_ -cin {} }})) {{",
+                    typed.join(", ")
+                )?;
+                "            "
+            }
+        };
+        for spelling in option.names() {
+            writeln!(
+                out,
+                "{indent}[CompletionResult]::new('{spelling}', '{spelling}', [CompletionResultType]::ParameterName, '{}')",
+                powershell_escape(if option.description.is_empty() {
+                    &option.long
+                } else {
+                    option.description
+                })
+            )?;
+        }
+        if !option.conflicts.is_empty() {
+            writeln!(out, "        }}")?;
+        }
+    }
+    writeln!(
+        out,
+        "    ) | Where-Object {{ $_.CompletionText -like \"$wordToComplete*\" }}"
+    )?;
+    writeln!(out, "}}")
+}
+
+/// Declares the program as a Nushell `extern`, so values are checked against their type
+/// and choices are offered by a `nu-complete` command per option.
+fn nushell(
+    name: &str,
+    options: &[CompletionOption],
+    parser: &ArgParser,
+    out: &mut dyn io::Write,
+) -> io::Result<()> {
+    let completer = |long: &str| format!("nu-complete {name} {long}");
+    let completed =
+        |option: &&CompletionOption| !option.choices.is_empty() || option.command.is_some();
+    for option in options.iter().filter(completed) {
+        writeln!(
+            out,
+            "def {} [] {{",
+            nushell_string(&completer(&option.long))
+        )?;
+        match option.command.filter(|_| option.choices.is_empty()) {
+            Some(command) => writeln!(
+                out,
+                "    ^sh -c {} | complete | get stdout | lines",
+                nushell_string(command)
+            )?,
+            None => {
+                let choices: Vec<String> = option
+                    .choices
+                    .iter()
+                    .map(|choice| nushell_string(choice))
+                    .collect();
+                writeln!(out, "    [{}]", choices.join(" "))?;
+            }
+        }
+        writeln!(out, "}}")?;
+        writeln!(out)?;
+    }
+    writeln!(out, "export extern {} [", nushell_string(name))?;
+    for option in options {
+        let mut spec = format!("--{}", option.long);
+        if let Some(c) = option.short {
+            spec.push_str(&format!("(-{c})"));
+        }
+        if option.takes_value {
+            let kind = match option.hint {
+                Some(ValueHint::DirPath) => "directory",
+                Some(ValueHint::FilePath) => "path",
+                _ => nushell_type(option.kind),
+            };
+            spec.push_str(&format!(": {kind}"));
+            if completed(&option) {
+                spec.push_str(&format!("@{}", nushell_string(&completer(&option.long))));
+            }
+        }
+        write_nushell_parameter(out, &spec, option.description)?;
+    }
+    for arg in parser
+        .args
+        .iter()
+        .filter(|arg| arg.positional && !arg.hidden)
+    {
+        let spec = match (arg.multiple, arg.required) {
+            (true, _) => format!("...{}", arg.long_name),
+            (false, true) => arg.long_name.clone(),
+            (false, false) => format!("{}?", arg.long_name),
+        };
+        let kind = match hint(arg) {
+            Some(ValueHint::DirPath) => "directory",
+            Some(ValueHint::FilePath) => "path",
+            _ => nushell_type(arg.kind),
+        };
+        write_nushell_parameter(out, &format!("{spec}: {kind}"), &arg.description)?;
+    }
+    writeln!(out, "]")
+}
+
+/// Writes a parameter of a Nushell `extern`, followed by its description as a comment.
+fn write_nushell_parameter(
+    out: &mut dyn io::Write,
+    spec: &str,
+    description: &str,
+) -> io::Result<()> {
+    if description.is_empty() {
+        writeln!(out, "    {spec}")
+    } else {
+        writeln!(out, "    {spec:<31} # {}", description.replace('\n', " "))
+    }
+}
+
+/// Nushell type of the values of `kind`.
+fn nushell_type(kind: ArgKind) -> &'static str {
+    match kind {
+        ArgKind::Integer | ArgKind::Unsigned | ArgKind::Count => "int",
+        ArgKind::Float => "number",
+        ArgKind::Boolean => "bool",
+        ArgKind::Path => "path",
+        // Nushell would convert its own durations and file sizes to a format of its own.
+        ArgKind::String
+        | ArgKind::DateTime
+        | ArgKind::Duration
+        | ArgKind::Size
+        | ArgKind::IpAddr
+        | ArgKind::SocketAddr
+        | ArgKind::Regex
+        | ArgKind::Url
+        | ArgKind::SemVer
+        | ArgKind::Uuid => "string",
+    }
+}
+
+/// Quotes text as a Nushell string.
+fn nushell_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn elvish(name: &str, options: &[CompletionOption], out: &mut dyn io::Write) -> io::Result<()> {
+    writeln!(out, "use path")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "set edit:completion:arg-completer[{}] = {{|@words|",
+        elvish_string(name)
+    )?;
+    writeln!(out, "    var current = $words[-1]")?;
+    writeln!(out, "    var previous = $words[-2]")?;
+    for option in options.iter().filter(|option| option.takes_value) {
+        let names: Vec<String> = option
+            .names()
+            .iter()
+            .map(|name| elvish_string(name))
+            .collect();
+        // numbers, dates, hosts, users and commands get no candidates rather than file names.
+        writeln!(out, "    if (has-value [{}] $previous) {{", names.join(" "))?;
+        if let (Some(command), true) = (option.command, option.choices.is_empty()) {
+            writeln!(
+                out,
+                "        try {{ sh -c {} | from-lines }} catch {{ }}",
+                elvish_string(&silenced(command))
+            )?;
+        } else if option.hint == Some(ValueHint::DirPath) {
+            writeln!(out, "        edit:complete-filename $current | each {{|candidate| if (path:is-dir &follow-symlink $candidate[stem]) {{ put $candidate }} }}")?;
+        } else if option.hint == Some(ValueHint::FilePath)
+            || (option.choices.is_empty()
+                && option.hint.is_none()
+                && matches!(option.kind, ArgKind::String | ArgKind::Path))
+        {
+            writeln!(out, "        edit:complete-filename $current")?;
+        } else if !option.choices.is_empty() {
+            let choices: Vec<String> = option
+                .choices
+                .iter()
+                .map(|choice| elvish_string(choice))
+                .collect();
+            writeln!(out, "        put {}", choices.join(" "))?;
+        }
+        writeln!(out, "        return")?;
+        writeln!(out, "    }}")?;
+    }
+    for option in options {
+        // options conflicting with one already typed are left out.
+        let indent = match option.conflicts.is_empty() {
+            true => "    ",
+            false => {
+                let typed: Vec<String> = option
+                    .conflicts
+                    .iter()
+                    .map(|name| elvish_string(name))
+                    .collect();
+                writeln!(
+                    out,
+                    "    if (not (has-value [(all $words[1..-1] | each {{|word| has-value [{}] $word }})] $true)) {{",
+                    typed.join(" ")
+                )?;
+                "        "
+            }
+        };
+        for spelling in option.names() {
+            let display = if option.description.is_empty() {
+                spelling.clone()
+            } else {
+                format!("{spelling} ({})", option.description)
+            };
+            writeln!(
+                out,
+                "{indent}edit:complex-candidate {} &display={}",
+                elvish_string(&spelling),
+                elvish_string(&display)
+            )?;
+        }
+        if !option.conflicts.is_empty() {
+            writeln!(out, "    }}")?;
+        }
+    }
+    writeln!(out, "}}")
+}
+
+/// Quotes text as a single quoted Elvish string.
+fn elvish_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// `command` with its errors discarded, so a failing command only yields no candidates.
+fn silenced(command: &str) -> String {
+    format!("{{ {command}; }} 2>/dev/null")
+}
+
+/// Quotes text as a single quoted POSIX shell word.
+fn posix_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Quotes the name of an option holding characters other than letters, digits, `-` and `_`,
+/// e.g. `-?`, so the shell doesn't take it for a pattern.
+fn pattern_safe(name: &str) -> String {
+    match name
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        true => name.to_owned(),
+        false => format!("'{}'", name.replace('\'', "'\\''")),
+    }
+}
+
+/// Quotes a (possibly empty) prefix for inclusion in a zsh word.
+fn shell_quote(text: &str) -> String {
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!("'{text}'")
+    }
+}
+
+/// Escapes a description placed inside a single quoted zsh `[...]` block.
+fn zsh_escape(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+/// Escapes a choice listed in the `(a b c)` action of zsh, which evaluates the list: every
+/// character but letters, digits and `-_.,/+=@%` is escaped with a backslash, then quoted for
+/// the single quoted word holding the action.
+fn zsh_choice(choice: &str) -> String {
+    let mut escaped = String::new();
+    for c in choice.chars() {
+        if !(c.is_alphanumeric() || "-_.,/+=@%".contains(c)) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped.replace('\'', "'\\''")
+}
+
+/// Escapes text placed inside single quotes in fish.
+fn fish_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// Escapes text placed inside single quotes in PowerShell.
+fn powershell_escape(text: &str) -> String {
+    text.replace('\'', "''")
+}
@@ -0,0 +1,485 @@
+//! Configuration file support.
+//!
+//! Configuration files use a small subset of TOML: `key = value` pairs whose keys match the
+//! long names of the parser arguments, `#` comments, and `[profiles.<name>]` sections.
+//! Arguments accepting multiple values can be given an array: `include = ["a", "b"]`.
+//! Values don't need quotes and lines starting with `;` are comments too, so simple INI style
+//! files like `~/.myapprc` holding `threads=4` lines are read as well.
+//!
+//! ```toml
+//! include = "base.toml"   # values from base.toml are loaded first
+//! path = "/var/data"
+//! threads = 4
+//!
+//! [profiles.production]
+//! threads = 16
+//! ```
+//!
+//! Top level values act as defaults, the profile selected with `--profile <name>` overlays them
+//! and values passed on the command line override both. `include` is only read at the top level.
+//!
+//! A starting configuration matching the parser definition can be generated with [`write_template`].
+//!
+//! Other backends, like the Windows registry, macOS defaults or a key-value store, take part in
+//! the resolution by implementing [`ConfigSource`], see [`crate::ArgParser::config_source`].
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{host::Host, ArgKind, ArgParser};
+
+/// Key used to chain configuration files.
+const INCLUDE_KEY: &str = "include";
+
+/// Prefix of the section headers defining profiles.
+const PROFILE_SECTION: &str = "profiles.";
+
+/// Maximum depth of `include` chains, protects against runaway recursion.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Backend providing configuration values keyed by the long names of the arguments.
+/// Sources are consulted after the command line, the environment and the configuration file,
+/// in the order they were added with [`crate::ArgParser::config_source`].
+///
+/// ```
+/// use std::collections::HashMap;
+/// use clarg::{config::ConfigSource, Arg, ArgParser};
+///
+/// struct Defaults(HashMap<&'static str, &'static str>);
+///
+/// impl ConfigSource for Defaults {
+///     fn name(&self) -> String {
+///         "built-in defaults".to_owned()
+///     }
+///
+///     fn get(&self, key: &str) -> Option<String> {
+///         self.0.get(key).map(|value| value.to_string())
+///     }
+/// }
+///
+/// let parser = ArgParser::new("Serve files.")
+///     .arg(Arg::integer("port", None, false, "Port to listen on"))
+///     .config_source(Defaults(HashMap::from([("port", "8080")])));
+/// let args = parser.try_parse_from::<_, &str>([]).unwrap();
+/// assert_eq!(args.get::<i64>("port"), Ok(8080));
+/// ```
+pub trait ConfigSource: Send + Sync {
+    /// Name of the source shown in error messages, e.g. a path or a registry key.
+    fn name(&self) -> String;
+
+    /// Value for the argument named `key`, `None` when the source doesn't set it.
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Values for the argument named `key`, several for arguments accepting multiple values.
+    /// The single value of [`ConfigSource::get`] by default.
+    fn get_all(&self, key: &str) -> Option<Vec<String>> {
+        self.get(key).map(|value| vec![value])
+    }
+
+    /// Every key set by the source, so keys matching no argument are reported.
+    /// Sources that cannot list their keys return none, the default.
+    fn keys(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Configuration file in the format described in the [module documentation](self), read by
+/// [`crate::ArgParser::config_file`].
+#[derive(Debug)]
+pub struct FileSource {
+    path: PathBuf,
+    values: HashMap<String, Vec<String>>,
+}
+
+impl FileSource {
+    /// Loads the configuration file at `path`, following its `include` chain.
+    /// # Arguments
+    /// `path` Configuration file to read.
+    /// `profile` Profile to overlay on the top level values, if any.
+    /// # Errors
+    /// If any of the files cannot be read, contains invalid syntax or lacks the profile.
+    /// `include` is only followed at the top level, inside a profile section it is an error:
+    /// ```
+    /// use clarg::config::FileSource;
+    ///
+    /// let path = std::env::temp_dir().join("clarg_profile_include.toml");
+    /// std::fs::write(&path, "threads = 4\n[profiles.fast]\ninclude = \"fast.toml\"\n").unwrap();
+    /// let error = FileSource::load(&path, None).unwrap_err();
+    /// assert!(error.ends_with(":3: `include` is only allowed at the top level."));
+    /// ```
+    pub fn load(path: impl AsRef<Path>, profile: Option<&str>) -> Result<FileSource, String> {
+        let path = path.as_ref();
+        let values = Config::load(path).and_then(|config| config.resolve(profile))?;
+        Ok(FileSource {
+            path: path.to_path_buf(),
+            values,
+        })
+    }
+}
+
+impl ConfigSource for FileSource {
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.get(key)?.first().cloned()
+    }
+
+    fn get_all(&self, key: &str) -> Option<Vec<String>> {
+        self.values.get(key).cloned()
+    }
+
+    fn keys(&self) -> Vec<String> {
+        self.values.keys().cloned().collect()
+    }
+}
+
+/// Values loaded from a configuration file (and the files it includes).
+#[derive(Debug, Default)]
+pub(crate) struct Config {
+    values: HashMap<String, Vec<String>>,
+    profiles: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+impl Config {
+    /// Loads a configuration file, following its `include` chain.
+    /// # Arguments
+    /// `path` Configuration file to read.
+    /// # Errors
+    /// If any of the files cannot be read or contains invalid syntax.
+    pub(crate) fn load(path: &Path) -> Result<Config, String> {
+        let mut config = Config::default();
+        let mut chain = Vec::new();
+        config.load_file(path, &mut chain)?;
+        Ok(config)
+    }
+
+    /// Values resulting of overlaying a profile on top of the top level values.
+    /// # Arguments
+    /// `profile` Name of the profile selected, if any.
+    /// # Errors
+    /// If the profile requested is not defined in the configuration.
+    pub(crate) fn resolve(
+        &self,
+        profile: Option<&str>,
+    ) -> Result<HashMap<String, Vec<String>>, String> {
+        let mut values = self.values.clone();
+        if let Some(name) = profile {
+            match self.profiles.get(name) {
+                Some(overlay) => values.extend(overlay.clone()),
+                None => {
+                    let mut known: Vec<&String> = self.profiles.keys().collect();
+                    known.sort();
+                    let known: Vec<&str> = known.iter().map(|k| k.as_str()).collect();
+                    return Err(format!(
+                        "Unknown configuration profile `{name}`. Available profiles: [{}]",
+                        known.join(", ")
+                    ));
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    fn load_file(&mut self, path: &Path, chain: &mut Vec<PathBuf>) -> Result<(), String> {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if chain.contains(&canonical) {
+            return Err(format!(
+                "Configuration file `{}` includes itself.",
+                path.display()
+            ));
+        }
+        if chain.len() >= MAX_INCLUDE_DEPTH {
+            return Err(format!(
+                "Configuration includes nested too deeply at `{}`.",
+                path.display()
+            ));
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read configuration file `{}`: {e}", path.display()))?;
+        chain.push(canonical);
+
+        // current section, `None` for the top level.
+        let mut profile: Option<String> = None;
+        let mut own = Config::default();
+        let mut includes = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            let location = || format!("{}:{}", path.display(), number + 1);
+            let line = strip_comment(line).trim();
+            if line.is_empty() || line.starts_with(';') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header
+                    .strip_suffix(']')
+                    .ok_or_else(|| format!("{}: Unterminated section header.", location()))?
+                    .trim();
+                match header.strip_prefix(PROFILE_SECTION) {
+                    Some(name) if !name.is_empty() => {
+                        own.profiles.entry(name.to_owned()).or_default();
+                        profile = Some(name.to_owned());
+                    }
+                    _ => return Err(format!("{}: Unsupported section `[{header}]`.", location())),
+                }
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("{}: Expected `key = value`.", location()))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(format!("{}: Missing key.", location()));
+            }
+            let value = parse_value(value.trim()).map_err(|e| format!("{}: {e}", location()))?;
+            match &profile {
+                None if key == INCLUDE_KEY => includes.extend(value),
+                Some(_) if key == INCLUDE_KEY => {
+                    return Err(format!(
+                        "{}: `{INCLUDE_KEY}` is only allowed at the top level.",
+                        location()
+                    ));
+                }
+                None => {
+                    own.values.insert(key.to_owned(), value);
+                }
+                Some(name) => {
+                    own.profiles
+                        .entry(name.clone())
+                        .or_default()
+                        .insert(key.to_owned(), value);
+                }
+            }
+        }
+
+        // included files are loaded first so the including file takes precedence.
+        let base = path.parent().unwrap_or(Path::new(""));
+        for include in includes {
+            self.load_file(&base.join(include), chain)?;
+        }
+        self.values.extend(own.values);
+        for (name, values) in own.profiles {
+            self.profiles.entry(name).or_default().extend(values);
+        }
+        chain.pop();
+        Ok(())
+    }
+}
+
+/// Expands a leading `~` in `path` to the home directory of the user, from `HOME` or `USERPROFILE`
+/// as `host` sees them.
+pub(crate) fn expand_home(path: &str, host: &dyn Host) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => rest,
+        _ => return PathBuf::from(path),
+    };
+    match host.var("HOME").or_else(|| host.var("USERPROFILE")) {
+        Some(home) => PathBuf::from(home).join(rest.trim_start_matches(['/', '\\'])),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Writes a commented configuration file listing every argument of the parser,
+/// its description, type and default value. Every entry is commented out so the file
+/// starts with no effect and users uncomment only what they need.
+/// # Arguments
+/// `parser` Parser whose arguments are listed.
+/// `path` Location of the file to write, overwritten if it exists.
+/// # Errors
+/// If the file cannot be written.
+pub fn write_template(parser: &ArgParser, path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, render_template(parser))
+}
+
+/// Renders the configuration file written by [`write_template`].
+pub fn render_template(parser: &ArgParser) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Configuration for {}.", parser.executable);
+    if !parser.description.is_empty() {
+        let _ = writeln!(out, "# {}", parser.description);
+    }
+    let _ = writeln!(
+        out,
+        "# Values given on the command line take precedence over this file."
+    );
+    for arg in &parser.args {
+        let _ = writeln!(out);
+        if !arg.description.is_empty() {
+            let _ = writeln!(out, "# {}", arg.description);
+        }
+        let mut details = if arg.multiple {
+            format!("type: list of {}", arg.kind)
+        } else {
+            format!("type: {}", arg.kind)
+        };
+        if arg.required {
+            details.push_str(", required");
+        }
+        if let Some(default) = &arg.default_value {
+            let _ = write!(details, ", default: {}", arg.display_value(default));
+        }
+        if !arg.choices.is_empty() {
+            let _ = write!(details, ", choices: {}", arg.choices.join(" | "));
+        }
+        if let Some(range) = &arg.range {
+            let _ = write!(details, ", range: {range}");
+        }
+        if arg.sensitive {
+            details.push_str(", sensitive");
+        }
+        let _ = writeln!(out, "# {details}");
+        let sample = match (&arg.default_value, arg.kind) {
+            (Some(value), _) if !arg.sensitive => format_value(arg.kind, value),
+            (_, kind) if !arg.choices.is_empty() => format_value(kind, &arg.choices[0]),
+            (_, ArgKind::String | ArgKind::Path) => "\"\"".to_owned(),
+            (_, ArgKind::DateTime) => "\"now\"".to_owned(),
+            (_, ArgKind::Duration) => "\"1m\"".to_owned(),
+            (_, ArgKind::Size) => "\"1MB\"".to_owned(),
+            (_, ArgKind::IpAddr) => "\"127.0.0.1\"".to_owned(),
+            (_, ArgKind::SocketAddr) => "\"127.0.0.1:8080\"".to_owned(),
+            (_, ArgKind::Regex) => "\".*\"".to_owned(),
+            (_, ArgKind::Url) => "\"https://example.com\"".to_owned(),
+            (_, ArgKind::SemVer) => "\"1.0.0\"".to_owned(),
+            (_, ArgKind::Uuid) => "\"00000000-0000-0000-0000-000000000000\"".to_owned(),
+            (_, ArgKind::Integer | ArgKind::Unsigned | ArgKind::Count) => "0".to_owned(),
+            (_, ArgKind::Float) => "0.0".to_owned(),
+            (_, ArgKind::Boolean) => "false".to_owned(),
+        };
+        if arg.multiple {
+            let _ = writeln!(out, "#{} = [{}]", arg.long_name, sample);
+        } else {
+            let _ = writeln!(out, "#{} = {}", arg.long_name, sample);
+        }
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "# Profiles selected with `--profile <name>` override the values above."
+    );
+    let _ = writeln!(out, "#[profiles.example]");
+    out
+}
+
+/// Formats a raw value as it must be written in the configuration file.
+fn format_value(kind: ArgKind, value: &str) -> String {
+    match kind {
+        ArgKind::String
+        | ArgKind::Path
+        | ArgKind::DateTime
+        | ArgKind::Duration
+        | ArgKind::Size
+        | ArgKind::IpAddr
+        | ArgKind::SocketAddr
+        | ArgKind::Regex
+        | ArgKind::Url
+        | ArgKind::SemVer
+        | ArgKind::Uuid => {
+            let escaped = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+                .replace('\t', "\\t")
+                .replace('\r', "\\r");
+            format!("\"{escaped}\"")
+        }
+        _ => value.to_owned(),
+    }
+}
+
+/// Removes a trailing `#` comment, ignoring `#` characters inside quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..index],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Parses a value into its raw string representations, arrays produce one item per element.
+fn parse_value(value: &str) -> Result<Vec<String>, String> {
+    let Some(inner) = value.strip_prefix('[') else {
+        return parse_scalar(value).map(|value| vec![value]);
+    };
+    let inner = inner
+        .strip_suffix(']')
+        .ok_or_else(|| "Unterminated array.".to_owned())?;
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (index, c) in inner.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, ',') => {
+                items.push(parse_scalar(inner[start..index].trim())?);
+                start = index + 1;
+            }
+            _ => {}
+        }
+        escaped = false;
+    }
+    // allow a trailing comma
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        items.push(parse_scalar(last)?);
+    }
+    Ok(items)
+}
+
+/// Parses a single value into its raw string representation.
+/// Supports basic strings (`"..."`), literal strings (`'...'`) and bare words such as numbers and booleans.
+fn parse_scalar(value: &str) -> Result<String, String> {
+    if let Some(inner) = value.strip_prefix('\'') {
+        return inner
+            .strip_suffix('\'')
+            .map(str::to_owned)
+            .ok_or_else(|| "Unterminated string.".to_owned());
+    }
+    if let Some(inner) = value.strip_prefix('"') {
+        let inner = inner
+            .strip_suffix('"')
+            .ok_or_else(|| "Unterminated string.".to_owned())?;
+        let mut result = String::with_capacity(inner.len());
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('r') => result.push('\r'),
+                Some('"') => result.push('"'),
+                Some('\\') => result.push('\\'),
+                Some(other) => return Err(format!("Invalid escape sequence `\\{other}`.")),
+                None => return Err("Unterminated escape sequence.".to_owned()),
+            }
+        }
+        return Ok(result);
+    }
+    if value.is_empty() {
+        Err("Missing value.".to_owned())
+    } else {
+        Ok(value.to_owned())
+    }
+}
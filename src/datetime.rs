@@ -0,0 +1,213 @@
+//! Dates and times given on the command line, either absolute like `2024-05-01T12:00:00Z` or
+//! relative to the clock of the parser like `yesterday`, `2h ago` or `now-30d`.
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use alloc::{borrow::ToOwned, format, string::String};
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAY: i64 = 86_400;
+
+/// Point in time described by a value, before resolving it against a clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Moment {
+    /// Seconds since the epoch as read on a wall clock, with the UTC offset in seconds if given.
+    Absolute { seconds: i64, offset: Option<i64> },
+    /// Seconds from now.
+    FromNow(i64),
+    /// Days from the start of today, then seconds from there.
+    FromToday { days: i64, seconds: i64 },
+}
+
+/// Parses an absolute or relative date and time.
+/// # Errors
+/// If `value` is neither.
+pub(crate) fn parse(value: &str) -> Result<Moment, ()> {
+    let value = value.trim();
+    if let Some(moment) = relative(&value.to_lowercase()) {
+        return Ok(moment);
+    }
+    absolute(value)
+}
+
+/// Resolves `moment` against `now`, reading wall clock times and days at `offset` seconds from UTC.
+/// # Returns
+/// Seconds since the epoch.
+#[cfg(feature = "std")]
+pub(crate) fn resolve(moment: Moment, now: SystemTime, offset: i64) -> i64 {
+    let now = match now.duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs() as i64),
+    };
+    match moment {
+        Moment::Absolute {
+            seconds,
+            offset: given,
+        } => seconds - given.unwrap_or(offset),
+        Moment::FromNow(seconds) => now + seconds,
+        Moment::FromToday { days, seconds } => {
+            let today = (now + offset).div_euclid(DAY) * DAY - offset;
+            today + days * DAY + seconds
+        }
+    }
+}
+
+/// Formats seconds since the epoch as RFC 3339, at `offset` seconds from UTC.
+#[cfg(feature = "std")]
+pub(crate) fn format(timestamp: i64, offset: i64) -> String {
+    let local = timestamp + offset;
+    let (year, month, day) = civil_from_days(local.div_euclid(DAY));
+    let time = local.rem_euclid(DAY);
+    let zone = match offset {
+        0 => "Z".to_owned(),
+        _ => {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let minutes = offset.abs() / 60;
+            format!("{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+        }
+    };
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}{zone}",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// `now`, `today`, `yesterday` or `tomorrow` optionally followed by `+<amount>` or `-<amount>`,
+/// or `<amount> ago`.
+fn relative(value: &str) -> Option<Moment> {
+    if let Some(amount) = value.strip_suffix("ago") {
+        return Some(Moment::FromNow(-amount_seconds(amount.trim())?));
+    }
+    let (base, rest) = ["now", "today", "yesterday", "tomorrow"]
+        .iter()
+        .find_map(|base| Some((*base, value.strip_prefix(base)?)))?;
+    let rest = rest.trim();
+    let seconds = match rest.chars().next() {
+        None => 0,
+        Some('+') => amount_seconds(rest[1..].trim())?,
+        Some('-') => -amount_seconds(rest[1..].trim())?,
+        Some(_) => return None,
+    };
+    Some(match base {
+        "now" => Moment::FromNow(seconds),
+        "yesterday" => Moment::FromToday { days: -1, seconds },
+        "tomorrow" => Moment::FromToday { days: 1, seconds },
+        _ => Moment::FromToday { days: 0, seconds },
+    })
+}
+
+/// Seconds in an amount like `30d`, `2h` or `5 minutes`.
+fn amount_seconds(amount: &str) -> Option<i64> {
+    let split = amount.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = amount.split_at(split);
+    let number: i64 = number.parse().ok()?;
+    let unit = match unit.trim() {
+        "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60,
+        "h" | "hour" | "hours" => 3600,
+        "d" | "day" | "days" => DAY,
+        "w" | "week" | "weeks" => 7 * DAY,
+        _ => return None,
+    };
+    number.checked_mul(unit)
+}
+
+/// `YYYY-MM-DD`, optionally followed by `T` or a space and `HH:MM[:SS]`, and `Z` or `±HH:MM`.
+fn absolute(value: &str) -> Result<Moment, ()> {
+    let number = |text: &str| -> Result<i64, ()> {
+        if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(());
+        }
+        text.parse().map_err(|_| ())
+    };
+    let (date, rest) = match value.get(..10) {
+        Some(date) => (date, &value[10..]),
+        None => (value, ""),
+    };
+    let mut parts = date.split('-');
+    let (Some(year), Some(month), Some(day), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(());
+    };
+    let (year, month, day) = (number(year)?, number(month)?, number(day)?);
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(());
+    }
+    let mut seconds = days_from_civil(year, month, day) * DAY;
+    let mut offset = None;
+    let rest = match rest.chars().next() {
+        None => "",
+        Some('T' | 't' | ' ') => &rest[1..],
+        Some(_) => return Err(()),
+    };
+    if !rest.is_empty() {
+        let zone_start = rest.find(['Z', 'z', '+', '-']).unwrap_or(rest.len());
+        let (time, zone) = rest.split_at(zone_start);
+        let fields: Vec<&str> = time.split(':').collect();
+        let (hour, minute, second) = match fields.as_slice() {
+            [hour, minute] => (number(hour)?, number(minute)?, 0),
+            [hour, minute, second] => (number(hour)?, number(minute)?, number(second)?),
+            _ => return Err(()),
+        };
+        if hour > 23 || minute > 59 || second > 60 {
+            return Err(());
+        }
+        seconds += hour * 3600 + minute * 60 + second;
+        offset = match zone {
+            "" => None,
+            "Z" | "z" => Some(0),
+            _ => {
+                let (hours, minutes) = zone[1..].split_once(':').ok_or(())?;
+                let (hours, minutes) = (number(hours)?, number(minutes)?);
+                if hours > 23 || minutes > 59 {
+                    return Err(());
+                }
+                let sign = if zone.starts_with('-') { -1 } else { 1 };
+                Some(sign * (hours * 3600 + minutes * 60))
+            }
+        };
+    }
+    Ok(Moment::Absolute { seconds, offset })
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date of the proleptic Gregorian calendar `days` after 1970-01-01.
+#[cfg(feature = "std")]
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,186 @@
+//! Export of the whole definition of a parser as JSON, for external tooling like documentation
+//! generators, graphical wrappers or completion engines, see [`crate::ArgParser::to_json`].
+//!
+//! The definition is described once as a tree of [`Node`], rendered as JSON here and
+//! serialized with `serde` when the feature is enabled.
+use crate::{json, Arg, ArgKind, ArgParser, GroupKind};
+
+/// Part of the definition.
+pub(crate) enum Node {
+    Null,
+    Bool(bool),
+    Count(usize),
+    Text(String),
+    /// A raw value, rendered as the kind of its argument, see [`json::value`].
+    Value(ArgKind, String),
+    List(Vec<Node>),
+    /// Fields in the order they are rendered.
+    Object(Vec<(&'static str, Node)>),
+}
+
+impl Node {
+    /// JSON on one line.
+    fn inline(&self) -> String {
+        match self {
+            Node::Null => "null".to_owned(),
+            Node::Bool(flag) => flag.to_string(),
+            Node::Count(count) => count.to_string(),
+            Node::Text(text) => json::string(text),
+            Node::Value(kind, value) => json::value(*kind, value),
+            Node::List(items) => {
+                let items: Vec<String> = items.iter().map(Node::inline).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Node::Object(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(key, node)| format!("{}: {}", json::string(key), node.inline()))
+                    .collect();
+                format!("{{{}}}", fields.join(", "))
+            }
+        }
+    }
+}
+
+/// Renders the definition of `parser`: program, arguments, groups and examples.
+/// Arguments and groups are listed one per line.
+pub(crate) fn render(parser: &ArgParser) -> String {
+    let fields: Vec<String> = fields(parser)
+        .iter()
+        .map(|(key, node)| {
+            let rendered = match node {
+                Node::List(items) if items.iter().any(|item| matches!(item, Node::Object(_))) => {
+                    let items: Vec<String> = items
+                        .iter()
+                        .map(|item| format!("    {}", item.inline()))
+                        .collect();
+                    format!("[\n{}\n  ]", items.join(",\n"))
+                }
+                node => node.inline(),
+            };
+            format!("{}: {rendered}", json::string(key))
+        })
+        .collect();
+    format!("{{\n  {}\n}}\n", fields.join(",\n  "))
+}
+
+/// Definition of `parser`: program, arguments, groups and examples.
+#[cfg(feature = "serde")]
+pub(crate) fn describe(parser: &ArgParser) -> Node {
+    Node::Object(fields(parser))
+}
+
+fn fields(parser: &ArgParser) -> Vec<(&'static str, Node)> {
+    let args = parser
+        .args
+        .iter()
+        .map(|arg| argument(parser, arg))
+        .collect();
+    let groups = parser
+        .groups
+        .iter()
+        .map(|group| {
+            let kind = match group.kind() {
+                GroupKind::Exclusive => "exclusive",
+                GroupKind::OnlyWhen => "only_when",
+                GroupKind::AnyOf => "any_of",
+                GroupKind::AllOrNone => "all_or_none",
+            };
+            Node::Object(vec![
+                ("name", text(group.name())),
+                ("kind", text(kind)),
+                ("required", Node::Bool(group.is_required())),
+                ("args", list(group.args())),
+                ("parents", list(group.parents())),
+            ])
+        })
+        .collect();
+    vec![
+        ("name", text(&parser.executable)),
+        ("description", text(&parser.description)),
+        ("long_about", optional(parser.long_about.as_deref())),
+        ("version", optional(parser.version.as_deref())),
+        ("author", optional(parser.author.as_deref())),
+        ("license", optional(parser.license.as_deref())),
+        ("env_prefix", optional(parser.env_prefix.as_deref())),
+        ("keywords", list(&parser.keywords)),
+        ("category", optional(parser.category.as_deref())),
+        ("renamed_from", list(&parser.renamed_from)),
+        ("deprecated", optional(parser.command_deprecated.as_deref())),
+        ("args", Node::List(args)),
+        ("groups", Node::List(groups)),
+        ("examples", list(&parser.examples)),
+    ]
+}
+
+/// Definition of a single argument.
+fn argument(parser: &ArgParser, arg: &Arg) -> Node {
+    // the default of a sensitive argument is a secret as well.
+    let default = arg.default_value.as_deref().filter(|_| !arg.sensitive);
+    let range = arg.range.as_ref().map(ToString::to_string);
+    let short = arg.short_name.map(String::from);
+    let conditions = arg
+        .default_if
+        .iter()
+        .filter(|_| !arg.sensitive)
+        .map(|(other, value, default)| {
+            Node::Object(vec![
+                ("arg", text(other)),
+                ("value", text(value)),
+                ("default", Node::Value(arg.kind, default.clone())),
+            ])
+        })
+        .collect();
+    let shorts: Vec<String> = arg.short_aliases.iter().map(char::to_string).collect();
+    Node::Object(vec![
+        ("name", text(&arg.long_name)),
+        ("short", optional(short.as_deref())),
+        ("kind", Node::Text(arg.kind.to_string())),
+        ("description", text(&arg.description)),
+        ("long_help", optional(arg.long_help.as_deref())),
+        ("value_name", optional(arg.value_name.as_deref())),
+        ("required", Node::Bool(arg.required)),
+        ("positional", Node::Bool(arg.positional)),
+        ("multiple", Node::Bool(arg.multiple)),
+        ("min_values", count(arg.min_values)),
+        ("max_values", count(arg.max_values)),
+        ("hidden", Node::Bool(arg.hidden)),
+        ("sensitive", Node::Bool(arg.sensitive)),
+        (
+            "default",
+            default.map_or(Node::Null, |value| Node::Value(arg.kind, value.to_owned())),
+        ),
+        ("default_if", Node::List(conditions)),
+        ("choices", list(&arg.choices)),
+        ("optional_value", optional(arg.optional_value.as_deref())),
+        ("require_equals", Node::Bool(arg.require_equals)),
+        ("range", optional(range.as_deref())),
+        ("aliases", list(&arg.aliases)),
+        ("short_aliases", list(&shorts)),
+        ("env", optional(parser.env_name(arg).as_deref())),
+        ("heading", optional(arg.help_heading.as_deref())),
+        ("deprecated", optional(arg.deprecated.as_deref())),
+        ("alias_of", optional(arg.alias_of.as_deref())),
+        ("requires", list(&arg.requires)),
+        ("conflicts_with", list(&arg.conflicts_with)),
+        ("overrides_with", list(&arg.overrides_with)),
+        ("required_unless", list(&arg.required_unless)),
+        ("examples", list(&arg.examples)),
+    ])
+}
+
+fn text(value: &str) -> Node {
+    Node::Text(value.to_owned())
+}
+
+fn list(values: &[String]) -> Node {
+    Node::List(values.iter().map(|value| text(value)).collect())
+}
+
+fn optional(value: Option<&str>) -> Node {
+    value.map_or(Node::Null, text)
+}
+
+fn count(value: Option<usize>) -> Node {
+    value.map_or(Node::Null, Node::Count)
+}
@@ -0,0 +1,538 @@
+//! Errors produced while parsing the command line arguments.
+use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::json;
+
+/// Error found while parsing the command line arguments.
+/// Values of sensitive arguments are masked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// Help was requested with `--help` or `-h`, followed by a search pattern for `--help <pattern>`.
+    /// Only returned by [`crate::ArgParser::try_parse`] and [`crate::ArgParser::try_parse_from`].
+    HelpRequested { search: Option<String> },
+    /// The help page with the long help of every argument was requested with `--help --verbose`,
+    /// see [`crate::Arg::long_help`].
+    VerboseHelpRequested,
+    /// The version was requested with `--version` or `-V`, see [`crate::ArgParser::version`].
+    /// Only returned by [`crate::ArgParser::try_parse`] and [`crate::ArgParser::try_parse_from`].
+    VersionRequested,
+    /// The version was requested as JSON with `--version --format json` or `--version=json`,
+    /// see [`crate::ArgParser::render_version_json`].
+    /// Only returned by [`crate::ArgParser::try_parse`] and [`crate::ArgParser::try_parse_from`].
+    VersionJsonRequested,
+    /// The version was requested with its details with `--version --verbose`,
+    /// see [`crate::ArgParser::render_long_version`].
+    /// Only returned by [`crate::ArgParser::try_parse`] and [`crate::ArgParser::try_parse_from`].
+    VerboseVersionRequested,
+    /// The resolved values were requested with the hidden `--clarg-debug` flag, see
+    /// [`crate::ArgParser::disable_debug`]. `dump` is the JSON object printed by
+    /// [`crate::ArgParser::parse`], mapping each argument to its value and source.
+    /// Only returned by [`crate::ArgParser::try_parse`] and [`crate::ArgParser::try_parse_from`].
+    DebugRequested { dump: String },
+    /// An option expecting a value was given none. `option` is the option as given, e.g. `-p`.
+    MissingValue { arg: String, option: String },
+    /// A string option was given a value that looks like an option, or a flag was given a value
+    /// with `--flag=value`. `option` is the option as given, e.g. `-p`.
+    UnexpectedValue {
+        arg: String,
+        option: String,
+        value: String,
+    },
+    /// An option that is not part of the definition.
+    UnknownOption {
+        option: String,
+        suggestion: Option<String>,
+    },
+    /// An option taking a single value given several times, see [`crate::Arg::on_repeat`].
+    /// `option` is the option as given the second time, e.g. `-p`.
+    RepeatedArgument { arg: String, option: String },
+    /// An argument limited to other operating systems, see [`crate::Arg::only_on`].
+    /// `os` is the running system, e.g. `Windows`.
+    UnsupportedOs {
+        arg: String,
+        option: String,
+        os: String,
+    },
+    /// An abbreviated option matching several options, see [`crate::ArgParser::allow_abbrev`].
+    /// `candidates` are the options it could stand for, e.g. `--verbose`.
+    AmbiguousOption {
+        option: String,
+        candidates: Vec<String>,
+    },
+    /// A bare word that is not the value of any option.
+    UnexpectedArgument { value: String },
+    /// A value that cannot be converted into the kind of its argument.
+    /// `code` tells the kind apart, see [`ParseError::code`].
+    TypeMismatch {
+        arg: String,
+        value: String,
+        code: &'static str,
+        message: String,
+    },
+    /// A value that is not one of the choices of its argument.
+    InvalidChoice {
+        arg: String,
+        value: String,
+        choices: Vec<String>,
+        suggestion: Option<String>,
+        message: String,
+    },
+    /// A value breaking a constraint like [`crate::Arg::range`] or [`crate::Arg::sorted`].
+    /// `code` tells the constraint apart, see [`ParseError::code`].
+    ConstraintViolation {
+        arg: String,
+        value: String,
+        code: &'static str,
+        message: String,
+    },
+    /// A value rejected by a validator set with [`crate::Arg::validator`].
+    ValidationFailed {
+        arg: String,
+        value: String,
+        message: String,
+    },
+    /// The parsed arguments were rejected as a whole by a check set with
+    /// [`crate::ArgParser::validator`].
+    InvalidArguments { message: String },
+    /// The stdin or file a value refers to cannot be read, see [`crate::Arg::allow_stdin`] and
+    /// [`crate::Arg::allow_file_ref`]. `value` is the reference, e.g. `@token.txt`.
+    UnreadableValue {
+        arg: String,
+        value: String,
+        message: String,
+    },
+    /// A value repeated for an argument set with [`crate::Arg::deny_duplicates`].
+    DuplicateValue { arg: String, value: String },
+    /// A required argument that was not given.
+    MissingRequired { arg: String },
+    /// An argument set with [`crate::Arg::required_unless`] was given neither itself nor any of `others`.
+    RequiredUnless { arg: String, others: Vec<String> },
+    /// An argument used without `other`, which it was set to need with [`crate::Arg::requires`].
+    Requires { arg: String, other: String },
+    /// Two arguments used together although one was set with [`crate::Arg::conflicts_with`] the other.
+    Conflict { arg: String, other: String },
+    /// The arguments used break the rules of a group. `used` lists the members of the group in
+    /// use with their number of occurrences: an argument repeated, like `-vvv`, is used once.
+    GroupViolation {
+        group: String,
+        used: Vec<(String, usize)>,
+        message: String,
+    },
+    /// An invalid value read from an environment variable.
+    Environment {
+        variable: String,
+        error: Box<ParseError>,
+    },
+    /// An environment variable carrying the parser prefix that matches no argument.
+    UnknownEnv { variable: String },
+    /// An invalid value read from the configuration file.
+    ConfigValue { key: String, error: Box<ParseError> },
+    /// An argument set to prompt for its value got no answer, see [`crate::PromptFallback`].
+    PromptUnanswered { arg: String },
+    /// The configuration file cannot be used, e.g. it cannot be read or has unknown keys,
+    /// or there is no last run to repeat, see [`crate::ArgParser::remember_last`].
+    Config { message: String },
+    /// A response file given as `@file` cannot be read, see [`crate::ArgParser::response_files`].
+    ResponseFile { file: String, message: String },
+    /// Fewer values than set with [`crate::Arg::min_values`] were given to an argument.
+    TooFewValues {
+        arg: String,
+        min: usize,
+        count: usize,
+    },
+    /// More values than set with [`crate::Arg::max_values`] were given to an argument.
+    TooManyValues {
+        arg: String,
+        max: usize,
+        count: usize,
+    },
+    /// A command line given as one string cannot be split into arguments, e.g. a quote is left
+    /// open, see [`crate::ArgParser::try_parse_str`].
+    InvalidQuoting { message: String },
+    /// The command line goes past a limit set with [`crate::ArgParser::max_args`],
+    /// [`crate::ArgParser::max_value_length`] or [`crate::ArgParser::max_values`], the latter
+    /// naming the argument. `code` tells the limit apart, see [`ParseError::code`].
+    LimitExceeded {
+        arg: Option<String>,
+        limit: usize,
+        code: &'static str,
+        message: String,
+    },
+    /// No command was given to a program dispatching on commands, see [`crate::commands!`].
+    MissingCommand { commands: Vec<String> },
+    /// A command that is not one of the commands of the program, see [`crate::commands!`].
+    UnknownCommand {
+        command: String,
+        suggestion: Option<String>,
+    },
+}
+
+impl ParseError {
+    /// Long name of the argument (or name of the group) the error is about, if any.
+    pub fn arg(&self) -> Option<&str> {
+        match self {
+            ParseError::MissingValue { arg, .. }
+            | ParseError::RepeatedArgument { arg, .. }
+            | ParseError::UnsupportedOs { arg, .. }
+            | ParseError::UnexpectedValue { arg, .. }
+            | ParseError::TypeMismatch { arg, .. }
+            | ParseError::InvalidChoice { arg, .. }
+            | ParseError::DuplicateValue { arg, .. }
+            | ParseError::ConstraintViolation { arg, .. }
+            | ParseError::ValidationFailed { arg, .. }
+            | ParseError::UnreadableValue { arg, .. }
+            | ParseError::MissingRequired { arg }
+            | ParseError::RequiredUnless { arg, .. }
+            | ParseError::Conflict { arg, .. }
+            | ParseError::Requires { arg, .. }
+            | ParseError::TooFewValues { arg, .. }
+            | ParseError::TooManyValues { arg, .. }
+            | ParseError::PromptUnanswered { arg } => Some(arg),
+            ParseError::GroupViolation { group, .. } => Some(group),
+            ParseError::LimitExceeded { arg, .. } => arg.as_deref(),
+            ParseError::Environment { error, .. } => error.arg(),
+            ParseError::ConfigValue { key, .. } => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Offending value, if any. Masked for sensitive arguments.
+    pub fn value(&self) -> Option<&str> {
+        match self {
+            ParseError::UnexpectedValue { value, .. }
+            | ParseError::UnexpectedArgument { value }
+            | ParseError::TypeMismatch { value, .. }
+            | ParseError::InvalidChoice { value, .. }
+            | ParseError::DuplicateValue { value, .. }
+            | ParseError::ConstraintViolation { value, .. }
+            | ParseError::ValidationFailed { value, .. }
+            | ParseError::UnreadableValue { value, .. } => Some(value),
+            ParseError::UnknownOption { option, .. }
+            | ParseError::AmbiguousOption { option, .. } => Some(option),
+            ParseError::UnknownCommand { command, .. } => Some(command),
+            ParseError::ResponseFile { file, .. } => Some(file),
+            ParseError::Environment { error, .. } | ParseError::ConfigValue { error, .. } => {
+                error.value()
+            }
+            _ => None,
+        }
+    }
+
+    /// Correction proposed for a misspelled option, choice or command, e.g. `--verbose`.
+    /// Never given for sensitive arguments.
+    pub fn suggestion(&self) -> Option<String> {
+        match self {
+            ParseError::UnknownOption { suggestion, .. } => {
+                suggestion.as_ref().map(|name| format!("--{name}"))
+            }
+            ParseError::InvalidChoice { suggestion, .. }
+            | ParseError::UnknownCommand { suggestion, .. } => suggestion.clone(),
+            ParseError::Environment { error, .. } | ParseError::ConfigValue { error, .. } => {
+                error.suggestion()
+            }
+            _ => None,
+        }
+    }
+
+    /// The error as a single-line JSON object with its `code`, `arg`, `value`, `message` and
+    /// `suggestions`, as printed with [`crate::ErrorFormat::Json`]. Missing fields are `null`.
+    /// # Example
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.").arg(Arg::boolean("json", None, "JSON output"));
+    /// let error = parser.try_parse_from(["--jsn"]).unwrap_err();
+    /// assert_eq!(
+    ///     error.to_json(),
+    ///     r#"{"code": "E_UNKNOWN_OPTION", "arg": null, "value": "--jsn", "message": "Unrecognized option `--jsn` passed. Did you mean `--json`?", "suggestions": ["--json"]}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let optional = |value: Option<&str>| value.map_or("null".to_owned(), json::string);
+        let suggestions: Vec<String> = self.suggestion().iter().map(|s| json::string(s)).collect();
+        format!(
+            "{{\"code\": \"{}\", \"arg\": {}, \"value\": {}, \"message\": {}, \"suggestions\": [{}]}}",
+            self.code(),
+            optional(self.arg()),
+            optional(self.value()),
+            json::string(&self.to_string()),
+            suggestions.join(", ")
+        )
+    }
+
+    /// Whether the error only reports that help or the version was requested, which is not a
+    /// mistake of the user: [`crate::ArgParser::parse`] prints them and exits with status 0.
+    /// # Example
+    #[cfg_attr(feature = "std", doc = "```")]
+    #[cfg_attr(not(feature = "std"), doc = "```ignore")]
+    /// # use clarg::ArgParser;
+    /// let parser = ArgParser::new("Find duplicate files.");
+    /// match parser.try_parse_from(["--help"]) {
+    ///     Ok(arguments) => { /* run the program */ }
+    ///     Err(error) if error.is_benign() => parser.help(),
+    ///     Err(error) => eprintln!("{error}"),
+    /// }
+    /// ```
+    pub fn is_benign(&self) -> bool {
+        matches!(
+            self,
+            ParseError::HelpRequested { .. }
+                | ParseError::VerboseHelpRequested
+                | ParseError::VersionRequested
+                | ParseError::VersionJsonRequested
+                | ParseError::VerboseVersionRequested
+                | ParseError::DebugRequested { .. }
+        )
+    }
+
+    /// Stable, machine-readable code of the class of the error, for scripts and wrappers that
+    /// react to specific failures. Values that cannot be converted have a code per kind, e.g.
+    /// `E_INT_INVALID`, `E_DATETIME_INVALID` or `E_SIZE_INVALID`, and constraints one per check:
+    /// `E_INT_RANGE`, `E_UINT_RANGE`, `E_FLOAT_RANGE`, `E_PATH_NOT_FOUND`, `E_PATH_NOT_DIR`,
+    /// `E_PATH_NOT_FILE` and `E_UNSORTED`. Values read from the environment or the configuration file have the
+    /// code of their underlying error. Codes are never changed nor reused once published.
+    /// See [`crate::ArgParser::show_error_codes`] to print them.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::HelpRequested { .. } | ParseError::VerboseHelpRequested => "E_HELP",
+            ParseError::VersionRequested
+            | ParseError::VersionJsonRequested
+            | ParseError::VerboseVersionRequested => "E_VERSION",
+            ParseError::DebugRequested { .. } => "E_DEBUG",
+            ParseError::MissingValue { .. } => "E_MISSING_VALUE",
+            ParseError::UnexpectedValue { .. } => "E_UNEXPECTED_VALUE",
+            ParseError::UnknownOption { .. } => "E_UNKNOWN_OPTION",
+            ParseError::AmbiguousOption { .. } => "E_AMBIGUOUS_OPTION",
+            ParseError::RepeatedArgument { .. } => "E_ARG_REPEATED",
+            ParseError::UnsupportedOs { .. } => "E_UNSUPPORTED_OS",
+            ParseError::UnexpectedArgument { .. } => "E_UNEXPECTED_ARGUMENT",
+            ParseError::TypeMismatch { code, .. }
+            | ParseError::ConstraintViolation { code, .. }
+            | ParseError::LimitExceeded { code, .. } => code,
+            ParseError::InvalidChoice { .. } => "E_INVALID_CHOICE",
+            ParseError::ValidationFailed { .. } => "E_VALIDATION",
+            ParseError::InvalidArguments { .. } => "E_INVALID_ARGUMENTS",
+            ParseError::UnreadableValue { .. } => "E_VALUE_UNREADABLE",
+            ParseError::DuplicateValue { .. } => "E_DUPLICATE_VALUE",
+            ParseError::MissingRequired { .. } => "E_MISSING_REQUIRED",
+            ParseError::RequiredUnless { .. } => "E_REQUIRED_UNLESS",
+            ParseError::Requires { .. } => "E_REQUIRES",
+            ParseError::Conflict { .. } => "E_CONFLICT",
+            ParseError::GroupViolation { .. } => "E_GROUP",
+            ParseError::Environment { error, .. } | ParseError::ConfigValue { error, .. } => {
+                error.code()
+            }
+            ParseError::UnknownEnv { .. } => "E_UNKNOWN_ENV",
+            ParseError::PromptUnanswered { .. } => "E_PROMPT_UNANSWERED",
+            ParseError::Config { .. } => "E_CONFIG",
+            ParseError::ResponseFile { .. } => "E_RESPONSE_FILE",
+            ParseError::InvalidQuoting { .. } => "E_QUOTING",
+            ParseError::TooFewValues { .. } => "E_TOO_FEW_VALUES",
+            ParseError::TooManyValues { .. } => "E_TOO_MANY_VALUES",
+            ParseError::MissingCommand { .. } => "E_MISSING_COMMAND",
+            ParseError::UnknownCommand { .. } => "E_UNKNOWN_COMMAND",
+        }
+    }
+
+    /// Description of the problem, as printed by [`crate::ArgParser::parse`].
+    pub fn message(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::HelpRequested { .. } | ParseError::VerboseHelpRequested => {
+                write!(f, "Help requested.")
+            }
+            ParseError::VersionRequested
+            | ParseError::VersionJsonRequested
+            | ParseError::VerboseVersionRequested => {
+                write!(f, "Version requested.")
+            }
+            ParseError::DebugRequested { .. } => write!(f, "Debug output requested."),
+            ParseError::MissingValue { arg, option } => {
+                write!(f, "Missing value for argument: {}", as_given(option, arg))
+            }
+            ParseError::UnexpectedValue { arg, option, value } => {
+                write!(
+                    f,
+                    "Unexpected value `{value}` for argument: {}",
+                    as_given(option, arg)
+                )
+            }
+            ParseError::RepeatedArgument { arg, option } => {
+                write!(
+                    f,
+                    "Argument {} was provided more than once.",
+                    as_given(option, arg)
+                )
+            }
+            ParseError::UnsupportedOs { option, os, .. } => {
+                write!(f, "`{option}` is not supported on {os}.")
+            }
+            ParseError::AmbiguousOption { option, candidates } => {
+                let (last, others) = candidates
+                    .split_last()
+                    .map_or(("", &[][..]), |(last, others)| (last.as_str(), others));
+                match others {
+                    [] => write!(f, "Ambiguous option `{option}`, could be {last}."),
+                    _ => write!(
+                        f,
+                        "Ambiguous option `{option}`, could be {} or {last}.",
+                        others.join(", ")
+                    ),
+                }
+            }
+            ParseError::UnknownOption { option, suggestion } => {
+                write!(f, "Unrecognized option `{option}` passed.")?;
+                match suggestion {
+                    Some(name) => write!(f, " Did you mean `--{name}`?"),
+                    None => Ok(()),
+                }
+            }
+            ParseError::UnexpectedArgument { value } => {
+                write!(f, "Unexpected argument option `{value}` passed.")
+            }
+            ParseError::TypeMismatch { message, .. }
+            | ParseError::InvalidChoice { message, .. }
+            | ParseError::ConstraintViolation { message, .. }
+            | ParseError::ValidationFailed { message, .. }
+            | ParseError::UnreadableValue { message, .. }
+            | ParseError::GroupViolation { message, .. }
+            | ParseError::LimitExceeded { message, .. }
+            | ParseError::InvalidArguments { message }
+            | ParseError::Config { message } => f.write_str(message),
+            ParseError::DuplicateValue { arg, value } => {
+                write!(f, "Duplicate value `{value}` for argument: --{arg}")
+            }
+            ParseError::MissingRequired { arg } => write!(f, "Missing required argument: `{arg}`"),
+            ParseError::RequiredUnless { arg, others } => {
+                let others: Vec<String> = others.iter().map(|other| format!("--{other}")).collect();
+                write!(
+                    f,
+                    "Missing required argument: `{arg}`. It can only be left out when one of the following is used: [{}]",
+                    others.join(", ")
+                )
+            }
+            ParseError::Requires { arg, other } => {
+                write!(f, "Argument: --{arg} requires --{other} to be used as well")
+            }
+            ParseError::Conflict { arg, other } => {
+                write!(f, "Cannot use argument: --{arg} together with --{other}")
+            }
+            ParseError::Environment { variable, error } => {
+                write!(
+                    f,
+                    "Invalid value in environment variable `{variable}`: {error}"
+                )
+            }
+            ParseError::UnknownEnv { variable } => write!(
+                f,
+                "Environment variable `{variable}` does not match any argument."
+            ),
+            ParseError::ConfigValue { key, error } => {
+                write!(f, "Invalid configuration value for `{key}`: {error}")
+            }
+            ParseError::PromptUnanswered { arg } => {
+                write!(f, "No answer to the prompt for argument: --{arg}")
+            }
+            ParseError::ResponseFile { file, message } => {
+                write!(f, "Cannot read the response file `{file}`: {message}")
+            }
+            ParseError::InvalidQuoting { message } => write!(f, "{message}"),
+            ParseError::TooFewValues { arg, min, count } => write!(
+                f,
+                "Too few values for argument `{arg}`: expected at least {min}, got {count}."
+            ),
+            ParseError::TooManyValues { arg, max, count } => write!(
+                f,
+                "Too many values for argument `{arg}`: expected at most {max}, got {count}."
+            ),
+            ParseError::MissingCommand { commands } => write!(
+                f,
+                "Missing command. One of the following must be used: [{}]",
+                commands.join(", ")
+            ),
+            ParseError::UnknownCommand {
+                command,
+                suggestion,
+            } => {
+                write!(f, "Unrecognized command `{command}` passed.")?;
+                match suggestion {
+                    Some(name) => write!(f, " Did you mean `{name}`?"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// Error reading a value from an [`crate::ArgMap`], e.g. with [`crate::ArgMap::get`].
+/// It is built without allocating, the name is shared with the map, so lookups of arguments that
+/// were not given stay cheap in hot paths. Converts into the `String` it displays, so it can be
+/// returned with `?` from functions returning `Result<_, String>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GetError {
+    /// The argument has no value, or is not declared.
+    Missing { name: Arc<str> },
+    /// The value of the argument cannot be converted into the type requested, e.g. `300` into `u8`.
+    Invalid {
+        name: Arc<str>,
+        /// name of the type requested, e.g. `u8`, or the kind for `duration` and `datetime`.
+        type_name: &'static str,
+    },
+}
+
+impl GetError {
+    /// Long name of the argument requested.
+    pub fn name(&self) -> &str {
+        match self {
+            GetError::Missing { name } | GetError::Invalid { name, .. } => name,
+        }
+    }
+}
+
+impl fmt::Display for GetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GetError::Missing { name } => write!(f, "Inexistent `{name}` value requested."),
+            GetError::Invalid { name, type_name } => {
+                write!(
+                    f,
+                    "Cannot convert the value of `{name}` into type `{type_name}`"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for GetError {}
+
+impl From<GetError> for String {
+    fn from(error: GetError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Option as given on the command line, followed by its long name when spelled differently,
+/// e.g. `-p (--path)`.
+fn as_given(option: &str, arg: &str) -> String {
+    match option.strip_prefix("--") {
+        Some(name) if name == arg => option.to_owned(),
+        _ => format!("{option} (--{arg})"),
+    }
+}
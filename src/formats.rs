@@ -0,0 +1,123 @@
+//! Checks of the values of regular expression, URL, version and UUID arguments.
+//!
+//! Each kind is fully validated by its crate when the cargo feature of the same name is enabled,
+//! and otherwise checked for its overall shape only, keeping the default build free of
+//! dependencies. Values are retrieved as text, or as the type of the crate with the feature.
+
+/// Whether `value` is a regular expression, e.g. `^v\d+$`.
+/// Without the `regex` feature, only brackets and escapes are checked.
+#[cfg(feature = "regex")]
+pub(crate) fn is_regex(value: &str) -> bool {
+    regex::Regex::new(value).is_ok()
+}
+
+#[cfg(not(feature = "regex"))]
+pub(crate) fn is_regex(value: &str) -> bool {
+    let mut groups = 0usize;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.next().is_none() => return false,
+            '\\' => {}
+            '(' => groups += 1,
+            ')' if groups == 0 => return false,
+            ')' => groups -= 1,
+            // a class ends at the first `]` not right after its start, escapes aside.
+            '[' => {
+                let mut empty = true;
+                loop {
+                    match chars.next() {
+                        None => return false,
+                        Some('\\') if chars.next().is_none() => return false,
+                        Some(']') if !empty => break,
+                        Some('^') if empty => continue,
+                        _ => {}
+                    }
+                    empty = false;
+                }
+            }
+            _ => {}
+        }
+    }
+    groups == 0
+}
+
+/// Whether `value` is an absolute URL, e.g. `https://example.com/path`.
+/// Without the `url` feature, only the scheme and the absence of spaces are checked.
+#[cfg(feature = "url")]
+pub(crate) fn is_url(value: &str) -> bool {
+    url::Url::parse(value).is_ok()
+}
+
+#[cfg(not(feature = "url"))]
+pub(crate) fn is_url(value: &str) -> bool {
+    let Some((scheme, rest)) = value.split_once(':') else {
+        return false;
+    };
+    let scheme_valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    // `scheme://` must be followed by a host, except for files.
+    let host_given = match rest.strip_prefix("//") {
+        Some(authority) => scheme.eq_ignore_ascii_case("file") || !authority.starts_with('/'),
+        None => true,
+    };
+    scheme_valid
+        && host_given
+        && !rest.is_empty()
+        && !rest.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// Whether `value` is a semantic version, e.g. `1.2.3` or `2.0.0-rc.1+build.5`.
+/// Without the `semver` feature, the same rules are checked by hand.
+#[cfg(feature = "semver")]
+pub(crate) fn is_semver(value: &str) -> bool {
+    semver::Version::parse(value).is_ok()
+}
+
+#[cfg(not(feature = "semver"))]
+pub(crate) fn is_semver(value: &str) -> bool {
+    // numbers have no leading zero, identifiers are made of alphanumerics and hyphens.
+    let number = |part: &str| {
+        !part.is_empty()
+            && part.bytes().all(|b| b.is_ascii_digit())
+            && (part == "0" || !part.starts_with('0'))
+    };
+    let identifiers = |text: &str, numbers_checked: bool| {
+        text.split('.').all(|part| {
+            !part.is_empty()
+                && part.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+                && (!numbers_checked || !part.bytes().all(|b| b.is_ascii_digit()) || number(part))
+        })
+    };
+    let (rest, build) = match value.split_once('+') {
+        Some((rest, build)) => (rest, Some(build)),
+        None => (value, None),
+    };
+    let (core, pre) = match rest.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (rest, None),
+    };
+    let parts: alloc::vec::Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|part| number(part))
+        && pre.is_none_or(|pre| identifiers(pre, true))
+        && build.is_none_or(|build| identifiers(build, false))
+}
+
+/// Whether `value` is a UUID, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+/// Without the `uuid` feature, only the hyphenated form is accepted.
+#[cfg(feature = "uuid")]
+pub(crate) fn is_uuid(value: &str) -> bool {
+    uuid::Uuid::parse_str(value).is_ok()
+}
+
+#[cfg(not(feature = "uuid"))]
+pub(crate) fn is_uuid(value: &str) -> bool {
+    let groups: alloc::vec::Vec<&str> = value.split('-').collect();
+    groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12])
+        && groups
+            .iter()
+            .all(|group| group.bytes().all(|b| b.is_ascii_hexdigit()))
+}
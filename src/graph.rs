@@ -0,0 +1,150 @@
+//! Export of the argument constraints as a [Graphviz](https://graphviz.org) DOT graph.
+//!
+//! Every argument is a box (bold when required) and every group an ellipse linked to its members.
+//! Constraints between arguments are drawn as edges: `conflicts` between the members of an
+//! exclusive group and `requires` from the members of an `allow_when` group to its parents,
+//! as well as the relations set on single arguments like [`crate::Arg::conflicts_with`].
+//! ```
+//! # use clarg::{Arg, ArgGroup, ArgParser};
+//! let parser = ArgParser::new("Find duplicate files.")
+//!     .arg(Arg::boolean("json", None, "Format output as JSON"))
+//!     .arg(Arg::boolean("yaml", None, "Format output as YAML"))
+//!     .add_group(ArgGroup::exclusive("format", false, &["json", "yaml"]));
+//! let dot = clarg::graph::render_dot(&parser);
+//! assert!(dot.contains("\"arg:json\" -> \"arg:yaml\""));
+//! ```
+//! The output can be rendered with `dot -Tsvg cli.dot -o cli.svg`.
+use std::fmt::Write;
+
+use crate::{ArgParser, GroupKind};
+
+/// Renders the arguments of `parser`, its groups and the constraints between them as a DOT graph.
+pub fn render_dot(parser: &ArgParser) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph {} {{", quote(&parser.executable));
+    let _ = writeln!(out, "    rankdir=LR;");
+    let _ = writeln!(out, "    node [shape=box];");
+    for arg in &parser.args {
+        let mut label = arg.synopsis().to_string();
+        if let Some(short) = arg.short_name {
+            label = format!("-{short}, {label}");
+        }
+        let style = if arg.required { ", style=bold" } else { "" };
+        let _ = writeln!(
+            out,
+            "    {} [label={}{style}];",
+            quote(&format!("arg:{}", arg.long_name)),
+            quote(&label)
+        );
+    }
+    for group in &parser.groups {
+        let id = quote(&format!("group:{}", group.name()));
+        let kind = match group.kind() {
+            GroupKind::Exclusive => "exclusive",
+            GroupKind::OnlyWhen => "allow when",
+            GroupKind::AnyOf => "any of",
+            GroupKind::AllOrNone => "all or none",
+        };
+        let required = if group.is_required() {
+            ", required"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "    {id} [shape=ellipse, label={}];",
+            quote(&format!("{}\n({kind}{required})", group.name()))
+        );
+        for member in group.args() {
+            let _ = writeln!(
+                out,
+                "    {id} -> {} [style=dotted, arrowhead=none];",
+                node(parser, member)
+            );
+        }
+        match group.kind() {
+            GroupKind::Exclusive => {
+                for (i, first) in group.args().iter().enumerate() {
+                    for second in &group.args()[i + 1..] {
+                        let _ = writeln!(
+                            out,
+                            "    {} -> {} [label=\"conflicts\", dir=both, color=red];",
+                            node(parser, first),
+                            node(parser, second)
+                        );
+                    }
+                }
+            }
+            GroupKind::OnlyWhen => {
+                for member in group.args() {
+                    for parent in group.parents() {
+                        let _ = writeln!(
+                            out,
+                            "    {} -> {} [label=\"requires\"];",
+                            node(parser, member),
+                            node(parser, parent)
+                        );
+                    }
+                }
+            }
+            GroupKind::AllOrNone => {
+                for (i, first) in group.args().iter().enumerate() {
+                    for second in &group.args()[i + 1..] {
+                        let _ = writeln!(
+                            out,
+                            "    {} -> {} [label=\"requires\", dir=both];",
+                            node(parser, first),
+                            node(parser, second)
+                        );
+                    }
+                }
+            }
+            // the group node already links its members.
+            GroupKind::AnyOf => {}
+        }
+    }
+    for arg in &parser.args {
+        let id = quote(&format!("arg:{}", arg.long_name));
+        for other in &arg.conflicts_with {
+            let _ = writeln!(
+                out,
+                "    {id} -> {} [label=\"conflicts\", dir=both, color=red];",
+                quote(&format!("arg:{other}"))
+            );
+        }
+        for other in &arg.requires {
+            let _ = writeln!(
+                out,
+                "    {id} -> {} [label=\"requires\"];",
+                quote(&format!("arg:{other}"))
+            );
+        }
+        for other in &arg.required_unless {
+            let _ = writeln!(
+                out,
+                "    {id} -> {} [label=\"required unless\", style=dashed];",
+                quote(&format!("arg:{other}"))
+            );
+        }
+    }
+    let _ = writeln!(out, "}}");
+    out
+}
+
+/// Node of `name`, a member of a group: the group of that name when groups are nested,
+/// otherwise the argument.
+fn node(parser: &ArgParser, name: &str) -> String {
+    match parser.group(name) {
+        Some(_) => quote(&format!("group:{name}")),
+        None => quote(&format!("arg:{name}")),
+    }
+}
+
+/// Quotes `value` as a DOT identifier, newlines become centered line breaks.
+fn quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
@@ -0,0 +1,92 @@
+//! Access to the environment the program runs in: environment variables, terminals and stdin.
+//! The parser asks a [`Host`] rather than the process itself, so tests can replace it with
+//! [`crate::testing::FakeHost`], see [`crate::ArgParser::host`].
+use std::{
+    ffi::OsString,
+    io::{self, IsTerminal},
+};
+
+use crate::terminal;
+
+/// Standard stream of the process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stream {
+    /// Standard input, where prompts are answered.
+    Stdin,
+    /// Standard output, where help and version are printed.
+    Stdout,
+    /// Standard error, where errors are printed.
+    Stderr,
+}
+
+/// Environment the parser reads environment variables, terminal properties and prompt answers from.
+pub trait Host: Send + Sync {
+    /// Value of the environment variable `name`, if set.
+    fn var(&self, name: &str) -> Option<OsString>;
+
+    /// Every environment variable, e.g. to detect unknown ones with [`crate::UnknownEnvPolicy`].
+    fn vars(&self) -> Vec<(OsString, OsString)>;
+
+    /// Whether `stream` is a terminal.
+    fn is_terminal(&self, stream: Stream) -> bool;
+
+    /// Number of columns of the terminal on stdout, if known.
+    fn terminal_width(&self) -> Option<usize>;
+
+    /// Line typed on stdin to answer a prompt, without its terminator.
+    /// `None` once stdin is closed.
+    fn read_line(&self) -> Option<String>;
+
+    /// Whole content of stdin, for values given as `-`, see [`crate::Arg::allow_stdin`].
+    /// # Errors
+    /// If stdin cannot be read or is not valid UTF-8.
+    fn read_stdin(&self) -> io::Result<String>;
+
+    /// Like [`Host::read_line`], without echoing what is typed, to answer the prompt of an
+    /// [`crate::Arg::secret`] argument. Echoes by default.
+    fn read_secret(&self) -> Option<String> {
+        self.read_line()
+    }
+}
+
+/// The process itself, used by default.
+pub(crate) struct System;
+
+impl Host for System {
+    fn var(&self, name: &str) -> Option<OsString> {
+        std::env::var_os(name)
+    }
+
+    fn vars(&self) -> Vec<(OsString, OsString)> {
+        std::env::vars_os().collect()
+    }
+
+    fn is_terminal(&self, stream: Stream) -> bool {
+        match stream {
+            Stream::Stdin => io::stdin().is_terminal(),
+            Stream::Stdout => io::stdout().is_terminal(),
+            Stream::Stderr => io::stderr().is_terminal(),
+        }
+    }
+
+    fn terminal_width(&self) -> Option<usize> {
+        terminal::width()
+    }
+
+    fn read_line(&self) -> Option<String> {
+        let mut line = String::new();
+        match io::stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim_end_matches(['\r', '\n']).to_owned()),
+        }
+    }
+
+    fn read_stdin(&self) -> io::Result<String> {
+        io::read_to_string(io::stdin())
+    }
+
+    /// `None` when the input can't be hidden.
+    fn read_secret(&self) -> Option<String> {
+        terminal::without_echo(|| self.read_line()).flatten()
+    }
+}
@@ -0,0 +1,47 @@
+//! Minimal JSON output helpers.
+#[cfg(feature = "std")]
+use alloc::{borrow::ToOwned, string::ToString};
+use alloc::{format, string::String};
+
+#[cfg(feature = "std")]
+use crate::ArgKind;
+
+/// Quotes `value` as a JSON string.
+pub(crate) fn string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A raw value as a JSON literal of the kind of its argument.
+/// Numbers are normalized, `+5`, `007` or `inf` are not valid JSON.
+#[cfg(feature = "std")]
+pub(crate) fn value(kind: ArgKind, value: &str) -> String {
+    let integer = value.parse::<i128>();
+    match kind {
+        // formatted from the integer itself, large values don't fit in a `f64`.
+        ArgKind::Integer | ArgKind::Unsigned | ArgKind::Count if integer.is_ok() => {
+            integer.unwrap_or_default().to_string()
+        }
+        ArgKind::Integer | ArgKind::Unsigned | ArgKind::Float | ArgKind::Count => {
+            match value.parse::<f64>() {
+                Ok(number) if number.is_finite() => number.to_string(),
+                _ => string(value),
+            }
+        }
+        ArgKind::Boolean if value.parse::<bool>().is_ok() => value.to_owned(),
+        _ => string(value),
+    }
+}
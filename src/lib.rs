@@ -1,7 +1,8 @@
 //! Crate to parse command line arguments and generate help/documentation for for the program.
 //!
 //! # Example
-//! ```no_run
+#![cfg_attr(feature = "std", doc = "```no_run")]
+#![cfg_attr(not(feature = "std"), doc = "```ignore")]
 //!# use clarg::{Arg, ArgGroup, ArgParser};
 //! let arguments = ArgParser::new("Find duplicate files.")
 //!         .arg(Arg::boolean("verbose", Some('V'), "verbose execution"))
@@ -16,49 +17,522 @@
 //! let json_output = arguments.get::<bool>("json").unwrap_or(false);
 //! ```
 //!
-//!
-use std::{collections::HashMap, process::exit, str::FromStr};
+//! Everything but [`scan`] needs the default `std` feature, see [`scan`] for targets without it.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    convert::Infallible,
+    ffi::{OsStr, OsString},
+    io::{self, Write},
+    ops::{Bound, ControlFlow, RangeBounds},
+    path::PathBuf,
+    process::exit,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "std")]
+mod cli;
+#[cfg(feature = "std")]
+mod commands;
+#[cfg(feature = "std")]
+pub mod common;
+#[cfg(feature = "std")]
+pub mod completions;
+#[cfg(feature = "std")]
+pub mod config;
+mod datetime;
+#[cfg(feature = "std")]
+mod definition;
+mod error;
+mod formats;
+#[cfg(feature = "std")]
+pub mod graph;
+#[cfg(feature = "std")]
+pub mod host;
+mod json;
+#[cfg(feature = "std")]
+mod markdown;
+#[cfg(feature = "std")]
+pub mod messages;
+mod numbers;
+#[cfg(feature = "std")]
+mod prompt;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "std")]
+mod response;
+pub mod scan;
+#[cfg(feature = "std")]
+pub mod schema;
+#[cfg(feature = "std")]
+pub mod selftest;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "std")]
+mod shell;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod styled;
+mod suggest;
+#[cfg(feature = "std")]
+mod terminal;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod text;
+mod units;
+#[cfg(feature = "std")]
+mod value;
+#[cfg(feature = "std")]
+pub mod wrapper;
+
+#[cfg(feature = "derive")]
+pub use clarg_derive::ClArgs;
+#[cfg(feature = "std")]
+pub use cli::Cli;
+#[cfg(feature = "std")]
+use config::ConfigSource;
+pub use error::{GetError, ParseError};
+#[cfg(feature = "std")]
+use host::{Host, Stream, System};
+#[cfg(feature = "std")]
+use messages::{English, Messages};
+#[cfg(feature = "std")]
+pub use numbers::Bounded;
+#[cfg(feature = "std")]
+use numbers::Limit;
+#[cfg(feature = "std")]
+pub use report::{ParseReport, ValueSource};
+#[cfg(feature = "std")]
+use scan::Known;
+#[cfg(feature = "std")]
+pub use stream::ArgStream;
+#[cfg(feature = "std")]
+use styled::{Style, Styled};
+#[cfg(feature = "std")]
+use value::Value;
+
+/// Support functions for the code generated by [`commands!`], not part of the public API.
+#[doc(hidden)]
+#[cfg(feature = "std")]
+pub mod __commands {
+    pub use crate::commands::{command_name, exit_with, split, subcommand, to_json};
+}
+
+/// Support functions for the code generated by `#[derive(ClArgs)]`, not part of the public API.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __derive {
+    use crate::{Arg, ArgMap};
+    use std::{process::exit, str::FromStr};
+
+    pub fn required<T: FromStr + 'static>(map: &ArgMap, name: &str) -> T {
+        map.get(name).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            exit(1)
+        })
+    }
+
+    pub fn optional<T: FromStr + 'static>(map: &ArgMap, name: &str) -> Option<T> {
+        map.has_arg(name).then(|| required(map, name))
+    }
+
+    pub fn many<T: FromStr + 'static>(map: &ArgMap, name: &str) -> Vec<T> {
+        if !map.has_arg(name) {
+            return Vec::new();
+        }
+        map.get_many(name).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            exit(1)
+        })
+    }
+
+    /// Lets the integer argument of an `i128` or `u128` field take values up to 128 bits.
+    pub fn wide(mut arg: Arg) -> Arg {
+        arg.wide = true;
+        arg
+    }
+}
 
+#[cfg(feature = "std")]
 const ARG_PADDING: usize = 9;
+/// Narrowest column descriptions are wrapped to, however narrow the terminal.
+#[cfg(feature = "std")]
+const MIN_DESCRIPTION_WIDTH: usize = 30;
+
+/// Conservative limit of the platform on the length of a command line, in bytes.
+/// Windows limits the whole command line to 32767 characters, Unix systems limit the size of the
+/// arguments and environment together, and Linux each argument to 128 KiB.
+#[cfg(feature = "std")]
+pub const MAX_COMMAND_LINE: usize = if cfg!(windows) { 32_767 } else { 131_072 };
+
+/// Replacement shown instead of the values of sensitive arguments.
+const MASK: &str = "******";
 /// Struct to represent the type of arguments that the user can pass to this program.
-#[derive(Default)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ArgKind {
     #[default]
     String,
+    /// Signed integer, up to the range of `i64`, or of `i128` when [`Arg::range`] goes beyond it.
     Integer,
+    /// Unsigned integer, up to the range of `u64`, or of `u128` when [`Arg::range`] goes beyond
+    /// it, e.g. sizes in bytes.
+    Unsigned,
     Float,
     Boolean,
+    /// Flag counting its occurrences, e.g. `-vvv` yields `3`. Never required.
+    Count,
+    /// File system path, retrieved with `ArgMap::get::<PathBuf>`.
+    /// See [`Arg::must_exist`], [`Arg::must_be_dir`] and [`Arg::must_be_file`].
+    Path,
+    /// Date and time, absolute like `2024-05-01`, `2024-05-01T12:00:00Z` or
+    /// `2024-05-01 12:00+02:00`, or relative like `now`, `yesterday`, `2h ago`, `now-30d` or
+    /// `today+9h`. Retrieved with [`ArgMap::get_time`] or [`ArgMap::get_timestamp`],
+    /// see [`ArgParser::clock`] and [`ArgParser::utc_offset`].
+    DateTime,
+    /// Length of time made of amounts with a unit among `ms`, `s`, `m`, `h` and `d`, e.g. `30s`,
+    /// `5m` or `2h30m`. Retrieved with [`ArgMap::get_duration`].
+    Duration,
+    /// Number of bytes, with an optional unit either decimal (`KB`, `MB`, `GB`, `TB`) or binary
+    /// (`KiB`, `MiB`, `GiB`, `TiB`, `PiB`), e.g. `10MB` or `1GiB`. Retrieved with `ArgMap::get::<u64>`.
+    Size,
+    /// IPv4 or IPv6 address, e.g. `127.0.0.1` or `::1`. Retrieved with `ArgMap::get::<IpAddr>`.
+    IpAddr,
+    /// IP address and port, e.g. `127.0.0.1:8080` or `[::1]:8080`.
+    /// Retrieved with `ArgMap::get::<SocketAddr>`.
+    SocketAddr,
+    /// Regular expression, e.g. `^v\d+$`. With the `regex` feature, values are compiled when
+    /// parsing and retrieved with `ArgMap::get::<regex::Regex>`. Without it, only brackets and
+    /// escapes are checked and values are retrieved as `String`.
+    Regex,
+    /// Absolute URL, e.g. `https://example.com/path`. With the `url` feature, values are fully
+    /// validated and retrieved with `ArgMap::get::<url::Url>`. Without it, only a scheme and the
+    /// absence of spaces are checked and values are retrieved as `String`.
+    Url,
+    /// Semantic version, e.g. `1.2.3` or `2.0.0-rc.1`. Validated by hand without the `semver`
+    /// feature, which allows retrieving values with `ArgMap::get::<semver::Version>`.
+    SemVer,
+    /// UUID, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`. With the `uuid` feature, the simple,
+    /// braced and URN forms are accepted too, and values are retrieved with
+    /// `ArgMap::get::<uuid::Uuid>`. Without it, only the hyphenated form is accepted.
+    Uuid,
+}
+
+impl core::fmt::Display for ArgKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            ArgKind::String => "string",
+            ArgKind::Integer => "integer",
+            ArgKind::Unsigned => "unsigned",
+            ArgKind::Float => "float",
+            ArgKind::Boolean => "boolean",
+            ArgKind::Count => "count",
+            ArgKind::Path => "path",
+            ArgKind::DateTime => "datetime",
+            ArgKind::Duration => "duration",
+            ArgKind::Size => "size",
+            ArgKind::IpAddr => "ip address",
+            ArgKind::SocketAddr => "socket address",
+            ArgKind::Regex => "regex",
+            ArgKind::Url => "url",
+            ArgKind::SemVer => "semver",
+            ArgKind::Uuid => "uuid",
+        };
+        f.write_str(name)
+    }
+}
+
+impl ArgKind {
+    /// Whether values are numbers accepting a range, see [`Arg::range`].
+    fn is_number(self) -> bool {
+        matches!(self, ArgKind::Integer | ArgKind::Unsigned | ArgKind::Float)
+    }
 }
 
 /// Struct representing the requirements for each argument passed to the program.
 /// Helpful when validating the type of arguments that the user passes to the program.
 #[derive(Default)]
+#[cfg(feature = "std")]
 pub struct Arg {
     long_name: String,
     short_name: Option<char>,
+    /// see [`Arg::short_alias`] and [`Arg::hidden_short_alias`].
+    short_aliases: Vec<char>,
+    hidden_short_aliases: Vec<char>,
     kind: ArgKind,
     required: bool,
     description: String,
-    scanned: bool,
+    /// detailed explanation, see [`Arg::long_help`].
+    long_help: Option<String>,
+    value_name: Option<String>,
+    multiple: bool,
+    default_value: Option<String>,
+    /// `(other, value, default)` rules, see [`Arg::default_value_if`].
+    default_if: Vec<(String, String, String)>,
+    /// computes the value when no source gave one, see [`Arg::required_or_else`].
+    default_with: Option<DefaultFn>,
+    choices: Vec<String>,
+    error_message: Option<String>,
+    sensitive: bool,
+    /// answers to prompts are not echoed, see [`Arg::secret`].
+    secret: bool,
+    prompt: Option<String>,
+    dedup: bool,
+    deny_duplicates: bool,
+    negatable: bool,
+    sorted: bool,
+    range: Option<NumericRange>,
+    /// integer values may take 128 bits, see [`Arg::range`].
+    wide: bool,
+    validators: Vec<Validator>,
+    positional: bool,
+    must_exist: bool,
+    must_be_dir: bool,
+    must_be_file: bool,
+    aliases: Vec<String>,
+    hidden_aliases: Vec<String>,
+    /// copied from the parser, see [`ArgParser::stdio_policy`].
+    stdio: StdioPolicy,
+    /// copied from the parser, see [`ArgParser::ignore_case`].
+    ignore_case: bool,
+    hidden: bool,
+    /// systems the argument exists on, all when empty.
+    only_on: Vec<Os>,
+    deprecated: Option<String>,
+    /// argument given the values instead, see [`Arg::deprecated_alias_of`].
+    alias_of: Option<String>,
+    rewrite: Option<Rewrite>,
+    out_of_range: OutOfRangePolicy,
+    on_repeat: RepeatPolicy,
+    required_unless: Vec<String>,
+    conflicts_with: Vec<String>,
+    /// see [`Arg::overrides_with`].
+    overrides_with: Vec<String>,
+    /// number of values accepted, see [`Arg::min_values`] and [`Arg::max_values`].
+    min_values: Option<usize>,
+    max_values: Option<usize>,
+    requires: Vec<String>,
+    examples: Vec<String>,
+    help_heading: Option<String>,
+    display_order: Option<u32>,
+    completion_command: Option<String>,
+    /// see [`Arg::value_hint`].
+    value_hint: Option<completions::ValueHint>,
+    allow_stdin: bool,
+    allow_file_ref: bool,
+    value_delimiter: Option<char>,
+    /// value when the option is given without one, see [`Arg::optional_value`].
+    optional_value: Option<String>,
+    /// the optional value is only given as `--name=value`, see [`Arg::require_equals`].
+    require_equals: bool,
+}
+
+/// What to do with a value outside the range set with [`Arg::range`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum OutOfRangePolicy {
+    /// The value is rejected.
+    #[default]
+    Error,
+    /// The value is replaced with the closest one in range, e.g. `--threads 10000` means the
+    /// maximum number of threads. A warning is recorded, see [`ArgMap::warnings`].
+    Clamp,
 }
 
+/// What to do when an option taking a single value is given several times on the command line,
+/// like `--path a --path b`, see [`Arg::on_repeat`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum RepeatPolicy {
+    /// The repetition is rejected.
+    #[default]
+    Error,
+    /// The last value wins, e.g. to override options set in a shell alias.
+    Overwrite,
+    /// Every value is kept, read with [`ArgMap::get_many`], while [`ArgMap::get`] returns the
+    /// last one. Unlike [`Arg::multiple`], each value needs its own option.
+    Append,
+}
+
+/// Which values win when combining two [`ArgMap`] holding the same argument, see [`ArgMap::merge`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum MergePolicy {
+    /// The values of the map merged into win.
+    Keep,
+    /// The values of the map merged in win.
+    #[default]
+    Overwrite,
+    /// The values of the map merged in win, unless they are defaults or computed values while
+    /// the others are not, e.g. so saved options are not reset by the defaults of a new run.
+    Explicit,
+    /// The values of both maps are kept in order, read with [`ArgMap::get_many`], while
+    /// [`ArgMap::get`] returns the last one.
+    Append,
+}
+
+/// Operating system an argument can be limited to, see [`Arg::only_on`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum Os {
+    Linux,
+    Mac,
+    Windows,
+    FreeBsd,
+}
+
+#[cfg(feature = "std")]
+impl Os {
+    /// The operating system the program runs on, `None` for others than those listed.
+    pub fn current() -> Option<Os> {
+        if cfg!(target_os = "linux") {
+            Some(Os::Linux)
+        } else if cfg!(target_os = "macos") {
+            Some(Os::Mac)
+        } else if cfg!(target_os = "windows") {
+            Some(Os::Windows)
+        } else if cfg!(target_os = "freebsd") {
+            Some(Os::FreeBsd)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for Os {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Os::Linux => "Linux",
+            Os::Mac => "macOS",
+            Os::Windows => "Windows",
+            Os::FreeBsd => "FreeBSD",
+        })
+    }
+}
+
+/// Range of values accepted by a numeric argument, see [`Arg::range`].
+#[derive(Clone, Copy)]
+#[cfg(feature = "std")]
+struct NumericRange {
+    start: Bound<Limit>,
+    end: Bound<Limit>,
+}
+
+#[cfg(feature = "std")]
+impl NumericRange {
+    /// Whether `value` is within the range, or not a number.
+    fn contains(&self, value: &str) -> bool {
+        let start = match self.start {
+            Bound::Included(start) => start.compare(value) != Some(Ordering::Less),
+            Bound::Excluded(start) => {
+                start.compare(value) != Some(Ordering::Less)
+                    && start.compare(value) != Some(Ordering::Equal)
+            }
+            Bound::Unbounded => true,
+        };
+        let end = match self.end {
+            Bound::Included(end) => end.compare(value) != Some(Ordering::Greater),
+            Bound::Excluded(end) => {
+                end.compare(value) != Some(Ordering::Greater)
+                    && end.compare(value) != Some(Ordering::Equal)
+            }
+            Bound::Unbounded => true,
+        };
+        start && end
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for NumericRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.start {
+            Bound::Included(start) => write!(f, "{start}")?,
+            // ranges can't exclude their start in Rust syntax, only shown for completeness.
+            Bound::Excluded(start) => write!(f, "{start}<")?,
+            Bound::Unbounded => {}
+        }
+        match self.end {
+            Bound::Included(end) => write!(f, "..={end}"),
+            Bound::Excluded(end) => write!(f, "..{end}"),
+            Bound::Unbounded => write!(f, ".."),
+        }
+    }
+}
+
+/// Check run on every value of an argument, see [`Arg::validator`].
+#[cfg(feature = "std")]
+type Validator = Box<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+#[cfg(feature = "std")]
+type DefaultFn = Box<dyn Fn() -> String + Send + Sync>;
+
+#[cfg(feature = "std")]
+type Rewrite = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+#[cfg(feature = "std")]
 impl Arg {
-    pub fn new() -> Arg {
-        Arg::default()
+    /// Creates a new optional string argument, to be refined with the chained setters.
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgKind};
+    /// let arg = Arg::new("path")
+    ///     .short('p')
+    ///     .kind(ArgKind::String)
+    ///     .required(true)
+    ///     .help("Directory to examine");
+    /// ```
+    pub fn new(long_name: &str) -> Arg {
+        Arg {
+            long_name: long_name.to_owned(),
+            ..Arg::default()
+        }
+    }
+
+    /// Set the single character representation for the argument.
+    pub fn short(mut self, short_name: char) -> Arg {
+        self.short_name = Some(short_name);
+        self
+    }
+
+    /// Set the type of values accepted by the argument.
+    pub fn kind(mut self, kind: ArgKind) -> Arg {
+        self.kind = kind;
+        self
+    }
+
+    /// Set whether this argument is required. Boolean arguments are always optional.
+    pub fn required(mut self, required: bool) -> Arg {
+        self.required = required;
+        self
+    }
+
+    /// Set the description for the argument, shown in the help page.
+    pub fn help(mut self, desc: &str) -> Arg {
+        self.description = desc.to_owned();
+        self
     }
+
     /// Boolean type of argument. This argument is always considered optional.
     /// # Arguments
     /// `long_name` Full name for the argument
     /// `short_name` Single character representation for the argument (optional)
     /// `desc` Description for the argument.
     pub fn boolean(name: &str, short_name: Option<char>, desc: &str) -> Arg {
-        let mut arg = Arg::new();
-        arg.long_name = name.to_owned();
-        arg.kind = ArgKind::Boolean;
-        arg.required = false;
-        arg.description = desc.to_owned();
-        arg.short_name = short_name;
-        arg
+        Arg::new(name)
+            .kind(ArgKind::Boolean)
+            .help(desc)
+            .short_opt(short_name)
     }
 
     /// String type of argument.
@@ -68,13 +542,11 @@ impl Arg {
     /// `required` set whether this argument required.
     /// `desc` Description for the argument.
     pub fn string(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
-        let mut arg = Arg::new();
-        arg.long_name = long_name.to_owned();
-        arg.short_name = short_name;
-        arg.kind = ArgKind::String;
-        arg.description = desc.to_owned();
-        arg.required = required;
-        arg
+        Arg::new(long_name)
+            .kind(ArgKind::String)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
     /// Integer type of argument.
     /// # Arguments
@@ -83,13 +555,25 @@ impl Arg {
     /// `required` set whether this argument required.
     /// `desc` Description for the argument.
     pub fn integer(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
-        let mut arg = Arg::new();
-        arg.long_name = long_name.to_owned();
-        arg.short_name = short_name;
-        arg.kind = ArgKind::Integer;
-        arg.description = desc.to_owned();
-        arg.required = required;
-        arg
+        Arg::new(long_name)
+            .kind(ArgKind::Integer)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
+    }
+
+    /// Unsigned integer type of argument, accepting values up to `u64::MAX`.
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn unsigned(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::Unsigned)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
 
     /// Floating point number type of argument.
@@ -98,490 +582,6386 @@ impl Arg {
     /// `short_name` Single character representation for the argument (optional)
     /// `required` set whether this argument required.
     /// `desc` Description for the argument.
-    pub fn float(long_name: &str, short_name: Option<char>, option: bool, desc: &str) -> Arg {
-        let mut arg = Arg::new();
-        arg.long_name = long_name.to_owned();
-        arg.short_name = short_name;
-        arg.kind = ArgKind::Float;
-        arg.description = desc.to_owned();
-        arg.required = option;
-        arg
+    pub fn float(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::Float)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
-}
 
-/// Wrapper around a map of arguments passed by the user.
-#[derive(Debug)]
-pub struct ArgMap {
-    inner: HashMap<String, String>,
-}
+    /// File system path type of argument.
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn path(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::Path)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
+    }
 
-impl ArgMap {
-    /// Get the value for a given argument if it exists and cast it to the type requested.
+    /// Secret string type of argument, e.g. a password or token. Its values are
+    /// [`Arg::sensitive`], and what the user types to answer its prompt is not echoed,
+    /// see [`Arg::prompt`] and [`ArgParser::prompt_missing`]. The prompt goes unanswered when
+    /// the terminal can't hide the input. Giving it on the command line,
+    /// where other users can see it with `ps`, prints a warning recorded in [`ArgMap::warnings`]:
+    /// secrets are meant to come from the environment, see [`ArgParser::env_prefix`], or a prompt.
     /// # Arguments
-    /// `name` name for the argument being requested.
-    /// # Returns
-    /// The given argument casted to the type `T`.
-    ///
-    /// # Errors
-    /// If the argument does not exist or cannot be casted into `T`.
-    pub fn get<T: FromStr>(&self, name: &str) -> Result<T, String> {
-        if let Some(value) = self.inner.get(name) {
-            value.parse::<T>().map_err(|_| {
-                format!(
-                    "Cannot convert value `{}` into type `{}`",
-                    value,
-                    std::any::type_name::<T>()
-                )
-            })
-        } else {
-            Err(format!("Inexistent `{name}` value requested."))
-        }
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn secret(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        let mut arg = Arg::string(long_name, short_name, required, desc).sensitive(true);
+        arg.secret = true;
+        arg
     }
-    /// Get the value for a given argument if it exists.
+
+    /// Date and time type of argument, see [`ArgKind::DateTime`] for the accepted values.
     /// # Arguments
-    /// `name` name for the argument being requested.
-    /// # Returns
-    ///
-    pub fn get_raw(&self, name: &str) -> Option<&String> {
-        self.inner.get(name)
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn datetime(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::DateTime)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
 
-    /// Check whether an argument was passed by the user.
-    pub fn has_arg(&self, name: &str) -> bool {
-        self.inner.contains_key(name)
+    /// Duration type of argument, see [`ArgKind::Duration`] for the accepted values.
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn duration(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::Duration)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
-}
 
-#[derive(Clone, Copy)]
-pub enum GroupKind {
-    Exclusive,
-    OnlyWhen,
-}
+    /// Size in bytes type of argument, see [`ArgKind::Size`] for the accepted values.
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn size(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::Size)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
+    }
 
-/// An argument group. Helps isolate arguments that only apply as combination.
-/// When using groups a requirement is implemented on the user to not use the same name for
-/// any of the arguments in the  group or outside it.
-pub struct ArgGroup {
-    name: String,
-    kind: GroupKind,
-    required: bool,
-    /// main arguments to match
-    args: Vec<String>,
+    /// IP address type of argument, see [`ArgKind::IpAddr`].
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn ip_addr(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::IpAddr)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
+    }
 
-    /// secondary arguments to match, see "OnlyIf"
-    parents: Vec<String>,
-}
+    /// Socket address type of argument, see [`ArgKind::SocketAddr`].
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn socket_addr(
+        long_name: &str,
+        short_name: Option<char>,
+        required: bool,
+        desc: &str,
+    ) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::SocketAddr)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
+    }
 
-impl ArgGroup {
-    fn new(name: &str, kind: GroupKind, args1: &[&str], args2: &[&str], required: bool) -> Self {
-        let args1: Vec<String> = args1.iter().map(|e| e.to_string()).collect();
-        let args2: Vec<String> = args2.iter().map(|e| e.to_string()).collect();
-        Self {
-            name: name.to_string(),
-            kind,
-            args: args1,
-            parents: args2,
-            required,
-        }
+    /// Regular expression type of argument, see [`ArgKind::Regex`].
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn regex(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::Regex)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
-    pub fn contains(&self, name: &String) -> bool {
-        self.args.contains(&name.to_owned())
+
+    /// URL type of argument, see [`ArgKind::Url`].
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn url(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::Url)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
-    pub fn kind(&self) -> GroupKind {
-        self.kind
+
+    /// Semantic version type of argument, see [`ArgKind::SemVer`].
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn semver(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::SemVer)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
-    pub fn name(&self) -> &String {
-        &self.name
+
+    /// UUID type of argument, see [`ArgKind::Uuid`].
+    /// # Arguments
+    /// `long_name` Full name for the argument
+    /// `short_name` Single character representation for the argument (optional)
+    /// `required` set whether this argument required.
+    /// `desc` Description for the argument.
+    pub fn uuid(long_name: &str, short_name: Option<char>, required: bool, desc: &str) -> Arg {
+        Arg::new(long_name)
+            .kind(ArgKind::Uuid)
+            .required(required)
+            .help(desc)
+            .short_opt(short_name)
     }
-    pub fn args(&self) -> &Vec<String> {
-        &self.args
+
+    /// Set the value used when the argument is not given on the command line or configuration file.
+    /// An argument with a default value is always satisfied, even when required.
+    pub fn default_value(mut self, value: &str) -> Arg {
+        self.default_value = Some(value.to_owned());
+        self
     }
-    pub fn parents(&self) -> &Vec<String> {
-        &self.parents
+
+    /// Set the value used by default when the argument `other` has the value `value`, given or
+    /// by default, e.g. an `--indent` of 2 for `--format json`. The first matching rule wins over
+    /// [`Arg::default_value`], which still applies when none matches. Booleans match `true`.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Export the records.")
+    ///     .arg(Arg::string("format", Some('f'), false, "Output format").choices(&["json", "csv"]))
+    ///     .arg(
+    ///         Arg::unsigned("indent", None, false, "Indentation width")
+    ///             .default_value_if("format", "json", "2")
+    ///             .default_value("0"),
+    ///     );
+    /// let args = parser.try_parse_from(["--format", "json"]).unwrap();
+    /// assert_eq!(args.get::<u64>("indent").unwrap(), 2);
+    /// let args = parser.try_parse_from(["--format", "csv"]).unwrap();
+    /// assert_eq!(args.get::<u64>("indent").unwrap(), 0);
+    /// ```
+    pub fn default_value_if(mut self, other: &str, value: &str, default: &str) -> Arg {
+        self.default_if
+            .push((other.to_owned(), value.to_owned(), default.to_owned()));
+        self
     }
-    pub fn is_required(&self) -> bool {
-        self.required
+
+    /// Compute the value at parse time when the argument is not given on the command line,
+    /// environment, configuration file, preset or prompt and has no [`Arg::default_value`],
+    /// e.g. detecting the number of CPUs or reading a lockfile. The argument always has a value,
+    /// and [`ArgMap::source`] reports [`ValueSource::Computed`] for it. The computed value is
+    /// validated like any other.
+    /// # Example
+    /// ```
+    /// use clarg::{Arg, ArgParser, ValueSource};
+    ///
+    /// let args = ArgParser::new("Build the project.")
+    ///     .arg(Arg::unsigned("jobs", Some('j'), false, "Parallel jobs").required_or_else(|| {
+    ///         std::thread::available_parallelism().map_or(1, usize::from).to_string()
+    ///     }))
+    ///     .try_parse_from([] as [&str; 0])
+    ///     .unwrap();
+    /// assert!(args.get::<u64>("jobs").unwrap() >= 1);
+    /// assert_eq!(args.source("jobs"), Some(ValueSource::Computed));
+    /// ```
+    pub fn required_or_else(mut self, compute: impl Fn() -> String + Send + Sync + 'static) -> Arg {
+        self.default_with = Some(Box::new(compute));
+        self
     }
-    /// Requires at least one of the items in the group arguments
-    /// to be present.
-    pub fn allow_when(name: &str, required: bool, args: &[&str], parents: &[&str]) -> Self {
-        ArgGroup::new(name, GroupKind::OnlyWhen, args, parents, required)
+
+    /// Restrict the values accepted by the argument to a fixed set.
+    /// The choices are listed in the help page as `--format <json|yaml|text>`, and any other
+    /// value is rejected with a message listing them. Pairs well with a user enum implementing
+    /// [`FromStr`] retrieved through [`ArgMap::get`].
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Convert files.")
+    ///     .arg(Arg::string("format", None, false, "Output format").choices(&["json", "yaml"]));
+    /// let error = parser.try_parse_from(["--format", "jsn"]).unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "Invalid value `jsn` for argument: --format. Valid choices are: [json, yaml]. Did you mean `json`?"
+    /// );
+    /// ```
+    pub fn choices(mut self, choices: &[&str]) -> Arg {
+        self.choices = choices.iter().map(|c| c.to_string()).collect();
+        self
     }
-    /// Requires at least one of the items in the group arguments
-    /// to be present.
-    pub fn exclusive(name: &str, required: bool, args: &[&str]) -> Self {
-        ArgGroup::new(name, GroupKind::Exclusive, args, &[], required)
+
+    /// Replace the generated message shown when a value for this argument fails validation,
+    /// e.g. `"PORT must be a number between 1 and 65535"`.
+    pub fn error_message(mut self, message: &str) -> Arg {
+        self.error_message = Some(message.to_owned());
+        self
     }
-}
 
-/// General argument parser.
-/// Created to avoid a dependency on CLAP which was used during prototyping.
-pub struct ArgParser {
-    executable: String,
-    description: String,
-    args: Vec<Arg>,
-    groups: Vec<ArgGroup>,
-}
-impl ArgParser {
-    /// Creates a new argument parser.
+    /// Mark the values of this argument (tokens, passwords, hostnames) as sensitive.
+    /// Sensitive values are masked in error messages, the help page and the `Debug` output
+    /// of [`ArgMap`], so logging the effective configuration can't leak them.
+    pub fn sensitive(mut self, sensitive: bool) -> Arg {
+        self.sensitive = sensitive;
+        self
+    }
+
+    /// Ask the user for the value on the terminal when it is not given on the command line,
+    /// environment or configuration file. An empty answer leaves the argument to its default value.
+    /// Answers are echoed, except for [`Arg::secret`] arguments. See [`ArgParser::prompt_timeout`] and
+    /// [`ArgParser::prompt_fallback`] for unattended runs.
     /// # Arguments
-    /// `description` Description/purpose of this executable.
-    pub fn new(description: &str) -> Self {
-        let executable = std::env::args()
-            .nth(0)
-            .expect("Executable name missing.")
-            .split(&['\\', '/'])
-            .last()
-            .unwrap()
-            .to_owned();
+    /// `message` Question shown to the user, e.g. `"Database password"`.
+    pub fn prompt(mut self, message: &str) -> Arg {
+        self.prompt = Some(message.to_owned());
+        self
+    }
+
+    /// Leave the argument out of the usage line, help page and shell completions, e.g. for
+    /// internal or debugging flags like `--internal-dump-state`. It is parsed as usual.
+    pub fn hidden(mut self, hidden: bool) -> Arg {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Name shown for the value in the usage line, help page, Markdown reference and zsh
+    /// completions, e.g. `"DIR"` for `--path <DIR>`, instead of the long name in upper case.
+    pub fn value_name(mut self, name: &str) -> Arg {
+        self.value_name = Some(name.to_owned());
+        self
+    }
+
+    /// Set a detailed explanation of the argument, possibly several paragraphs separated by blank
+    /// lines. It is shown by `--help <name>`, which describes the argument alone with its default,
+    /// environment variable and constraints, and under the argument by `--help --verbose`, while
+    /// the help page keeps the short description.
+    pub fn long_help(mut self, text: &str) -> Arg {
+        self.long_help = Some(text.to_owned());
+        self
+    }
+
+    /// Limit the argument to the operating systems `systems`, e.g. `&[Os::Linux, Os::Mac]` for an
+    /// option relying on signals. On other systems it is left out of the usage line, help page and
+    /// shell completions, it is never required, and using it is rejected with
+    /// "`--inotify` is not supported on Windows." rather than as an unknown option.
+    pub fn only_on(mut self, systems: &[Os]) -> Arg {
+        self.only_on = systems.to_vec();
+        self
+    }
+
+    /// Checks whether the argument exists on the running system, see [`Arg::only_on`].
+    fn is_supported(&self) -> bool {
+        self.only_on.is_empty() || Os::current().is_some_and(|os| self.only_on.contains(&os))
+    }
+
+    /// Mark the argument as deprecated: it is parsed as usual, but using it on the command line
+    /// prints a warning to stderr, once per run, and the help page tags it with `[deprecated]`.
+    /// # Arguments
+    /// `note` What to use instead, e.g. `"use --output instead"`.
+    pub fn deprecated(mut self, note: &str) -> Arg {
+        self.deprecated = Some(note.to_owned());
+        self
+    }
+
+    /// Mark the argument as a deprecated spelling of the argument named `name`, e.g. `--out`
+    /// replaced with `--output`. Its values given on the command line are handed over to `name`,
+    /// so the [`ArgMap`] only holds the new name, unless the new option is given as well, which
+    /// wins. It is deprecated with the note `use --<name> instead` unless [`Arg::deprecated`]
+    /// gave another one.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Compress files.")
+    ///     .arg(Arg::string("output", Some('o'), false, "Output file"))
+    ///     .arg(Arg::string("out", None, false, "Output file").hidden(true).deprecated_alias_of("output"));
+    /// let args = parser.try_parse_from(["--out", "a.zip"]).unwrap();
+    /// assert_eq!(args.get::<String>("output").unwrap(), "a.zip");
+    /// assert!(!args.has_arg("out"));
+    /// ```
+    pub fn deprecated_alias_of(mut self, name: &str) -> Arg {
+        if self.deprecated.is_none() {
+            self.deprecated = Some(format!("use --{name} instead"));
+        }
+        self.alias_of = Some(name.to_owned());
+        self
+    }
+
+    /// Like [`Arg::deprecated_alias_of`], converting each value with `rewrite` for the new
+    /// argument, e.g. a number of seconds for `--timeout-secs` into a duration for `--timeout`.
+    /// The converted values are checked like those of `name`.
+    pub fn deprecated_alias_of_with(
+        self,
+        name: &str,
+        rewrite: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Arg {
+        let mut arg = self.deprecated_alias_of(name);
+        arg.rewrite = Some(Box::new(rewrite));
+        arg
+    }
+
+    /// Require the argument unless the argument named `name` is used, e.g. `--output` unless
+    /// `--stdout`. Can be called several times, the argument is then required unless any of
+    /// them is used. The help page tags it with `[required unless: --stdout]`.
+    /// # Arguments
+    /// `name` Long name of the other argument.
+    pub fn required_unless(mut self, name: &str) -> Arg {
+        self.required_unless.push(name.to_owned());
+        self
+    }
+
+    /// Reject using the argument together with the argument named `name`, e.g. `--output` with
+    /// `--dry-run`. The help page tags it with `[conflicts with: --dry-run]`.
+    /// Finer grained than an [`ArgGroup::exclusive`] group for a single pair of arguments.
+    /// # Arguments
+    /// `name` Long name of the other argument.
+    pub fn conflicts_with(mut self, name: &str) -> Arg {
+        self.conflicts_with.push(name.to_owned());
+        self
+    }
+
+    /// Let the argument and the argument named `name` replace each other, the last one given
+    /// winning silently, e.g. `--plain` after `--json`. Unlike [`Arg::conflicts_with`], wrappers
+    /// and aliases can put their choice first and still let users change it at the end of the line.
+    /// # Arguments
+    /// `name` Long name of the other argument.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("List files.")
+    ///     .arg(Arg::boolean("json", None, "Format output as JSON").overrides_with("plain"))
+    ///     .arg(Arg::boolean("plain", None, "Format output as plain text"));
+    /// let args = parser.try_parse_from(["--json", "--plain"]).unwrap();
+    /// assert!(!args.has_arg("json"));
+    /// assert!(args.get::<bool>("plain").unwrap());
+    /// ```
+    pub fn overrides_with(mut self, name: &str) -> Arg {
+        self.overrides_with.push(name.to_owned());
+        self
+    }
+
+    /// Reject using the argument without the argument named `name`, e.g. `--upload` without
+    /// `--server`. The help page tags it with `[requires: --server]`.
+    /// Simpler than an [`ArgGroup::allow_when`] group for a single dependency.
+    /// # Arguments
+    /// `name` Long name of the other argument.
+    pub fn requires(mut self, name: &str) -> Arg {
+        self.requires.push(name.to_owned());
+        self
+    }
+
+    /// Complete the values of the argument with the lines printed by `command` when the user
+    /// presses tab, e.g. `"git branch --format=%(refname:short)"`. The command is run by the shell
+    /// at completion time, by `sh` for fish, Nushell and Elvish, and by `Invoke-Expression` for
+    /// PowerShell. [`Arg::choices`] take precedence, and a failing command offers nothing.
+    pub fn completion_command(mut self, command: &str) -> Arg {
+        self.completion_command = Some(command.to_owned());
+        self
+    }
+
+    /// Tell the completion scripts what the values are, so the shell offers files, directories,
+    /// host names, user names or commands, e.g. `ValueHint::Hostname` for `--server`.
+    /// Path arguments set with [`Arg::must_be_dir`] are completed with directories anyway.
+    /// [`Arg::completion_command`] takes precedence.
+    pub fn value_hint(mut self, hint: completions::ValueHint) -> Arg {
+        self.value_hint = Some(hint);
+        self
+    }
+
+    /// List the argument in the help page under its own `heading`, e.g. `"Network options"`,
+    /// instead of the `options:` or `arguments:` list. Headings are listed after the options,
+    /// in the order they are first used.
+    pub fn help_heading(mut self, heading: &str) -> Arg {
+        self.help_heading = Some(heading.to_owned());
+        self
+    }
+
+    /// Place the option in the help page by `order`, lower first. Options with an order come
+    /// before the others, which follow [`ArgParser::sort_help`]. Positional arguments keep the
+    /// order in which they are filled.
+    pub fn display_order(mut self, order: u32) -> Arg {
+        self.display_order = Some(order);
+        self
+    }
+
+    /// Add an example of use of the argument, listed under it in the help page, e.g.
+    /// `"--timeout 30"`. Can be called several times.
+    pub fn example(mut self, args: &str) -> Arg {
+        self.examples.push(args.to_owned());
+        self
+    }
+
+    /// Accept `--<name>` as another spelling of the argument, e.g. `colour` for `color`.
+    /// Values are stored under the long name of the argument, and the alias is listed in
+    /// the help page as `(alias: --colour)`.
+    pub fn alias(mut self, name: &str) -> Arg {
+        self.aliases.push(name.to_owned());
+        self
+    }
+
+    /// Accept `--<name>` as another spelling of the argument like [`Arg::alias`],
+    /// without listing it in the help page, e.g. to keep accepting a renamed option.
+    pub fn hidden_alias(mut self, name: &str) -> Arg {
+        self.hidden_aliases.push(name.to_owned());
+        self
+    }
+
+    /// Accept `-<c>` as another short name of the argument, e.g. `s` for `-q`, `--quiet`.
+    /// The alias is listed in the help page as `(alias: -s)`.
+    pub fn short_alias(mut self, c: char) -> Arg {
+        self.short_aliases.push(c);
+        self
+    }
+
+    /// Accept `-<c>` as another short name of the argument like [`Arg::short_alias`],
+    /// without listing it in the help page.
+    pub fn hidden_short_alias(mut self, c: char) -> Arg {
+        self.hidden_short_aliases.push(c);
+        self
+    }
+
+    /// Visible aliases with their dashes, short ones first, e.g. `["-s", "--colour"]`.
+    fn alias_names(&self) -> Vec<String> {
+        let shorts = self.short_aliases.iter().map(|c| format!("-{c}"));
+        shorts
+            .chain(self.aliases.iter().map(|alias| format!("--{alias}")))
+            .collect()
+    }
+
+    /// Visible aliases as shown after the long name in help and error messages,
+    /// e.g. ` (alias: --colour)`, empty without aliases.
+    fn alias_note(&self) -> String {
+        match self.alias_names().as_slice() {
+            [] => String::new(),
+            [alias] => format!(" (alias: {alias})"),
+            aliases => format!(" (aliases: {})", aliases.join(", ")),
+        }
+    }
+
+    /// Compares two names or choices, regardless of case following [`ArgParser::ignore_case`].
+    fn same(&self, a: &str, b: &str) -> bool {
+        if self.ignore_case {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    }
+
+    /// The choice `value` stands for, if any, e.g. `json` for `JSON` when case is ignored.
+    fn choice(&self, value: &str) -> Option<&String> {
+        self.choices.iter().find(|choice| self.same(choice, value))
+    }
+
+    fn short_opt(mut self, short_name: Option<char>) -> Arg {
+        self.short_name = short_name;
+        self
+    }
+
+    /// Allow the argument to collect several values, either by repeating it (`--include a --include b`)
+    /// or by listing the values after it (`--include a b c`).
+    /// Values are retrieved with [`ArgMap::get_many`]. Has no effect on boolean arguments.
+    pub fn multiple(mut self, multiple: bool) -> Arg {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Require at least `count` values of an argument set with [`Arg::multiple`] once it is used,
+    /// e.g. two points for `--polygon`. A positional argument needing values is required, and
+    /// shown as `<FILE>...` rather than `[FILE]...` in the usage.
+    /// The error has the code `E_TOO_FEW_VALUES`.
+    pub fn min_values(mut self, count: usize) -> Arg {
+        self.min_values = Some(count);
+        self
+    }
+
+    /// Accept at most `count` values of an argument set with [`Arg::multiple`].
+    /// The error has the code `E_TOO_MANY_VALUES`.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Merge files.")
+    ///     .arg(Arg::path("input", Some('i'), false, "Files to merge").multiple(true).max_values(2));
+    /// let error = parser.try_parse_from(["-i", "a", "b", "c"]).unwrap_err();
+    /// assert_eq!(error.to_string(), "Too many values for argument `input`: expected at most 2, got 3.");
+    /// ```
+    pub fn max_values(mut self, count: usize) -> Arg {
+        self.max_values = Some(count);
+        self
+    }
+
+    /// Accept `--no-<name>` to set a boolean argument to `false`, next to `--<name>` setting it
+    /// to `true`. Combined with a default value of `true`, the flag can be turned off explicitly.
+    /// Shown in the help page as `--[no-]<name>`. Has no effect on other kinds of arguments.
+    pub fn negatable(mut self, negatable: bool) -> Arg {
+        self.negatable = negatable;
+        self
+    }
+
+    /// Take the value of the argument from the bare words of the command line, in declaration
+    /// order, e.g. `FILE` in `mytool --verbose FILE`. A multi-value positional argument collects
+    /// every remaining bare word. Positional arguments take part in groups like options, so
+    /// `ArgGroup::exclusive("input", true, &["file", "stdin"])` makes `FILE` conflict with `--stdin`.
+    pub fn positional(mut self, positional: bool) -> Arg {
+        self.positional = positional;
+        self
+    }
+
+    /// Split the values given on the command line or in the environment at `delimiter`, so
+    /// `--include a,b,c` is stored like `--include a --include b --include c`. Empty items, as in
+    /// `a,,b`, are rejected. The argument takes multiple values, see [`Arg::multiple`].
+    /// Arrays of the configuration file are not split.
+    pub fn value_delimiter(mut self, delimiter: char) -> Arg {
+        self.value_delimiter = Some(delimiter);
+        self.multiple = true;
+        self
+    }
+
+    /// Let the option be given without a value, e.g. `--color` meaning `--color=auto` with
+    /// `default_when_present` set to `"auto"`. A value is given as `--color=never`, or as the next
+    /// word when it is one of the choices, so `--color FILE` leaves `FILE` to the positional
+    /// arguments. Shown in the help page as `--color[=<COLOR>]`.
+    /// Has no effect on boolean and count arguments.
+    pub fn optional_value(mut self, default_when_present: &str) -> Arg {
+        self.optional_value = Some(default_when_present.to_owned());
+        self
+    }
+
+    /// With [`Arg::optional_value`], only take a value given in the `--color=never` form, like
+    /// GNU tools do: the next word is never the value, even when it is one of the choices.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("List files.")
+    ///     .arg(
+    ///         Arg::string("color", None, false, "When to color")
+    ///             .choices(&["auto", "always", "never"])
+    ///             .optional_value("auto")
+    ///             .require_equals(true),
+    ///     )
+    ///     .arg(Arg::string("dir", None, false, "Directory to list").positional(true));
+    /// let args = parser.try_parse_from(["--color", "never"]).unwrap();
+    /// assert_eq!(args.get::<String>("color").unwrap(), "auto");
+    /// assert_eq!(args.get::<String>("dir").unwrap(), "never");
+    /// ```
+    pub fn require_equals(mut self, require: bool) -> Arg {
+        self.require_equals = require;
+        self
+    }
+
+    /// Collapse repeated identical values of a multi-value argument (`-I include -I include`),
+    /// keeping the first occurrence of each.
+    pub fn dedup(mut self, dedup: bool) -> Arg {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Reject repeated identical values of a multi-value argument instead of collapsing them.
+    pub fn deny_duplicates(mut self, deny: bool) -> Arg {
+        self.deny_duplicates = deny;
+        self
+    }
+
+    /// Require the values of a multi-value integer or float argument to be strictly increasing,
+    /// e.g. `--ports 80 443 8080`.
+    pub fn sorted(mut self, sorted: bool) -> Arg {
+        self.sorted = sorted;
+        self
+    }
+
+    /// Read the value from stdin when it is given as `-`, e.g. `--message -`.
+    /// The value is the content of stdin, without its final line break, checked like any other
+    /// value. Only one argument can read stdin per run. Default values are taken as is.
+    pub fn allow_stdin(mut self, allow: bool) -> Arg {
+        self.allow_stdin = allow;
+        self
+    }
+
+    /// Read the value from a file when it is given as `@path`, e.g. `--token @token.txt`.
+    /// The value is the content of the file, without its final line break, checked like any
+    /// other value. Default values are taken as is.
+    pub fn allow_file_ref(mut self, allow: bool) -> Arg {
+        self.allow_file_ref = allow;
+        self
+    }
+
+    /// Require the values of a path argument to exist when parsing.
+    pub fn must_exist(mut self, must_exist: bool) -> Arg {
+        self.must_exist = must_exist;
+        self
+    }
+
+    /// Require the values of a path argument to be existing directories.
+    /// Shell completions only offer directories.
+    pub fn must_be_dir(mut self, must_be_dir: bool) -> Arg {
+        self.must_be_dir = must_be_dir;
+        self
+    }
+
+    /// Require the values of a path argument to be existing files.
+    pub fn must_be_file(mut self, must_be_file: bool) -> Arg {
+        self.must_be_file = must_be_file;
+        self
+    }
+
+    /// Attach a check run on every value of the argument once its kind was verified.
+    /// The message returned on failure is reported along with the argument name,
+    /// unless replaced with [`Arg::error_message`]. Several validators run in the order added.
+    /// # Example
+    /// ```
+    /// # use clarg::Arg;
+    /// let arg = Arg::string("port", None, true, "Port to listen on")
+    ///     .validator(|v| v.parse::<u16>().map(|_| ()).map_err(|e| e.to_string()));
+    /// ```
+    pub fn validator<F>(mut self, validator: F) -> Arg
+    where
+        F: Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Select what happens when the option is given several times, rejected by default.
+    /// Has no effect on arguments taking multiple values, flags and positional arguments.
+    pub fn on_repeat(mut self, policy: RepeatPolicy) -> Arg {
+        self.on_repeat = policy;
+        self
+    }
+
+    /// Select what happens to values outside the range set with [`Arg::range`],
+    /// rejected by default.
+    pub fn out_of_range(mut self, policy: OutOfRangePolicy) -> Arg {
+        self.out_of_range = policy;
+        self
+    }
+
+    /// Restrict the values of an integer or float argument to a range, e.g. `1..=65535`,
+    /// `0.0..1.0` or `1..`. Every value of multi-value arguments is checked.
+    /// The range is shown in the help page.
+    ///
+    /// Bounds of any primitive number type are accepted and integers are compared exactly.
+    /// A bound beyond the range of `i64` and `u64` lets integer arguments take values up to
+    /// 128 bits, retrieved as `i128` or `u128`.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let port = Arg::integer("port", Some('p'), true, "Port to listen on").range(1..=65535);
+    /// let ratio = Arg::float("ratio", None, false, "Compression ratio").range(0.0..=1.0);
+    /// let size = Arg::unsigned("size", None, false, "Size in bytes").range(1..=u64::MAX);
+    /// let args = ArgParser::new("Fill a disk.")
+    ///     .arg(size)
+    ///     .try_parse_from(["--size", "18446744073709551615"])
+    ///     .unwrap();
+    /// assert_eq!(args.get::<u64>("size"), Ok(u64::MAX));
+    /// ```
+    pub fn range<T: Bounded>(mut self, range: impl RangeBounds<T>) -> Arg {
+        let bound = |bound: Bound<&T>| match bound {
+            Bound::Included(value) => Bound::Included(value.limit()),
+            Bound::Excluded(value) => Bound::Excluded(value.limit()),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+        let range = NumericRange {
+            start: bound(range.start_bound()),
+            end: bound(range.end_bound()),
+        };
+        self.wide = [range.start, range.end].iter().any(|bound| match bound {
+            Bound::Included(limit) | Bound::Excluded(limit) => limit.is_wide(),
+            Bound::Unbounded => false,
+        });
+        self.range = Some(range);
+        self
+    }
+
+    /// Sample usage of the argument as shown in help, e.g. `path <PATH>`, or `<PATH>` for positional arguments.
+    fn sample_usage(&self) -> Styled {
+        let mut usage = Styled::new();
+        let placeholder = format!("<{}>", self.placeholder());
+        match self.kind {
+            _ if self.positional => usage.placeholder(placeholder),
+            ArgKind::Boolean if self.negatable => usage.literal(format!("[no-]{}", self.long_name)),
+            ArgKind::Boolean | ArgKind::Count => usage.literal(&self.long_name),
+            _ if self.optional_value.is_some() => usage
+                .literal(&self.long_name)
+                .plain("[=")
+                .placeholder(placeholder)
+                .plain("]"),
+            _ => usage
+                .literal(&self.long_name)
+                .plain(" ")
+                .placeholder(placeholder),
+        };
+        if self.multiple && !matches!(self.kind, ArgKind::Boolean | ArgKind::Count) {
+            usage.plain("...");
+        }
+        usage
+    }
+
+    /// How the argument is written on the command line, e.g. `--path <PATH>` or `<PATH>`.
+    fn synopsis(&self) -> Styled {
+        let mut synopsis = Styled::new();
+        if !self.positional {
+            synopsis.literal("--");
+        }
+        synopsis.append(self.sample_usage());
+        synopsis
+    }
+
+    /// Name shown for the value of the argument, set with [`Arg::value_name`], the valid choices,
+    /// the kind for durations, sizes, addresses, URLs and UUIDs or the long name in upper case.
+    fn placeholder(&self) -> String {
+        match self.kind {
+            _ if self.value_name.is_some() => self.value_name.clone().unwrap_or_default(),
+            _ if !self.choices.is_empty() => self.choices.join("|"),
+            ArgKind::Duration => "DURATION".to_owned(),
+            ArgKind::Size => "SIZE".to_owned(),
+            ArgKind::IpAddr => "IP".to_owned(),
+            ArgKind::SocketAddr => "HOST:PORT".to_owned(),
+            ArgKind::Url => "URL".to_owned(),
+            ArgKind::Uuid => "UUID".to_owned(),
+            _ => self.long_name.to_uppercase(),
+        }
+    }
+
+    /// Checks whether `value` is acceptable for the kind of this argument, see [`Arg::check_content`].
+    /// References to stdin or a file are checked once read, see [`ArgParser::resolve_references`].
+    fn check_value(&self, value: &str) -> Result<(), ParseError> {
+        self.check_value_os(value, None)
+    }
+
+    /// Checks `value` like [`Arg::check_value`], looking for the path `original` rather than
+    /// `value` when it is given, the value as given when it is not valid UTF-8.
+    fn check_value_os(&self, value: &str, original: Option<&OsStr>) -> Result<(), ParseError> {
+        if self.reference(value).is_some() {
+            return Ok(());
+        }
+        self.check_content(value, original)
+    }
+
+    /// Items of `values` following [`Arg::value_delimiter`], `values` themselves without delimiter.
+    /// # Errors
+    /// A [`ParseError::ConstraintViolation`] for a value with an empty item.
+    fn split_items(&self, values: Vec<String>) -> Result<Vec<String>, ParseError> {
+        let Some(delimiter) = self.value_delimiter else {
+            return Ok(values);
+        };
+        let mut items = Vec::with_capacity(values.len());
+        for value in values {
+            if value.split(delimiter).any(str::is_empty) {
+                let shown = self.display_value(&value).to_owned();
+                return Err(ParseError::ConstraintViolation {
+                    arg: self.long_name.clone(),
+                    message: format!(
+                        "Empty item in value `{shown}` for argument: --{}{}, items are separated by `{delimiter}`.",
+                        self.long_name,
+                        self.alias_note()
+                    ),
+                    value: shown,
+                    code: "E_EMPTY_ITEM",
+                });
+            }
+            items.extend(value.split(delimiter).map(str::to_owned));
+        }
+        Ok(items)
+    }
+
+    /// Items of `values` following [`Arg::value_delimiter`], checked with [`Arg::check_value`].
+    /// # Errors
+    /// The first problem found.
+    fn checked_items(&self, values: Vec<String>) -> Result<Vec<String>, ParseError> {
+        let items = self.split_items(values)?;
+        match items.iter().find_map(|item| self.check_value(item).err()) {
+            Some(e) => Err(e),
+            None => Ok(items),
+        }
+    }
+
+    /// Source `value` refers to, following [`Arg::allow_stdin`] and [`Arg::allow_file_ref`].
+    fn reference<'v>(&self, value: &'v str) -> Option<Reference<'v>> {
+        match value.strip_prefix('@') {
+            _ if self.allow_stdin && value == "-" => Some(Reference::Stdin),
+            Some(path) if self.allow_file_ref && !path.is_empty() => Some(Reference::File(path)),
+            _ => None,
+        }
+    }
+
+    /// Checks whether `value` is acceptable for the kind of this argument.
+    /// # Errors
+    /// A [`ParseError::TypeMismatch`] or [`ParseError::InvalidChoice`] describing why the value
+    /// is rejected, carrying the custom message set with [`Arg::error_message`] if any.
+    fn check_content(&self, value: &str, original: Option<&OsStr>) -> Result<(), ParseError> {
+        if let Err(mut e) =
+            scan::check(&self.long_name, self.kind, value, self.wide, self.sensitive)
+        {
+            if let (ParseError::TypeMismatch { message, .. }, Some(custom)) =
+                (&mut e, &self.error_message)
+            {
+                message.clone_from(custom);
+            }
+            return Err(e);
+        }
+        if !self.choices.is_empty() && self.choice(value).is_none() {
+            // a suggestion close to a sensitive value would leak it.
+            let choices = self.choices.iter().map(String::as_str);
+            let suggestion = suggest::closest(value, choices)
+                .filter(|_| !self.sensitive)
+                .map(str::to_owned);
+            let hint = match &suggestion {
+                Some(choice) => format!(". Did you mean `{choice}`?"),
+                None => String::new(),
+            };
+            let shown = self.display_value(value).to_owned();
+            let message = self.error_message.clone().unwrap_or_else(|| {
+                format!(
+                    "Invalid value `{}` for argument: --{}{}. Valid choices are: [{}]{}",
+                    shown,
+                    self.long_name,
+                    self.alias_note(),
+                    self.choices.join(", "),
+                    hint
+                )
+            });
+            return Err(ParseError::InvalidChoice {
+                arg: self.long_name.clone(),
+                value: shown,
+                choices: self.choices.clone(),
+                suggestion,
+                message,
+            });
+        }
+        self.check_range(value)
+            .and_then(|_| self.check_path(value, original))
+            .and_then(|_| self.run_validators(value))
+    }
+
+    /// Checks whether a numeric `value` is within the range set with [`Arg::range`].
+    fn check_range(&self, value: &str) -> Result<(), ParseError> {
+        let Some(range) = &self.range else {
+            return Ok(());
+        };
+        if !self.kind.is_number()
+            || range.contains(value)
+            || self.out_of_range == OutOfRangePolicy::Clamp
+        {
+            return Ok(());
+        }
+        let shown = self.display_value(value).to_owned();
+        let message = self.error_message.clone().unwrap_or_else(|| {
+            format!(
+                "Value `{shown}` for argument: --{}{} is out of range {range}",
+                self.long_name,
+                self.alias_note()
+            )
+        });
+        let code = match self.kind {
+            ArgKind::Unsigned => "E_UINT_RANGE",
+            ArgKind::Float => "E_FLOAT_RANGE",
+            _ => "E_INT_RANGE",
+        };
+        Err(ParseError::ConstraintViolation {
+            arg: self.long_name.clone(),
+            value: shown,
+            code,
+            message,
+        })
+    }
+
+    /// Closest value to `value` within the range set with [`Arg::range`], following [`OutOfRangePolicy::Clamp`].
+    /// # Returns
+    /// The replacement, or `None` when `value` is in range or must not be clamped.
+    fn clamp(&self, value: &str) -> Option<String> {
+        let range = self.range.as_ref()?;
+        if self.out_of_range != OutOfRangePolicy::Clamp
+            || !self.kind.is_number()
+            || range.contains(value)
+        {
+            return None;
+        }
+        let integer = self.kind != ArgKind::Float;
+        let (start, end) = (range.start, range.end);
+        match (start, end) {
+            (Bound::Included(min) | Bound::Excluded(min), _)
+                if min.compare(value) != Some(Ordering::Greater) =>
+            {
+                let excluded = matches!(start, Bound::Excluded(_));
+                Some(min.nearest(Ordering::Greater, excluded, integer))
+            }
+            (_, Bound::Included(max) | Bound::Excluded(max)) => {
+                let excluded = matches!(end, Bound::Excluded(_));
+                Some(max.nearest(Ordering::Less, excluded, integer))
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks whether a path `value` meets the requirements set with [`Arg::must_exist`],
+    /// [`Arg::must_be_dir`] and [`Arg::must_be_file`], `original` being the path as given when
+    /// it is not valid UTF-8.
+    fn check_path(&self, value: &str, original: Option<&OsStr>) -> Result<(), ParseError> {
+        if self.kind != ArgKind::Path || self.stdio.is_stdio(value) {
+            return Ok(());
+        }
+        let path = std::path::Path::new(original.unwrap_or(OsStr::new(value)));
+        let (problem, code) =
+            if (self.must_exist || self.must_be_dir || self.must_be_file) && !path.exists() {
+                ("does not exist", "E_PATH_NOT_FOUND")
+            } else if self.must_be_dir && !path.is_dir() {
+                ("is not a directory", "E_PATH_NOT_DIR")
+            } else if self.must_be_file && !path.is_file() {
+                ("is not a file", "E_PATH_NOT_FILE")
+            } else {
+                return Ok(());
+            };
+        let shown = self.display_value(value).to_owned();
+        let message = self.error_message.clone().unwrap_or_else(|| {
+            format!(
+                "Path `{shown}` for argument: --{}{} {problem}.",
+                self.long_name,
+                self.alias_note()
+            )
+        });
+        Err(ParseError::ConstraintViolation {
+            arg: self.long_name.clone(),
+            value: shown,
+            code,
+            message,
+        })
+    }
+
+    /// Runs the validators set with [`Arg::validator`] on `value`.
+    fn run_validators(&self, value: &str) -> Result<(), ParseError> {
+        for validator in &self.validators {
+            if let Err(reason) = validator(value) {
+                let shown = self.display_value(value).to_owned();
+                let message = self.error_message.clone().unwrap_or_else(|| {
+                    format!(
+                        "Invalid value `{shown}` for argument: --{}{}: {reason}",
+                        self.long_name,
+                        self.alias_note()
+                    )
+                });
+                return Err(ParseError::ValidationFailed {
+                    arg: self.long_name.clone(),
+                    value: shown,
+                    message,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies the constraints on the whole list of values of a multi-value argument,
+    /// collapsing duplicates if requested.
+    /// # Errors
+    /// A [`ParseError::DuplicateValue`] or [`ParseError::ConstraintViolation`] for the first offending value.
+    fn check_values(&self, values: &mut Vec<String>) -> Result<(), ParseError> {
+        if self.deny_duplicates {
+            let mut seen = HashSet::new();
+            if let Some(value) = values
+                .iter()
+                .find(|value| !seen.insert(self.value_key(value)))
+            {
+                return Err(ParseError::DuplicateValue {
+                    arg: self.long_name.clone(),
+                    value: self.display_value(value).to_owned(),
+                });
+            }
+        } else if self.dedup {
+            let mut seen = HashSet::new();
+            values.retain(|value| seen.insert(self.value_key(value)));
+        }
+        if self.sorted && self.kind.is_number() {
+            let numbers: Vec<f64> = values.iter().filter_map(|v| v.parse().ok()).collect();
+            if let Some(i) = numbers.windows(2).position(|pair| pair[0] >= pair[1]) {
+                let (previous, value) = (&values[i], &values[i + 1]);
+                return Err(ParseError::ConstraintViolation {
+                    arg: self.long_name.clone(),
+                    value: self.display_value(value).to_owned(),
+                    code: "E_UNSORTED",
+                    message: format!(
+                        "Values for argument: --{}{} must be strictly increasing, `{}` follows `{}`.",
+                        self.long_name,
+                        self.alias_note(),
+                        self.display_value(value),
+                        self.display_value(previous)
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Value compared to find duplicates, numbers are compared by value so `080` repeats `80`.
+    fn value_key(&self, value: &str) -> String {
+        match self.kind {
+            // compared exactly, large integers don't fit in a `f64`.
+            ArgKind::Integer | ArgKind::Unsigned => value
+                .parse::<i128>()
+                .map_or_else(|_| value.to_owned(), |number| number.to_string()),
+            ArgKind::Float => value
+                .parse::<f64>()
+                .map_or_else(|_| value.to_owned(), |number| number.to_string()),
+            _ => value.to_owned(),
+        }
+    }
+
+    /// Value as it can be shown to the user, masked for sensitive arguments.
+    fn display_value<'a>(&self, value: &'a str) -> &'a str {
+        if self.sensitive {
+            MASK
+        } else {
+            value
+        }
+    }
+}
+
+/// Declaration of an argument, kept by [`ArgMap`] to export the values in declaration order.
+#[cfg(feature = "std")]
+struct Declared {
+    name: String,
+    kind: ArgKind,
+    multiple: bool,
+    negatable: bool,
+    positional: bool,
+}
+
+/// Wrapper around a map of arguments passed by the user.
+#[cfg(feature = "std")]
+pub struct ArgMap {
+    inner: HashMap<String, Vec<String>>,
+    /// declared names, shared with the errors of lookups, see [`GetError`].
+    names: HashSet<Arc<str>>,
+    /// lookups of undeclared names panic in debug builds, see [`ArgParser::strict_lookups`].
+    strict: bool,
+    /// values of `inner` converted according to the kind of their argument.
+    values: HashMap<String, Value>,
+    occurrences: HashMap<String, usize>,
+    sensitive: HashSet<String>,
+    trailing: Vec<String>,
+    /// arguments kept following [`UnknownArgPolicy`].
+    unknown: Vec<String>,
+    declared: Vec<Declared>,
+    sources: HashMap<String, ValueSource>,
+    /// values as given next to those of `inner`, for the arguments given values that are not
+    /// valid UTF-8, `None` for the values that are.
+    os_values: HashMap<String, Vec<Option<OsString>>>,
+    /// path arguments whose value stands for a standard stream.
+    stdio: HashSet<String>,
+    warnings: Vec<String>,
+    /// values given on the command line as (name, value, index), in the order given.
+    order: Vec<(String, String, usize)>,
+}
+
+/// Entries printed as a map in the order given.
+#[cfg(feature = "std")]
+struct OrderedMap<K, V>(Vec<(K, V)>);
+
+#[cfg(feature = "std")]
+impl<K: std::fmt::Debug, V: std::fmt::Debug> std::fmt::Debug for OrderedMap<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|(k, v)| (k, v)))
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Debug for ArgMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let inner = OrderedMap(
+            self.entries()
+                .map(|(arg, values)| (&arg.name, self.shown_values(&arg.name, values)))
+                .collect(),
+        );
+        let occurrences = OrderedMap(
+            self.declared
+                .iter()
+                .filter_map(|arg| Some((&arg.name, self.occurrences.get(&arg.name)?)))
+                .collect(),
+        );
+        f.debug_struct("ArgMap")
+            .field("inner", &inner)
+            .field("occurrences", &occurrences)
+            .field("trailing", &self.trailing)
+            .field("unknown", &self.unknown)
+            .finish()
+    }
+}
+
+/// Appends the short name of an option followed by `--`, e.g. `-h, --`, aligned when there is none.
+#[cfg(feature = "std")]
+fn option_name(line: &mut Styled, short_name: Option<char>) -> &mut Styled {
+    match short_name {
+        Some(c) => line.literal(format!("-{c}")).plain(","),
+        None => line.plain("   "),
+    };
+    line.plain(" ").literal("--")
+}
+
+#[cfg(feature = "std")]
+impl Known for Arg {
+    fn value_kind(&self) -> ArgKind {
+        self.kind
+    }
+
+    fn is_positional(&self) -> bool {
+        self.positional
+    }
+
+    /// Checks whether `--<name>` refers to this argument, through its long name or an alias.
+    fn answers_to(&self, name: &str) -> bool {
+        std::iter::once(&self.long_name)
+            .chain(&self.aliases)
+            .chain(&self.hidden_aliases)
+            .any(|own| self.same(own, name))
+    }
+
+    /// Checks whether `-<c>` refers to this argument, through its short name or a short alias.
+    fn answers_to_short(&self, c: char) -> bool {
+        self.short_name == Some(c)
+            || self.short_aliases.contains(&c)
+            || self.hidden_short_aliases.contains(&c)
+    }
+
+    /// Checks whether a command line token following the argument is one of its values rather
+    /// than the next option. Negative numbers like `-5` are values of integer and float arguments,
+    /// and `-` of path arguments when it stands for a standard stream.
+    fn takes_token(&self, token: &str) -> bool {
+        match self.kind {
+            ArgKind::Path if self.stdio.is_stdio(token) => true,
+            _ if self.allow_stdin && token == "-" => true,
+            kind => scan::takes(kind, token),
+        }
+    }
+}
+
+/// Entry of `key` in `map`, inserted with the default value when missing.
+/// Unlike [`HashMap::entry`], the key is only copied when inserted.
+#[cfg(feature = "std")]
+fn slot<'m, V: Default>(map: &'m mut HashMap<String, V>, key: &str) -> &'m mut V {
+    if !map.contains_key(key) {
+        map.insert(key.to_owned(), V::default());
+    }
+    map.get_mut(key).expect("inserted above")
+}
+
+/// `text` wrapped like [`wrap`], with every line indented and blank lines kept between paragraphs.
+#[cfg(feature = "std")]
+fn indented(text: &str, indent: usize, width: usize) -> String {
+    text.lines()
+        .map(|line| match line.trim() {
+            "" => "\n".to_owned(),
+            line => format!("{}{}\n", " ".repeat(indent), wrap(line, indent, width)),
+        })
+        .collect()
+}
+
+/// Wraps `text` with [`text::wrap`] so that, starting at column `indent`, no line goes past
+/// `width` columns unless a single word does. Line breaks of `text` are kept, and continuation
+/// lines are indented to `indent`.
+#[cfg(feature = "std")]
+fn wrap(text: &str, indent: usize, width: usize) -> String {
+    let available = width.saturating_sub(indent).max(MIN_DESCRIPTION_WIDTH);
+    text::wrap(text, available).join(&format!("\n{}", " ".repeat(indent)))
+}
+
+/// Help page laid out by `template`, see [`ArgParser::help_template`]. Placeholders are looked up
+/// with `section`, which gives `None` for unknown names. Sections lose their final line break so
+/// the template controls the spacing.
+#[cfg(feature = "std")]
+fn fill_template(template: &str, section: impl Fn(&str) -> Option<Styled>) -> Styled {
+    let mut help = Styled::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        let end = start + length + 1;
+        match section(&rest[start + 1..end - 1]) {
+            Some(text) => help.plain(&rest[..start]).append(text.trim_end()),
+            None => help.plain(&rest[..end]),
+        };
+        rest = &rest[end..];
+    }
+    help.plain(rest);
+    if !help.to_string().ends_with('\n') {
+        help.plain("\n");
+    }
+    help
+}
+
+/// Writes `text` to `stream` at once.
+#[cfg(feature = "std")]
+fn write_all(mut stream: impl Write, text: &str) -> io::Result<()> {
+    stream.write_all(text.as_bytes())?;
+    stream.flush()
+}
+
+/// Arguments in use with their number of occurrences when repeated, e.g. `--verbose (3 times), --quiet`.
+#[cfg(feature = "std")]
+fn counted(used: &[(String, usize)]) -> String {
+    let used: Vec<String> = used
+        .iter()
+        .map(|(name, count)| match count {
+            0 | 1 => format!("--{name}"),
+            count => format!("--{name} ({count} times)"),
+        })
+        .collect();
+    used.join(", ")
+}
+
+/// Long names as options, e.g. `--stdout, --quiet`.
+#[cfg(feature = "std")]
+fn dashed(names: &[String]) -> String {
+    let names: Vec<String> = names.iter().map(|name| format!("--{name}")).collect();
+    names.join(", ")
+}
+
+#[cfg(feature = "std")]
+impl ArgMap {
+    /// Name of the argument `name`, shared when declared.
+    fn name(&self, name: &str) -> Arc<str> {
+        self.names.get(name).cloned().unwrap_or_else(|| name.into())
+    }
+
+    /// Panics in debug builds on a name that was never declared, following
+    /// [`ArgParser::strict_lookups`].
+    fn check_declared(&self, name: &str) {
+        debug_assert!(
+            !self.strict || self.names.contains(name),
+            "`{name}` is not a declared argument"
+        );
+    }
+
+    fn missing(&self, name: &str) -> GetError {
+        self.check_declared(name);
+        GetError::Missing {
+            name: self.name(name),
+        }
+    }
+
+    fn invalid(&self, name: &str, type_name: &'static str) -> GetError {
+        GetError::Invalid {
+            name: self.name(name),
+            type_name,
+        }
+    }
+
+    /// Converts the raw value of the argument `name` into the type requested.
+    fn convert<T: FromStr>(&self, name: &str, value: &str) -> Result<T, GetError> {
+        value
+            .parse::<T>()
+            .map_err(|_| self.invalid(name, std::any::type_name::<T>()))
+    }
+
+    /// Get the value for a given argument if it exists and cast it to the type requested.
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// # Returns
+    /// The given argument casted to the type `T`.
+    ///
+    /// # Errors
+    /// If the argument does not exist or cannot be casted into `T`.
+    ///
+    /// For arguments accepting multiple values the last value is returned.
+    ///
+    /// Values are converted once, when parsing: reading an integer argument as any primitive
+    /// integer type, a float as `f64`, a boolean as `bool` or a string as `String` runs no
+    /// conversion, so `get` can be called in hot loops. Other types are converted from the raw
+    /// value on each call. Lookups don't allocate, errors included, see [`GetError`].
+    ///
+    /// A `PathBuf` or `OsString` is the value as given, even when it is not valid UTF-8, see
+    /// [`ArgParser::try_parse_from_os`].
+    pub fn get<T: FromStr + 'static>(&self, name: &str) -> Result<T, GetError> {
+        let value = match self.values.get(name) {
+            Some(Value::List(values)) => values.last(),
+            value => value,
+        };
+        if let Some(value) = value.and_then(Value::downcast) {
+            return Ok(value);
+        }
+        let original = self
+            .os_values
+            .get(name)
+            .and_then(|originals| originals.last()?.as_deref());
+        if let Some(value) = original.and_then(value::from_os) {
+            return Ok(value);
+        }
+        match self.get_raw(name) {
+            Some(value) => self.convert(name, value),
+            None => Err(self.missing(name)),
+        }
+    }
+
+    /// Get the value for a given argument, like [`ArgMap::get`], telling an argument that was not
+    /// given apart from one whose value cannot be converted.
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// # Returns
+    /// The given argument casted to the type `T`, or `None` when it has no value.
+    /// # Errors
+    /// If the value cannot be casted into `T`.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let args = ArgParser::new("Resize images.")
+    ///     .arg(Arg::string("width", None, false, "Width in pixels"))
+    ///     .try_parse_from(["--width", "wide"])
+    ///     .unwrap();
+    /// assert_eq!(args.get_opt::<u32>("height"), Ok(None));
+    /// assert!(args.get_opt::<u32>("width").is_err());
+    /// ```
+    pub fn get_opt<T: FromStr + 'static>(&self, name: &str) -> Result<Option<T>, GetError> {
+        match self.get(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(GetError::Missing { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get the value for a given argument, or `default` when it has no value. Unlike
+    /// `get(name).unwrap_or(default)`, a value that cannot be converted is still an error.
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// `default` value returned when the argument was not given.
+    /// # Errors
+    /// If the value cannot be casted into `T`.
+    pub fn get_or<T: FromStr + 'static>(&self, name: &str, default: T) -> Result<T, GetError> {
+        self.get_opt(name).map(|value| value.unwrap_or(default))
+    }
+
+    /// Get all the values for a given argument and cast them to the type requested.
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// # Returns
+    /// The values in the order they were given, casted to the type `T`.
+    ///
+    /// # Errors
+    /// If the argument does not exist or any of its values cannot be casted into `T`.
+    pub fn get_many<T: FromStr + 'static>(&self, name: &str) -> Result<Vec<T>, GetError> {
+        if let Some(Value::List(values)) = self.values.get(name) {
+            if let Some(values) = values.iter().map(Value::downcast).collect() {
+                return Ok(values);
+            }
+        }
+        let originals = self.os_values.get(name);
+        match self.inner.get(name) {
+            Some(values) => values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    let original = originals.and_then(|originals| originals[index].as_deref());
+                    match original.and_then(value::from_os) {
+                        Some(value) => Ok(value),
+                        None => self.convert(name, value),
+                    }
+                })
+                .collect(),
+            None => Err(self.missing(name)),
+        }
+    }
+
+    /// Get the value for a given argument if it exists.
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// # Returns
+    /// The raw value, or the last one for arguments accepting multiple values.
+    pub fn get_raw(&self, name: &str) -> Option<&String> {
+        self.check_declared(name);
+        self.inner.get(name).and_then(|values| values.last())
+    }
+
+    /// Get the value of a date and time argument, see [`ArgKind::DateTime`].
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// # Errors
+    /// If the argument does not exist or is not a date and time.
+    pub fn get_time(&self, name: &str) -> Result<SystemTime, GetError> {
+        let timestamp = self.get_timestamp(name)?;
+        let elapsed = Duration::from_secs(timestamp.unsigned_abs());
+        Ok(if timestamp < 0 {
+            UNIX_EPOCH - elapsed
+        } else {
+            UNIX_EPOCH + elapsed
+        })
+    }
+
+    /// Get the value of a date and time argument as seconds since the Unix epoch, negative
+    /// before 1970, see [`ArgKind::DateTime`].
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// # Errors
+    /// If the argument does not exist or is not a date and time.
+    pub fn get_timestamp(&self, name: &str) -> Result<i64, GetError> {
+        let value = self.get_raw(name).ok_or_else(|| self.missing(name))?;
+        match datetime::parse(value) {
+            Ok(moment @ datetime::Moment::Absolute { .. }) => {
+                Ok(datetime::resolve(moment, UNIX_EPOCH, 0))
+            }
+            _ => Err(self.invalid(name, "datetime")),
+        }
+    }
+
+    /// Checks whether the [`common::dry_run`] flag was given, `false` when the parser has none.
+    pub fn is_dry_run(&self) -> bool {
+        self.names.contains(common::DRY_RUN) && self.get::<bool>(common::DRY_RUN).unwrap_or(false)
+    }
+
+    /// Runs `action` unless the [`common::dry_run`] flag was given.
+    /// # Returns
+    /// The result of `action`, or `None` when it was skipped.
+    pub fn unless_dry_run<T>(&self, action: impl FnOnce() -> T) -> Option<T> {
+        if self.is_dry_run() {
+            None
+        } else {
+            Some(action())
+        }
+    }
+
+    /// Get the value of a duration argument, see [`ArgKind::Duration`].
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// # Errors
+    /// If the argument does not exist or is not a duration.
+    pub fn get_duration(&self, name: &str) -> Result<Duration, GetError> {
+        let value = self.get_raw(name).ok_or_else(|| self.missing(name))?;
+        units::duration(value).map_err(|_| self.invalid(name, "duration"))
+    }
+
+    /// Get the value for a given argument as given on the command line, without replacing
+    /// the parts that are not valid UTF-8 like [`ArgMap::get_raw`] does. Meant for paths.
+    /// # Arguments
+    /// `name` name for the argument being requested.
+    /// # Returns
+    /// The value, or the last one for arguments accepting multiple values.
+    pub fn get_os(&self, name: &str) -> Option<&OsStr> {
+        let value = self.get_raw(name)?;
+        let original = self
+            .os_values
+            .get(name)
+            .and_then(|originals| originals.last()?.as_deref());
+        Some(original.unwrap_or(OsStr::new(value)))
+    }
+
+    /// Check whether the values of an argument are marked as sensitive and must not be logged.
+    pub fn is_sensitive(&self, name: &str) -> bool {
+        self.sensitive.contains(name)
+    }
+
+    /// Number of times an argument was passed on the command line.
+    /// Values read from configuration files are not counted.
+    pub fn occurrences(&self, name: &str) -> usize {
+        self.occurrences.get(name).copied().unwrap_or(0)
+    }
+
+    /// Indices of the values of an argument on the command line, counted from 0 for the first
+    /// argument after the program name, once `@file` arguments are expanded. The value of
+    /// `-n5` or of a flag has the index of the option itself.
+    /// Values from other sources, like environment variables, have no index.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Link objects.")
+    ///     .arg(Arg::string("include", Some('i'), false, "Pattern to include").multiple(true))
+    ///     .arg(Arg::string("exclude", Some('x'), false, "Pattern to exclude").multiple(true));
+    /// let args = parser.try_parse_from(["-x", "*.o", "-ia.o", "-x", "b.o"]).unwrap();
+    /// assert_eq!(args.indices_of("exclude"), [1, 4]);
+    /// assert_eq!(args.indices_of("include"), [2]);
+    /// ```
+    pub fn indices_of(&self, name: &str) -> Vec<usize> {
+        self.check_declared(name);
+        self.order
+            .iter()
+            .filter(|(arg, ..)| arg == name)
+            .map(|&(_, _, index)| index)
+            .collect()
+    }
+
+    /// Values given on the command line as `(name, value, index)`, in the order they were given,
+    /// for tools whose options apply in sequence like `--include` and `--exclude`. Repeated
+    /// options are all listed even when only the last value is kept, flags with their state
+    /// (`"true"` or `"false"`) and counts with the total so far. See [`ArgMap::indices_of`]
+    /// for the index; maps combined with [`ArgMap::merge`] list the values of the other map last.
+    pub fn in_order(&self) -> impl Iterator<Item = (&str, &str, usize)> {
+        self.order
+            .iter()
+            .map(|(name, value, index)| (name.as_str(), value.as_str(), *index))
+    }
+
+    /// Where the value of an argument came from: the command line, an environment variable,
+    /// the configuration file, a prompt or its default value.
+    /// # Returns
+    /// The source, or `None` when the argument has no value.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser, ValueSource};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::integer("depth", Some('d'), false, "Maximum depth").default_value("4"));
+    /// let args = parser.try_parse_from(["--depth", "2"]).unwrap();
+    /// assert_eq!(args.source("depth"), Some(ValueSource::CommandLine));
+    /// let args = parser.try_parse_from::<_, &str>([]).unwrap();
+    /// assert_eq!(args.source("depth"), Some(ValueSource::Default));
+    /// ```
+    pub fn source(&self, name: &str) -> Option<ValueSource> {
+        self.sources.get(name).copied()
+    }
+
+    /// Problems that did not prevent parsing, like values clamped following [`OutOfRangePolicy::Clamp`]
+    /// or [`Arg::secret`] arguments given on the command line.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Check whether the value of a path argument stands for stdin or stdout rather than a file,
+    /// following [`ArgParser::stdio_policy`].
+    pub fn is_stdio(&self, name: &str) -> bool {
+        self.stdio.contains(name)
+    }
+
+    /// Arguments given after the `--` separator, verbatim and in order,
+    /// e.g. `["cargo", "build", "--release"]` for `mytool run -- cargo build --release`.
+    pub fn trailing(&self) -> &[String] {
+        &self.trailing
+    }
+
+    /// Arguments that don't match the definition, verbatim and in order, kept instead of
+    /// rejected following [`ArgParser::unknown_arg_policy`].
+    pub fn unknown(&self) -> &[String] {
+        &self.unknown
+    }
+
+    /// Rebuild an argument vector producing these values, e.g. to log the effective invocation.
+    /// Arguments are listed in declaration order, each value of multi-value arguments as a
+    /// separate option, followed by the values of positional arguments, the unknown arguments,
+    /// then `--` and the trailing arguments. Sensitive values are masked, see
+    /// [`ArgMap::to_args_unmasked`] to re-run the program.
+    pub fn to_args(&self) -> Vec<String> {
+        self.args_of(|_| true, true)
+    }
+
+    /// Like [`ArgMap::to_args`], with sensitive values as they are, e.g. to re-run the program.
+    /// Check [`ArgMap::is_sensitive`] before logging them.
+    pub fn to_args_unmasked(&self) -> Vec<String> {
+        self.args_of(|_| true, false)
+    }
+
+    /// Like [`ArgMap::to_args`], limited to the arguments for which `kept` is true, with
+    /// sensitive values `masked` or not.
+    fn args_of(&self, kept: impl Fn(&Declared) -> bool, masked: bool) -> Vec<String> {
+        let mut args = Vec::new();
+        let mut positionals = Vec::new();
+        for (arg, values) in self.entries().filter(|(arg, _)| kept(arg)) {
+            for value in values {
+                let value = match masked && self.is_sensitive(&arg.name) {
+                    true => MASK.to_owned(),
+                    false => value.clone(),
+                };
+                if arg.positional {
+                    positionals.push(value);
+                } else if arg.kind == ArgKind::Count {
+                    let count = value.parse::<usize>().unwrap_or(0);
+                    args.extend(std::iter::repeat_n(format!("--{}", arg.name), count));
+                } else if arg.kind == ArgKind::Boolean {
+                    if value == "true" {
+                        args.push(format!("--{}", arg.name));
+                    } else if arg.negatable {
+                        args.push(format!("--no-{}", arg.name));
+                    }
+                } else {
+                    args.push(format!("--{}", arg.name));
+                    args.push(value);
+                }
+            }
+        }
+        args.extend(positionals);
+        args.extend(self.unknown.iter().cloned());
+        if !self.trailing.is_empty() {
+            args.push("--".to_owned());
+            args.extend(self.trailing.iter().cloned());
+        }
+        args
+    }
+
+    /// Check whether the argument vector of [`ArgMap::to_args_unmasked`] fits within
+    /// [`MAX_COMMAND_LINE`], leaving room for a program name of `program_len` bytes.
+    pub fn fits_command_line(&self, program_len: usize) -> bool {
+        program_len + response::command_line_length(&self.to_args_unmasked()) <= MAX_COMMAND_LINE
+    }
+
+    /// Like [`ArgMap::to_args_unmasked`], but when the arguments don't fit within [`MAX_COMMAND_LINE`]
+    /// they are written to a response file in `dir` and replaced by a single `@<file>` argument.
+    /// The program receiving them must support response files, as compilers and linkers usually do.
+    /// Sensitive values are written as they are, in a file only its owner can read on Unix; on
+    /// other platforms `dir` should be private to the user, like the temporary directory of
+    /// Windows. The caller is responsible for removing the file once the program finished.
+    /// # Arguments
+    /// `program_len` Length of the program name preceding the arguments.
+    /// `dir` Directory for the response file, e.g. `std::env::temp_dir()`.
+    /// # Errors
+    /// If the response file cannot be written.
+    pub fn to_args_spilling(
+        &self,
+        program_len: usize,
+        dir: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<Vec<String>> {
+        let args = self.to_args_unmasked();
+        if program_len + response::command_line_length(&args) <= MAX_COMMAND_LINE {
+            return Ok(args);
+        }
+        let path = response::write(dir.as_ref(), &args)?;
+        Ok(vec![format!("@{}", path.display())])
+    }
+
+    /// Iterate over the arguments holding a value, given or default, as `(name, values)` pairs in
+    /// declaration order. Single-value arguments have one value. Values are raw, as returned by
+    /// [`ArgMap::get_raw`], and sensitive values are included as is: check [`ArgMap::is_sensitive`]
+    /// before logging them, or use [`ArgMap::to_json`] which masks them.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::integer("depth", Some('d'), false, "Maximum depth").default_value("3"))
+    ///     .arg(Arg::boolean("json", None, "Format output as JSON"));
+    /// let args = parser.try_parse_from(["--json"]).unwrap();
+    /// let settings: Vec<(&str, &[String])> = args.iter().collect();
+    /// assert_eq!(settings, [("depth", &["3".to_owned()][..]), ("json", &["true".to_owned()][..])]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.entries()
+            .map(|(arg, values)| (arg.name.as_str(), values.as_slice()))
+    }
+
+    /// Export the values as a JSON object keyed by long name, in declaration order, so exports
+    /// of identical runs are identical. Numbers and booleans are unquoted, multi-value arguments
+    /// are arrays, sensitive values are masked and the trailing arguments are left out.
+    /// With the `serde` feature, the map is serialized the same way by `serde::Serialize`.
+    pub fn to_json(&self) -> String {
+        let entries: Vec<String> = self
+            .entries()
+            .map(|(arg, values)| {
+                format!(
+                    "{}: {}",
+                    json::string(&arg.name),
+                    self.json_value(arg, values)
+                )
+            })
+            .collect();
+        format!("{{{}}}", entries.join(", "))
+    }
+
+    /// Every resolved value with its type and source, one argument per line, as printed for
+    /// `--clarg-debug`. Sensitive values are masked.
+    fn debug_dump(&self) -> String {
+        let entries: Vec<String> = self
+            .entries()
+            .map(|(arg, values)| {
+                let source = self
+                    .sources
+                    .get(&arg.name)
+                    .map_or("null".to_owned(), |source| {
+                        json::string(&source.to_string())
+                    });
+                format!(
+                    "  {}: {{\"value\": {}, \"type\": {}, \"source\": {source}}}",
+                    json::string(&arg.name),
+                    self.json_value(arg, values),
+                    json::string(&arg.kind.to_string())
+                )
+            })
+            .collect();
+        format!("{{\n{}\n}}\n", entries.join(",\n"))
+    }
+
+    /// Values of `arg` as JSON, an array for arguments taking multiple values.
+    fn json_value(&self, arg: &Declared, values: &[String]) -> String {
+        let values: Vec<String> = self
+            .shown_values(&arg.name, values)
+            .into_iter()
+            .map(|value| match self.is_sensitive(&arg.name) {
+                true => json::string(value),
+                false => json::value(arg.kind, value),
+            })
+            .collect();
+        if arg.multiple {
+            format!("[{}]", values.join(", "))
+        } else {
+            values.join("")
+        }
+    }
+
+    /// Declared arguments holding values, in declaration order.
+    fn entries(&self) -> impl Iterator<Item = (&Declared, &Vec<String>)> {
+        self.declared
+            .iter()
+            .filter_map(|arg| Some((arg, self.inner.get(&arg.name)?)))
+    }
+
+    /// Values as they can be shown to the user, masked for sensitive arguments.
+    fn shown_values<'a>(&self, name: &str, values: &'a [String]) -> Vec<&'a str> {
+        values
+            .iter()
+            .map(|value| match self.is_sensitive(name) {
+                true => MASK,
+                false => value.as_str(),
+            })
+            .collect()
+    }
+
+    /// Check whether an argument was passed by the user.
+    pub fn has_arg(&self, name: &str) -> bool {
+        self.inner.contains_key(name)
+    }
+
+    /// Combines the arguments of `other` with these, e.g. the arguments saved by a previous run
+    /// with the ones of this run. Arguments held by both maps are resolved following `policy`,
+    /// the others are kept along with their source. Trailing and unknown arguments are appended
+    /// for [`MergePolicy::Append`], and otherwise taken from the map whose values win, if any.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser, MergePolicy};
+    /// let parser = ArgParser::new("Sync folders.")
+    ///     .arg(Arg::string("remote", None, false, "Remote to sync with"))
+    ///     .arg(Arg::integer("jobs", Some('j'), false, "Parallel transfers").default_value("4"));
+    /// let saved = parser.try_parse_from(["--remote", "origin", "--jobs", "8"]).unwrap();
+    /// let args = saved.merge(parser.try_parse_from(["--remote", "backup"]).unwrap(), MergePolicy::Explicit);
+    /// assert_eq!(args.get::<String>("remote").unwrap(), "backup");
+    /// assert_eq!(args.get::<i64>("jobs").unwrap(), 8);
+    /// ```
+    pub fn merge(mut self, mut other: ArgMap, policy: MergePolicy) -> ArgMap {
+        let implicit = |source: Option<&ValueSource>| {
+            matches!(source, Some(ValueSource::Default | ValueSource::Computed))
+        };
+        for arg in std::mem::take(&mut other.declared) {
+            if !self.declared.iter().any(|known| known.name == arg.name) {
+                self.declared.push(arg);
+            }
+        }
+        for (name, raw) in std::mem::take(&mut other.inner) {
+            let replaced = match policy {
+                _ if !self.inner.contains_key(&name) => true,
+                MergePolicy::Keep => false,
+                MergePolicy::Overwrite | MergePolicy::Append => true,
+                MergePolicy::Explicit => {
+                    !implicit(other.sources.get(&name)) || implicit(self.sources.get(&name))
+                }
+            };
+            if !replaced {
+                continue;
+            }
+            if policy == MergePolicy::Append && self.inner.contains_key(&name) {
+                let count = other.occurrences.remove(&name).unwrap_or(0);
+                *self.occurrences.entry(name.clone()).or_default() += count;
+                let values = self.inner.entry(name.clone()).or_default();
+                // the values as given stay next to the values they stand for.
+                let theirs = other.os_values.remove(&name);
+                if theirs.is_some() || self.os_values.contains_key(&name) {
+                    let originals = self.os_values.entry(name.clone()).or_default();
+                    originals.resize(values.len(), None);
+                    originals.extend(theirs.unwrap_or_else(|| vec![None; raw.len()]));
+                }
+                values.extend(raw);
+                self.revalue(&name);
+            } else {
+                moved(&mut self.values, &mut other.values, &name);
+                moved(&mut self.occurrences, &mut other.occurrences, &name);
+                moved(&mut self.os_values, &mut other.os_values, &name);
+                self.inner.insert(name.clone(), raw);
+            }
+            // the source and the standard stream follow the last value.
+            moved(&mut self.sources, &mut other.sources, &name);
+            match other.stdio.contains(&name) {
+                true => self.stdio.insert(name),
+                false => self.stdio.remove(&name),
+            };
+        }
+        for (mine, theirs) in [
+            (&mut self.trailing, other.trailing),
+            (&mut self.unknown, other.unknown),
+        ] {
+            match policy {
+                MergePolicy::Append => mine.extend(theirs),
+                MergePolicy::Keep if !mine.is_empty() => {}
+                _ if !theirs.is_empty() => *mine = theirs,
+                _ => {}
+            }
+        }
+        self.names.extend(other.names);
+        self.strict |= other.strict;
+        self.sensitive.extend(other.sensitive);
+        self.warnings.extend(other.warnings);
+        self.order.extend(other.order);
+        self
+    }
+
+    /// Converts the raw values of `name` again after they changed.
+    fn revalue(&mut self, name: &str) {
+        let (Some(arg), Some(raw)) = (
+            self.declared.iter().find(|arg| arg.name == name),
+            self.inner.get(name),
+        ) else {
+            return;
+        };
+        match Value::parse_all(arg.kind, arg.multiple, raw) {
+            Some(value) => self.values.insert(name.to_owned(), value),
+            None => self.values.remove(name),
+        };
+    }
+}
+
+/// The end of `word` as given, when `value` is the end of `word` converted to UTF-8, e.g.
+/// the value of `--name=value`.
+#[cfg(feature = "std")]
+fn os_suffix(word: &OsStr, value: &str) -> Option<OsString> {
+    let lossy = word.to_string_lossy();
+    let start = lossy.strip_suffix(value)?;
+    let rest = word.as_encoded_bytes().strip_prefix(start.as_bytes())?;
+    // SAFETY: `rest` follows `start`, valid UTF-8, in bytes of `OsStr::as_encoded_bytes`.
+    Some(unsafe { OsStr::from_encoded_bytes_unchecked(rest) }.to_owned())
+}
+
+/// Moves the entry of `name` from one map to the other, removing it there when `from` has none.
+#[cfg(feature = "std")]
+fn moved<V>(into: &mut HashMap<String, V>, from: &mut HashMap<String, V>, name: &str) {
+    match from.remove(name) {
+        Some(value) => into.insert(name.to_owned(), value),
+        None => into.remove(name),
+    };
+}
+
+#[derive(Clone, Copy)]
+#[cfg(feature = "std")]
+pub enum GroupKind {
+    Exclusive,
+    OnlyWhen,
+    /// At least one of the arguments must be used, see [`ArgGroup::any_of`].
+    AnyOf,
+    /// Either every argument is used or none is, see [`ArgGroup::all_or_none`].
+    AllOrNone,
+}
+
+/// An argument group. Helps isolate arguments that only apply as combination.
+/// When using groups a requirement is implemented on the user to not use the same name for
+/// any of the arguments in the  group or outside it.
+#[cfg(feature = "std")]
+pub struct ArgGroup {
+    name: String,
+    kind: GroupKind,
+    required: bool,
+    /// main arguments to match
+    args: Vec<String>,
+
+    /// secondary arguments to match, see "OnlyIf"
+    parents: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl ArgGroup {
+    fn new(name: &str, kind: GroupKind, args1: &[&str], args2: &[&str], required: bool) -> Self {
+        let args1: Vec<String> = args1.iter().map(|e| e.to_string()).collect();
+        let args2: Vec<String> = args2.iter().map(|e| e.to_string()).collect();
+        Self {
+            name: name.to_string(),
+            kind,
+            args: args1,
+            parents: args2,
+            required,
+        }
+    }
+    pub fn contains(&self, name: &String) -> bool {
+        self.args.contains(&name.to_owned())
+    }
+    pub fn kind(&self) -> GroupKind {
+        self.kind
+    }
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    pub fn args(&self) -> &Vec<String> {
+        &self.args
+    }
+    pub fn parents(&self) -> &Vec<String> {
+        &self.parents
+    }
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+    /// Requires at least one of the items in the group arguments
+    /// to be present.
+    pub fn allow_when(name: &str, required: bool, args: &[&str], parents: &[&str]) -> Self {
+        ArgGroup::new(name, GroupKind::OnlyWhen, args, parents, required)
+    }
+    /// Requires at least one of the items in the group arguments
+    /// to be present.
+    pub fn exclusive(name: &str, required: bool, args: &[&str]) -> Self {
+        ArgGroup::new(name, GroupKind::Exclusive, args, &[], required)
+    }
+    /// Requires at least one of the group arguments to be present,
+    /// e.g. either `--file` or `--url`, or both.
+    pub fn any_of(name: &str, args: &[&str]) -> Self {
+        ArgGroup::new(name, GroupKind::AnyOf, args, &[], true)
+    }
+    /// Requires the group arguments to be used together or not at all,
+    /// e.g. `--user` and `--password`. When required, they must all be present.
+    pub fn all_or_none(name: &str, required: bool, args: &[&str]) -> Self {
+        ArgGroup::new(name, GroupKind::AllOrNone, args, &[], required)
+    }
+}
+
+/// A reusable bundle of arguments and groups, e.g. the logging options shared by every binary
+/// of a workspace, added to a parser with [`ArgParser::args`].
+/// # Example
+/// ```
+/// use clarg::{Arg, ArgGroup, ArgParser, ArgSet};
+///
+/// fn logging() -> ArgSet {
+///     ArgSet::new()
+///         .arg(Arg::boolean("verbose", Some('v'), "Print more details"))
+///         .arg(Arg::boolean("quiet", Some('q'), "Print only errors"))
+///         .arg(Arg::path("log-file", None, false, "Also write the logs to this file"))
+///         .group(ArgGroup::exclusive("verbosity", false, &["verbose", "quiet"]))
+/// }
+///
+/// let args = ArgParser::new("Sync two folders.")
+///     .args(logging())
+///     .try_parse_from(["-v"])
+///     .unwrap();
+/// assert!(args.get::<bool>("verbose").unwrap());
+/// ```
+#[derive(Default)]
+#[cfg(feature = "std")]
+pub struct ArgSet {
+    args: Vec<Arg>,
+    groups: Vec<ArgGroup>,
+}
+
+#[cfg(feature = "std")]
+impl ArgSet {
+    /// An empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an argument to the set.
+    pub fn arg(mut self, arg: Arg) -> Self {
+        self.args.push(arg);
+        self
+    }
+
+    /// Add a group of arguments to the set, its arguments may come from other sets.
+    pub fn group(mut self, group: ArgGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+}
+
+/// What to do with environment variables carrying the parser prefix that don't match any argument.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum UnknownEnvPolicy {
+    /// Unknown variables are silently ignored.
+    #[default]
+    Ignore,
+    /// A warning naming each unknown variable is printed to stderr.
+    Warn,
+    /// Unknown variables are a usage error.
+    Error,
+}
+
+/// What to do with command line arguments that match no argument of the parser, like an unknown
+/// `--option` or a word left over once every positional argument has its value.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum UnknownArgPolicy {
+    /// Unknown arguments are a usage error.
+    #[default]
+    Error,
+    /// Unknown arguments are kept in [`ArgMap::unknown`] and parsing goes on. The value of an
+    /// unknown option is parsed like any other word, e.g. as a positional argument.
+    Collect,
+    /// The first unknown argument and every argument after it are kept verbatim in
+    /// [`ArgMap::unknown`], like wrappers passing the rest of the command line to another program.
+    Passthrough,
+}
+
+/// Source a value refers to instead of being the value itself, see [`Arg::allow_stdin`]
+/// and [`Arg::allow_file_ref`].
+#[cfg(feature = "std")]
+enum Reference<'v> {
+    Stdin,
+    File(&'v str),
+}
+
+/// Items of the usage line, see [`ArgParser::synopsis_text`].
+#[cfg(feature = "std")]
+struct SynopsisParts {
+    /// whether there are optional options, shown as `[OPTIONS]`.
+    options: bool,
+    /// required groups, e.g. `(--json | --csv)`.
+    groups: Vec<Styled>,
+    /// required options, collapsed following [`ArgParser::collapse_usage`].
+    required: Vec<Styled>,
+    positionals: Vec<Styled>,
+}
+
+/// Outcome of scanning the command line arguments.
+#[cfg(feature = "std")]
+enum Scan {
+    Parsed(Box<ArgMap>),
+    /// Help was requested, optionally searching for a pattern, with the long help of the
+    /// arguments for `--help --verbose`.
+    Help {
+        search: Option<String>,
+        verbose: bool,
+    },
+    /// The version was requested, as JSON with `--version --format json` and with the details
+    /// of [`ArgParser::render_long_version`] for `--version --verbose`.
+    Version {
+        json: bool,
+        verbose: bool,
+    },
+    /// The resolved values were requested with `--clarg-debug`.
+    Debug(Box<ArgMap>),
+    /// Help was shown by the hook of [`ArgParser::on_help`], with the arguments given before it.
+    HelpShown(Box<ArgMap>),
+}
+
+/// State shared by the phases of [`ArgParser::run`].
+#[derive(Default)]
+#[cfg(feature = "std")]
+struct Scanning {
+    /// whether the environment and configuration files provide values.
+    sources: bool,
+    /// whether only the arguments of the parser are taken, see [`ArgParser::parse_known`].
+    known: bool,
+    /// whether help was shown by the hook of [`ArgParser::on_help`], which ends the scan.
+    help_shown: bool,
+    unknown_args: UnknownArgPolicy,
+    /// the words that are not valid UTF-8 as given, at their position on the command line.
+    originals: Vec<Option<OsString>>,
+    /// number of words of [`ArgParser::env_args`], scanned first.
+    injected: usize,
+    /// number of words of [`ArgParser::env_args`] and of the last run, scanned before the
+    /// command line.
+    replayed: usize,
+    argument_map: HashMap<String, Vec<String>>,
+    occurrences: HashMap<String, usize>,
+    /// arguments given a value by the user, either through the command line, environment or configuration.
+    scanned: HashMap<String, ValueSource>,
+    /// deprecated arguments already warned about.
+    warned: HashSet<String>,
+    /// arguments whose prompt was skipped, their default and requirement don't apply.
+    skipped: HashSet<String>,
+    /// arguments given their default, their preset value or a computed default.
+    filled: Vec<String>,
+    preset_filled: Vec<String>,
+    computed: Vec<String>,
+    profile: Option<String>,
+    preset: Option<String>,
+    debug: bool,
+    trailing: Vec<String>,
+    unknown: Vec<String>,
+    /// values given on the command line with their index, see [`ArgMap::in_order`].
+    order: Vec<(String, String, usize)>,
+    /// values taken from words that are not valid UTF-8, with the number of the word.
+    given: Vec<(String, String, usize)>,
+    errors: Vec<ParseError>,
+    warnings: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl Scanning {
+    /// The value as given, when it is the word numbered `at` or its end, as for `--name=value`.
+    fn original(&self, inner: &Arg, at: usize, value: &str) -> Option<OsString> {
+        let word = at
+            .checked_sub(self.replayed + 1)
+            .and_then(|index| self.originals.get(index))?
+            .as_deref()?;
+        // the items of a delimited value can't be told apart.
+        match inner.value_delimiter {
+            Some(_) => None,
+            None => os_suffix(word, value),
+        }
+    }
+}
+
+/// What the debug flag does once the values are printed, see [`ArgParser::enable_debug_dump`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum DebugAction {
+    /// The values are printed to stdout and the execution halts.
+    #[default]
+    Exit,
+    /// The values are printed to stderr and the program runs as if the flag was not given.
+    Continue,
+}
+
+/// What to do when a prompt set with [`Arg::prompt`] cannot be answered,
+/// because stdin is not a terminal (e.g. a cron job), it was closed or the timeout elapsed.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum PromptFallback {
+    /// The argument is a usage error, so unattended runs fail instead of hanging.
+    #[default]
+    Error,
+    /// The argument is left to its default value, and must have one if required.
+    Default,
+    /// The argument is left unset, ignoring its default value and whether it is required.
+    Skip,
+}
+
+/// Which values of path arguments name a standard stream instead of a file, see [`ArgParser::stdio_policy`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum StdioPolicy {
+    /// Every value is a path.
+    #[default]
+    Paths,
+    /// `-` stands for stdin or stdout, depending on whether the argument is read or written.
+    Dash,
+    /// `-` as well as the device names `/dev/stdin`, `/dev/stdout`, `/dev/stderr` and
+    /// `/dev/fd/0` to `/dev/fd/2`, which are recognized even on platforms lacking them.
+    DashAndDevices,
+}
+
+#[cfg(feature = "std")]
+impl StdioPolicy {
+    /// Checks whether `value` names a standard stream under this policy.
+    fn is_stdio(self, value: &str) -> bool {
+        const DEVICES: [&str; 6] = [
+            "/dev/stdin",
+            "/dev/stdout",
+            "/dev/stderr",
+            "/dev/fd/0",
+            "/dev/fd/1",
+            "/dev/fd/2",
+        ];
+        match self {
+            StdioPolicy::Paths => false,
+            StdioPolicy::Dash => value == "-",
+            StdioPolicy::DashAndDevices => value == "-" || DEVICES.contains(&value),
+        }
+    }
+}
+
+/// When output is colored, see [`ArgParser::color`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum ColorChoice {
+    /// Colors on terminals, unless `NO_COLOR` is set or `TERM` is `dumb`.
+    #[default]
+    Auto,
+    /// Colors even when redirected to a file or pipe.
+    Always,
+    /// Plain text.
+    Never,
+}
+
+#[cfg(feature = "std")]
+impl ColorChoice {
+    /// Whether output to `stream` of `host` is colored.
+    fn enabled(self, host: &dyn Host, stream: Stream) -> bool {
+        let set = |name: &str| host.var(name).is_some_and(|value| !value.is_empty());
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                host.is_terminal(stream)
+                    && !set("NO_COLOR")
+                    && host.var("TERM").is_none_or(|t| t != "dumb")
+            }
+        }
+    }
+}
+
+/// Order of the options in the help page, see [`ArgParser::sort_help`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum Sort {
+    /// In the order the arguments were added.
+    #[default]
+    Declaration,
+    /// By long name, regardless of case.
+    Alphabetical,
+}
+
+/// What [`ArgParser::parse`] prints after the message of a usage error.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum ErrorHelpStyle {
+    /// A one-line hint like `Try 'myapp --help' for more information.`, the usage line
+    /// when the built-in help is disabled.
+    Hint,
+    /// The usage line, followed by a hint to `--help` when the built-in help is enabled.
+    #[default]
+    Usage,
+    /// The whole help page.
+    FullHelp,
+}
+
+/// How [`ArgParser::parse`] prints usage errors, see [`ArgParser::error_format`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg(feature = "std")]
+pub enum ErrorFormat {
+    /// The message for people, followed by help following [`ErrorHelpStyle`].
+    #[default]
+    Text,
+    /// A single line of JSON on stderr for programs driving the CLI, see [`ParseError::to_json`].
+    /// Warnings are printed as JSON too, e.g. `{"warning": "`--fast` is deprecated, ..."}`, so
+    /// every line of stderr can be decoded.
+    Json,
+}
+
+/// General argument parser.
+/// Created to avoid a dependency on CLAP which was used during prototyping.
+#[cfg(feature = "std")]
+pub struct ArgParser {
+    executable: String,
+    description: String,
+    args: Vec<Arg>,
+    groups: Vec<ArgGroup>,
+    /// as given, `~` is expanded when the file is read, see [`ArgParser::config_file`].
+    config_file: Option<String>,
+    config_sources: Vec<Arc<dyn ConfigSource>>,
+    /// names selectable with `--preset`, in order of declaration, see [`ArgParser::preset`].
+    presets: Vec<String>,
+    preset_values: HashMap<String, Vec<(String, String)>>,
+    env_prefix: Option<String>,
+    /// variable holding default options, see [`ArgParser::env_args`].
+    env_args: Option<String>,
+    /// file holding the options of the last run, see [`ArgParser::remember_last`].
+    last_run: Option<PathBuf>,
+    response_files: bool,
+    unknown_env: UnknownEnvPolicy,
+    unknown_args: UnknownArgPolicy,
+    error_exit_code: u8,
+    max_args: Option<usize>,
+    max_value_length: Option<usize>,
+    max_values: Option<usize>,
+    show_error_codes: bool,
+    strict_lookups: bool,
+    error_format: ErrorFormat,
+    host: Arc<dyn Host>,
+    /// see [`ArgParser::messages`].
+    messages: Arc<dyn Messages>,
+    examples: Vec<String>,
+    prompt_timeout: Option<Duration>,
+    prompt_fallback: PromptFallback,
+    prompt_missing: bool,
+    version: Option<String>,
+    /// see [`ArgParser::commit`].
+    commit: Option<String>,
+    /// see [`ArgParser::long_version`].
+    long_version: Option<String>,
+    author: Option<String>,
+    license: Option<String>,
+    /// see [`ArgParser::long_about`].
+    long_about: Option<String>,
+    help_enabled: bool,
+    /// see [`ArgParser::help_short_alias`].
+    help_short_aliases: Vec<char>,
+    /// see [`ArgParser::disable_debug`] and [`ArgParser::enable_debug_dump`].
+    debug_enabled: bool,
+    debug_flag: String,
+    debug_action: DebugAction,
+    on_help: Option<HelpHook>,
+    on_arg: Option<ArgHook>,
+    /// checks of the parsed arguments as a whole, see [`ArgParser::validator`].
+    validators: Vec<ArgsValidator>,
+    on_output: Option<OutputHook>,
+    on_exit: Option<ExitHook>,
+    error_help_style: ErrorHelpStyle,
+    sort_help: Sort,
+    required_first: bool,
+    stdio: StdioPolicy,
+    ignore_case: bool,
+    slash_prefix: bool,
+    allow_abbrev: bool,
+    clock: Option<Clock>,
+    /// seconds from UTC.
+    utc_offset: i64,
+    color: ColorChoice,
+    max_help_width: usize,
+    collapse_usage: Option<usize>,
+    /// set by [`ArgParser::build`], parsing doesn't check the definition again.
+    validated: bool,
+    help_template: Option<String>,
+    after_help: Option<String>,
+    /// other words for the command, see [`ArgParser::keywords`].
+    keywords: Vec<String>,
+    category: Option<String>,
+    /// former names of the command, see [`ArgParser::renamed_from`].
+    renamed_from: Vec<String>,
+    command_deprecated: Option<String>,
+}
+
+/// Replacement for the built-in help page, see [`ArgParser::on_help`].
+#[cfg(feature = "std")]
+type HelpHook = Box<dyn Fn(&ArgParser) + Send + Sync>;
+/// Observer of the arguments in the order given, see [`ArgParser::on_arg`].
+#[cfg(feature = "std")]
+type ArgHook = Box<dyn Fn(&str, &str) + Send + Sync>;
+/// Check of the parsed arguments as a whole, see [`ArgParser::validator`].
+#[cfg(feature = "std")]
+type ArgsValidator = Box<dyn Fn(&ArgMap) -> Result<(), String> + Send + Sync>;
+/// Replacement for writing to stdout and stderr, see [`ArgParser::on_output`].
+#[cfg(feature = "std")]
+type OutputHook = Box<dyn Fn(Stream, &str) + Send + Sync>;
+/// Replacement for [`std::process::exit`], see [`ArgParser::on_exit`].
+#[cfg(feature = "std")]
+type ExitHook = Box<dyn Fn(i32) -> Infallible + Send + Sync>;
+/// Source of the current time, see [`ArgParser::clock`].
+#[cfg(feature = "std")]
+type Clock = Box<dyn Fn() -> SystemTime + Send + Sync>;
+
+/// Name of the program as invoked, e.g. `myapp` for `./bin/myapp/`, `C:\tools\myapp.exe`
+/// or `\\?\C:\tools\myapp.EXE`.
+/// Directories and trailing separators are dropped on every platform, as well as the extensions
+/// Windows adds to executables.
+#[cfg(feature = "std")]
+fn program_name(invoked: &str) -> String {
+    let file = invoked
+        .trim_end_matches(['\\', '/'])
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or_default();
+    match file.rsplit_once('.') {
+        Some((stem, extension))
+            if !stem.is_empty()
+                && ["exe", "com", "bat", "cmd"]
+                    .iter()
+                    .any(|windows| extension.eq_ignore_ascii_case(windows)) =>
+        {
+            stem.to_owned()
+        }
+        _ => file.to_owned(),
+    }
+}
+
+#[cfg(feature = "std")]
+impl ArgParser {
+    /// Creates a new argument parser.
+    /// # Arguments
+    /// `description` Description/purpose of this executable.
+    pub fn new(description: &str) -> Self {
+        let executable = std::env::args_os()
+            .next()
+            .map(|name| program_name(&name.to_string_lossy()))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "program".to_owned());
         Self {
             executable,
             description: description.to_owned(),
             args: Vec::new(),
             groups: Vec::new(),
+            config_file: None,
+            config_sources: Vec::new(),
+            presets: Vec::new(),
+            preset_values: HashMap::new(),
+            env_prefix: None,
+            env_args: None,
+            last_run: None,
+            response_files: false,
+            unknown_env: UnknownEnvPolicy::Ignore,
+            unknown_args: UnknownArgPolicy::Error,
+            error_exit_code: 1,
+            max_args: None,
+            max_value_length: None,
+            max_values: None,
+            show_error_codes: false,
+            strict_lookups: false,
+            error_format: ErrorFormat::Text,
+            host: Arc::new(System),
+            messages: Arc::new(English),
+            examples: Vec::new(),
+            prompt_timeout: None,
+            prompt_fallback: PromptFallback::Error,
+            prompt_missing: false,
+            version: None,
+            commit: None,
+            long_version: None,
+            author: None,
+            license: None,
+            long_about: None,
+            help_enabled: true,
+            help_short_aliases: Vec::new(),
+            debug_enabled: true,
+            debug_flag: "clarg-debug".to_owned(),
+            debug_action: DebugAction::Exit,
+            on_help: None,
+            on_arg: None,
+            validators: Vec::new(),
+            on_output: None,
+            on_exit: None,
+            error_help_style: ErrorHelpStyle::Usage,
+            sort_help: Sort::Declaration,
+            required_first: false,
+            stdio: StdioPolicy::Paths,
+            ignore_case: false,
+            slash_prefix: false,
+            allow_abbrev: false,
+            clock: None,
+            utc_offset: 0,
+            color: ColorChoice::Auto,
+            max_help_width: 100,
+            collapse_usage: None,
+            validated: false,
+            help_template: None,
+            after_help: None,
+            keywords: Vec::new(),
+            category: None,
+            renamed_from: Vec::new(),
+            command_deprecated: None,
+        }
+    }
+    /// Replace the program name shown in usage lines, help pages, completions and exports,
+    /// taken by default from the first command line argument. Useful to get stable output
+    /// in tests, or when the program runs through a wrapper script.
+    /// # Arguments
+    /// `name` Name shown as is, e.g. `myapp`.
+    pub fn executable_name(mut self, name: &str) -> Self {
+        self.executable = name.to_owned();
+        self
+    }
+
+    pub fn add_group(mut self, group: ArgGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    /// Read default values from a configuration file.
+    /// Keys in the file match the long names of the arguments, see [`config`] for the format.
+    /// Enables the `--profile <PROFILE>` option which selects a `[profiles.<name>]` section
+    /// to overlay the top level values. Values passed on the command line always take precedence.
+    /// A missing file is not an error, it simply provides no values.
+    /// # Arguments
+    /// `path` Location of the configuration file, a leading `~` stands for the home directory,
+    /// e.g. `~/.myapprc`.
+    pub fn config_file(mut self, path: &str) -> Self {
+        self.config_file = Some(path.to_owned());
+        self
+    }
+
+    /// Read values from another configuration backend, like the Windows registry or a key-value
+    /// store. Sources are consulted after the command line, the environment and the
+    /// [`ArgParser::config_file`], in the order they were added.
+    pub fn config_source(mut self, source: impl ConfigSource + 'static) -> Self {
+        self.config_sources.push(Arc::new(source));
+        self
+    }
+
+    /// Declare a set of values selected with `--preset <name>`, e.g. to bundle tuning options.
+    /// The values of the preset take the place of the defaults, so the command line, environment
+    /// and configuration still override them. Declaring a name again replaces its values.
+    /// # Arguments
+    /// `name` Name given to `--preset`.
+    /// `values` Pairs of argument long name and value.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Compress files.")
+    ///     .arg(Arg::integer("threads", None, false, "Worker threads").default_value("4"))
+    ///     .arg(Arg::integer("compression", None, false, "Compression level").default_value("6"))
+    ///     .preset("fast", &[("threads", "16"), ("compression", "0")]);
+    /// let args = parser.try_parse_from(["--preset", "fast", "--threads", "8"].map(String::from)).unwrap();
+    /// assert_eq!(args.get::<i32>("threads"), Ok(8));
+    /// assert_eq!(args.get::<i32>("compression"), Ok(0));
+    /// ```
+    pub fn preset(mut self, name: &str, values: &[(&str, &str)]) -> Self {
+        if !self.presets.iter().any(|preset| preset == name) {
+            self.presets.push(name.to_owned());
+        }
+        let values = values
+            .iter()
+            .map(|(arg, value)| ((*arg).to_owned(), (*value).to_owned()))
+            .collect();
+        self.preset_values.insert(name.to_owned(), values);
+        self
+    }
+
+    /// Read values from environment variables named after the arguments.
+    /// The variable for an argument is the prefix followed by `_` and the long name in upper case
+    /// with dashes replaced by underscores, e.g. `MYAPP_LOG_LEVEL` for `--log-level`.
+    /// Environment values take precedence over configuration files but not over the command line.
+    /// # Arguments
+    /// `prefix` Prefix for the variable names, e.g. `MYAPP`.
+    pub fn env_prefix(mut self, prefix: &str) -> Self {
+        self.env_prefix = Some(prefix.to_owned());
+        self
+    }
+
+    /// Read default options from the environment variable `variable`, e.g.
+    /// `MYAPP_FLAGS="--color never -v"`, like ripgrep or fd users expect. The value is split like
+    /// a shell would, quotes included, and placed before the command line, so options given on the
+    /// command line override them, and [`ArgMap::source`] reports [`ValueSource::Environment`].
+    /// # Example
+    /// ```
+    /// # use clarg::{testing::FakeHost, Arg, ArgParser, ValueSource};
+    /// let parser = ArgParser::new("Search files.")
+    ///     .arg(Arg::string("color", None, false, "When to color").choices(&["auto", "never"]))
+    ///     .arg(Arg::boolean("verbose", Some('v'), "Verbose execution"))
+    ///     .env_args("SEARCH_FLAGS")
+    ///     .host(FakeHost::new().var("SEARCH_FLAGS", "--color never -v"));
+    /// let args = parser.try_parse_from(["--color", "auto"]).unwrap();
+    /// assert_eq!(args.get::<String>("color").unwrap(), "auto");
+    /// assert_eq!(args.source("verbose"), Some(ValueSource::Environment));
+    /// ```
+    pub fn env_args(mut self, variable: &str) -> Self {
+        self.env_args = Some(variable.to_owned());
+        self
+    }
+
+    /// Replace `@file` arguments with the arguments listed in the file, separated by spaces or
+    /// line breaks and quoted following POSIX shell rules, like compilers and linkers do to get
+    /// around the limits of the platform on the length of a command line, see [`MAX_COMMAND_LINE`].
+    /// Response files can name other response files, up to 8 levels deep. Values of
+    /// [`Arg::allow_file_ref`] options are left as they are, and so is everything after `--`.
+    /// Files that cannot be read are reported with the code `E_RESPONSE_FILE`.
+    pub fn response_files(mut self, enable: bool) -> Self {
+        self.response_files = enable;
+        self
+    }
+
+    /// Save the options of every successful parse to the file at `path`, and accept the built-in
+    /// `--again` flag, which applies them again, e.g. `mytool --again --threads 8` repeats the
+    /// last run with another number of threads. Options given along with `--again` override the
+    /// saved ones, whose source is [`ValueSource::LastRun`]. Values read from the environment or
+    /// configuration files, defaults and sensitive arguments are not saved. A file that cannot
+    /// be written is reported in [`ArgMap::warnings`], `--again` without a saved run is an error.
+    pub fn remember_last(mut self, path: impl Into<PathBuf>) -> Self {
+        self.last_run = Some(path.into());
+        self
+    }
+
+    /// Select what happens when an environment variable starting with the prefix set with
+    /// [`ArgParser::env_prefix`] does not match any argument, so typos like `MYAPP_TIMEOUTT=5`
+    /// don't silently do nothing.
+    pub fn unknown_env_policy(mut self, policy: UnknownEnvPolicy) -> Self {
+        self.unknown_env = policy;
+        self
+    }
+
+    /// Select what happens with command line arguments that match no argument, rejected by default.
+    /// Parsers of the commands of [`commands!`] each have their own policy, so a wrapper command
+    /// like `mytool exec` can pass arguments through while the other commands stay strict.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser, UnknownArgPolicy};
+    /// let parser = ArgParser::new("Run a program in the sandbox.")
+    ///     .arg(Arg::boolean("network", Some('n'), "Allow network access"))
+    ///     .unknown_arg_policy(UnknownArgPolicy::Passthrough);
+    /// let arguments = parser.try_parse_from(["-n", "cargo", "build", "--release"]).unwrap();
+    /// assert_eq!(arguments.unknown(), ["cargo", "build", "--release"]);
+    /// ```
+    pub fn unknown_arg_policy(mut self, policy: UnknownArgPolicy) -> Self {
+        self.unknown_args = policy;
+        self
+    }
+
+    /// Collect the unrecognized options and bare words into [`ArgMap::unknown`] instead of
+    /// rejecting them, e.g. to forward them to a child process. Shorthand for
+    /// [`UnknownArgPolicy::Collect`], or [`UnknownArgPolicy::Error`] when `allow` is `false`.
+    pub fn allow_unknown(self, allow: bool) -> Self {
+        self.unknown_arg_policy(match allow {
+            true => UnknownArgPolicy::Collect,
+            false => UnknownArgPolicy::Error,
+        })
+    }
+
+    /// Select the values of path arguments standing for stdin or stdout, like `-` in
+    /// `mytool --input - --output report.txt`, so every path argument of the tool follows the same
+    /// convention. Such values skip the checks of [`Arg::must_exist`] and friends, and are told
+    /// apart with [`ArgMap::is_stdio`].
+    pub fn stdio_policy(mut self, policy: StdioPolicy) -> Self {
+        self.stdio = policy;
+        for arg in &mut self.args {
+            arg.stdio = policy;
+        }
+        self
+    }
+
+    /// Match long options and choices regardless of case, so `--Verbose` and `--VERBOSE` stand
+    /// for `--verbose` and `--format JSON` is stored as the declared choice `json`. Short options
+    /// and the built-in options keep their case. Names differing only in case are reported by
+    /// [`ArgParser::validate`].
+    pub fn ignore_case(mut self, ignore: bool) -> Self {
+        self.ignore_case = ignore;
+        for arg in &mut self.args {
+            arg.ignore_case = ignore;
+        }
+        self
+    }
+
+    /// Accept options spelled the Windows way, with a slash: `/verbose` or `/v` for `--verbose`
+    /// or `-v`, and `/?` for `--help`, e.g. to keep the scripts of a legacy tool working.
+    /// Values follow the option as usual, like `/path C:\dir`. Only declared names are
+    /// translated, other words starting with a slash, like Unix paths, are left as they are.
+    pub fn allow_slash_prefix(mut self, allow: bool) -> Self {
+        self.slash_prefix = allow;
+        self
+    }
+
+    /// Accept unambiguous prefixes of long options like GNU `getopt_long`, so `--verb` stands for
+    /// `--verbose`. A prefix of several options, like `--ver` for `--verbose` and `--verify`, is
+    /// rejected with a [`ParseError::AmbiguousOption`]. Hidden arguments must be written in full.
+    pub fn allow_abbrev(mut self, allow: bool) -> Self {
+        self.allow_abbrev = allow;
+        self
+    }
+
+    /// Replace the clock relative dates like `yesterday` or `2h ago` are resolved against,
+    /// [`SystemTime::now`] by default. Lets tests parse relative dates deterministically.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// # use std::time::{Duration, UNIX_EPOCH};
+    /// let parser = ArgParser::new("Query the logs.")
+    ///     .arg(Arg::datetime("since", None, true, "Oldest entry shown"))
+    ///     .clock(|| UNIX_EPOCH + Duration::from_secs(1_714_564_800));
+    /// let args = parser.try_parse_from(["--since", "2h ago"]).unwrap();
+    /// assert_eq!(args.get_raw("since").unwrap(), "2024-05-01T10:00:00Z");
+    /// ```
+    pub fn clock<F>(mut self, clock: F) -> Self
+    where
+        F: Fn() -> SystemTime + Send + Sync + 'static,
+    {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Set the time zone dates are read in, as an offset from UTC in minutes, e.g. `120` for
+    /// UTC+2. Applies to the start of days for `today` or `yesterday`, and to dates given without
+    /// an offset like `2024-05-01 12:00`. Dates are stored in RFC 3339 form at this offset.
+    /// Defaults to UTC.
+    pub fn utc_offset(mut self, minutes: i32) -> Self {
+        self.utc_offset = i64::from(minutes) * 60;
+        self
+    }
+
+    /// Select what [`ArgParser::parse`] prints after the message of a usage error.
+    /// The usage line is printed by default, [`ErrorHelpStyle::Hint`] keeps typos in large
+    /// interfaces down to a single line pointing at `--help`.
+    pub fn error_help_style(mut self, style: ErrorHelpStyle) -> Self {
+        self.error_help_style = style;
+        self
+    }
+
+    /// Order of the options in the help page and the Markdown reference, after those placed
+    /// with [`Arg::display_order`]. Options are listed in declaration order by default.
+    pub fn sort_help(mut self, sort: Sort) -> Self {
+        self.sort_help = sort;
+        self
+    }
+
+    /// List the required options before the others in the help page, each part following
+    /// [`Arg::display_order`] and [`ArgParser::sort_help`].
+    pub fn required_first(mut self, first: bool) -> Self {
+        self.required_first = first;
+        self
+    }
+
+    /// Reject command lines of more than `count` words, options and values alike, for programs
+    /// fed untrusted input like setuid helpers or network services. Words past the limit are
+    /// not read. The error has the code `E_TOO_MANY_ARGS`.
+    pub fn max_args(mut self, count: usize) -> Self {
+        self.max_args = Some(count);
+        self
+    }
+
+    /// Reject command lines with a word longer than `bytes`, see [`ArgParser::max_args`].
+    /// The scan stops at that word, which is not quoted in the error, `E_VALUE_TOO_LONG`.
+    pub fn max_value_length(mut self, bytes: usize) -> Self {
+        self.max_value_length = Some(bytes);
+        self
+    }
+
+    /// Reject more than `count` values for any argument accepting multiple values on the
+    /// command line, see [`ArgParser::max_args`]. The error has the code `E_TOO_MANY_VALUES`.
+    pub fn max_values(mut self, count: usize) -> Self {
+        self.max_values = Some(count);
+        self
+    }
+
+    /// Exit status of [`ArgParser::parse`] on usage errors, 1 by default. Many tools use 2,
+    /// following the convention of `getopt` based programs. Help and version requests exit with 0.
+    pub fn error_exit_code(mut self, code: u8) -> Self {
+        self.error_exit_code = code;
+        self
+    }
+
+    /// Prefix the errors printed by [`ArgParser::parse`] with their [`ParseError::code`],
+    /// e.g. `[E_INT_RANGE] Value ...`, for scripts and wrappers that read stderr.
+    pub fn show_error_codes(mut self, show: bool) -> Self {
+        self.show_error_codes = show;
+        self
+    }
+
+    /// Make lookups in the parsed [`ArgMap`] of names that were never declared, e.g.
+    /// `get("tyop")`, panic in debug builds instead of failing like arguments that were not
+    /// given. Catches typos between the parser and the code reading it; release builds keep
+    /// returning [`GetError::Missing`].
+    pub fn strict_lookups(mut self, strict: bool) -> Self {
+        self.strict_lookups = strict;
+        self
+    }
+
+    /// Select how usage errors are printed, e.g. [`ErrorFormat::Json`] for CLIs driven by other
+    /// programs. Help and version requests are printed as usual.
+    pub fn error_format(mut self, format: ErrorFormat) -> Self {
+        self.error_format = format;
+        self
+    }
+
+    /// Prints `error` as a line of JSON with [`ErrorFormat::Json`].
+    /// # Returns
+    /// Whether the error was printed.
+    fn emit_json_error(&self, error: &ParseError) -> bool {
+        if self.error_format != ErrorFormat::Json {
+            return false;
+        }
+        let _ = self.write(Stream::Stderr, &format!("{}\n", error.to_json()));
+        true
+    }
+
+    /// Prints `message` on stderr as a warning, in JSON with [`ErrorFormat::Json`].
+    fn warn(&self, message: &str) {
+        let line = match self.error_format {
+            ErrorFormat::Text => format!("Warning: {message}\n"),
+            ErrorFormat::Json => format!("{{\"warning\": {}}}\n", json::string(message)),
+        };
+        let _ = self.write(Stream::Stderr, &line);
+    }
+
+    /// Error as printed on stderr, prefixed with its code following [`ArgParser::show_error_codes`].
+    fn error_line(&self, error: &ParseError) -> String {
+        let message = self
+            .messages
+            .error(error)
+            .unwrap_or_else(|| error.to_string());
+        if self.show_error_codes {
+            format!("[{}] {message}", error.code())
+        } else {
+            message
+        }
+    }
+
+    /// Replace the process as the source of environment variables, terminal properties and
+    /// prompt answers, e.g. with [`testing::FakeHost`] to test environment variables, colors and
+    /// prompts deterministically.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser, testing::FakeHost};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::integer("threads", None, false, "Number of threads"))
+    ///     .env_prefix("FDUP")
+    ///     .host(FakeHost::new().var("FDUP_THREADS", "8"));
+    /// let args = parser.try_parse_from::<_, &str>([]).unwrap();
+    /// assert_eq!(args.get::<i64>("threads"), Ok(8));
+    /// ```
+    pub fn host(mut self, host: impl Host + 'static) -> Self {
+        self.host = Arc::new(host);
+        self
+    }
+
+    /// Translate the help page and the errors printed by [`ArgParser::parse`], e.g. to ship a
+    /// localized CLI. Texts `messages` leaves out stay in English. The descriptions of the program
+    /// and its arguments are given by the application, in whatever language it chooses.
+    /// See [`messages`] for an example.
+    pub fn messages(mut self, messages: impl Messages + 'static) -> Self {
+        self.messages = Arc::new(messages);
+        self
+    }
+
+    /// `english`, one of the fixed texts listed in [`Messages`], translated.
+    fn message(&self, english: &str) -> String {
+        self.messages
+            .text(english)
+            .unwrap_or_else(|| english.to_owned())
+    }
+
+    /// `title` translated and underlined, as the headings of the help page.
+    fn section_title(&self, title: &str) -> String {
+        let title = self.message(title);
+        format!("{title}:\n{}", "-".repeat(title.chars().count()))
+    }
+
+    /// Select when the help page, usage line and errors printed to the terminal are colored:
+    /// headings bold, option names cyan and errors red. Colors are used on terminals by default,
+    /// unless the `NO_COLOR` environment variable is set or `TERM` is `dumb`.
+    /// [`ArgParser::write_help`] and the other `write_*` methods always write plain text.
+    pub fn color(mut self, choice: ColorChoice) -> Self {
+        self.color = choice;
+        self
+    }
+
+    /// Wrap the descriptions of the help page at `width` columns, 100 by default.
+    /// The help printed to a narrower terminal is wrapped at the width of the terminal,
+    /// taken from `COLUMNS` when set. Continuation lines are aligned with the descriptions.
+    /// # Arguments
+    /// `width` Maximum number of columns of a line.
+    pub fn max_help_width(mut self, width: usize) -> Self {
+        self.max_help_width = width;
+        self
+    }
+
+    /// List at most `threshold` required options in the usage line, followed by `...`, e.g.
+    /// `myapp [OPTIONS] --path <PATH> ...`. The help page still describes every option.
+    /// Usage lines wider than the help are wrapped otherwise, see [`ArgParser::max_help_width`].
+    pub fn collapse_usage(mut self, threshold: usize) -> Self {
+        self.collapse_usage = Some(threshold);
+        self
+    }
+
+    /// Number of columns the help page printed to stdout is wrapped at.
+    fn help_width(&self) -> usize {
+        let terminal = self.host.is_terminal(Stream::Stdout);
+        match terminal.then(|| self.host.terminal_width()).flatten() {
+            Some(columns) => columns.min(self.max_help_width),
+            None => self.max_help_width,
+        }
+    }
+
+    /// Text as printed to `stream`, following [`ArgParser::color`].
+    fn paint(&self, text: &Styled, stream: Stream) -> String {
+        if self.color.enabled(self.host.as_ref(), stream) {
+            text.ansi()
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Writes `text` to `stream`, painted for it following [`ArgParser::color`].
+    /// # Errors
+    /// If the stream is closed, e.g. stdout piped into `head`.
+    fn emit(&self, text: &Styled, stream: Stream) -> io::Result<()> {
+        self.write(stream, &self.paint(text, stream))
+    }
+
+    /// Writes `text` to `stream` at once, or hands it to [`ArgParser::on_output`].
+    /// # Errors
+    /// If the stream is closed, e.g. stdout piped into `head`.
+    fn write(&self, stream: Stream, text: &str) -> io::Result<()> {
+        match (&self.on_output, stream) {
+            (Some(hook), _) => {
+                hook(stream, text);
+                Ok(())
+            }
+            (None, Stream::Stderr) => write_all(io::stderr(), text),
+            (None, _) => write_all(io::stdout(), text),
+        }
+    }
+
+    /// Halts the execution with `status`, or calls [`ArgParser::on_exit`].
+    fn halt(&self, status: i32) -> ! {
+        match &self.on_exit {
+            Some(hook) => match hook(status) {},
+            None => exit(status),
+        }
+    }
+
+    /// Halts the execution with `status` once `printed`, or like a process killed by `SIGPIPE`
+    /// (status 141) when the output was closed early, e.g. `myapp --help | head -1`.
+    fn halt_after(&self, printed: io::Result<()>, status: i32) -> ! {
+        match printed {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => self.halt(141),
+            _ => self.halt(status),
+        }
+    }
+
+    /// Give up on a prompt set with [`Arg::prompt`] when it is not answered in time.
+    /// The [`ArgParser::prompt_fallback`] policy applies to it and every later prompt.
+    /// Prompts wait indefinitely by default.
+    pub fn prompt_timeout(mut self, timeout: Duration) -> Self {
+        self.prompt_timeout = Some(timeout);
+        self
+    }
+
+    /// Select what happens when a prompt cannot be answered, see [`PromptFallback`].
+    pub fn prompt_fallback(mut self, fallback: PromptFallback) -> Self {
+        self.prompt_fallback = fallback;
+        self
+    }
+
+    /// Ask on the terminal for the required arguments missing from every other source, e.g.
+    /// `Enter value for --path:`, instead of failing right away. When stdin is not a terminal,
+    /// like in scripts, or the prompt is left empty, the argument is reported as missing.
+    /// Arguments with their own [`Arg::prompt`] keep their question.
+    pub fn prompt_missing(mut self, prompt: bool) -> Self {
+        self.prompt_missing = prompt;
+        self
+    }
+
+    /// Stop intercepting `--help` and `-h`, so they can be defined as regular arguments.
+    /// Must be called before adding arguments named `help` or using `h` as short name,
+    /// which are otherwise ignored.
+    pub fn disable_help(mut self, disable: bool) -> Self {
+        self.help_enabled = !disable;
+        self
+    }
+
+    /// Accept `-<c>` for the built-in help as well as `-h`, e.g. `?` for users used to `-?`.
+    /// The alias is listed in the help page.
+    pub fn help_short_alias(mut self, c: char) -> Self {
+        self.help_short_aliases.push(c);
+        self
+    }
+
+    /// Stop intercepting the hidden `--clarg-debug` flag, which otherwise prints every resolved
+    /// value with its source as JSON, sensitive values masked, and exits, e.g. for support teams
+    /// to ask users for the effective settings of a program with one standard command.
+    pub fn disable_debug(mut self, disable: bool) -> Self {
+        self.debug_enabled = !disable;
+        self
+    }
+
+    /// Name the debug flag `--{name}` instead of `--clarg-debug`, e.g. `dump-args`, and choose
+    /// whether the program goes on running after printing the values. The flag stays hidden.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser, DebugAction};
+    /// # use std::sync::{Arc, Mutex};
+    /// let printed = Arc::new(Mutex::new(String::new()));
+    /// let output = printed.clone();
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::integer("depth", Some('d'), false, "Maximum depth").default_value("3"))
+    ///     .enable_debug_dump("dump-args", DebugAction::Continue)
+    ///     .on_output(move |_, text| output.lock().unwrap().push_str(text));
+    /// let args = parser.try_parse_from(["--dump-args"]).unwrap();
+    /// assert_eq!(args.get::<i64>("depth").unwrap(), 3);
+    /// assert!(printed.lock().unwrap().contains(r#""depth": {"value": 3, "type": "integer", "source": "default"}"#));
+    /// ```
+    pub fn enable_debug_dump(mut self, name: &str, action: DebugAction) -> Self {
+        self.debug_enabled = true;
+        self.debug_flag = name.to_owned();
+        self.debug_action = action;
+        self
+    }
+
+    /// Replace the built-in handling of `--help`: instead of printing the help page and halting
+    /// the execution, `hook` is called and parsing ends there. The arguments given before
+    /// `--help` are returned as they are: the words after it are ignored, no value is taken from
+    /// the environment, configuration files, prompts or defaults, and required arguments,
+    /// groups, relations and validators are not checked.
+    /// Lets applications with their own interface, like TUIs, show the help where they see fit.
+    /// The hook is never called by [`ArgParser::check`].
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::string("path", Some('p'), true, "Directory to examine"))
+    ///     .arg(Arg::boolean("verbose", Some('v'), "Verbose execution"))
+    ///     .on_help(|parser| parser.usage());
+    /// let arguments = parser.try_parse_from(["-v", "--help", "--bogus"]).unwrap();
+    /// assert!(arguments.get::<bool>("verbose").unwrap());
+    /// assert_eq!(arguments.get_raw("path"), None);
+    /// ```
+    pub fn on_help<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&ArgParser) + Send + Sync + 'static,
+    {
+        self.on_help = Some(Box::new(hook));
+        self
+    }
+
+    /// Call `hook` with the long name and the value of every argument matched while parsing, in
+    /// the order given, for behaviors the [`ArgMap`] cannot tell, like the relative order of
+    /// `-I` and `-L` paths. Flags are reported with `true` or `false`, counts with their running
+    /// total and each value of a multi-value argument separately. Options of
+    /// [`ArgParser::env_args`] and `--again` are reported first, values from other sources are
+    /// not. Sensitive values are passed as is. The hook is never called by [`ArgParser::check`].
+    /// # Example
+    /// ```
+    /// # use std::sync::{Arc, Mutex};
+    /// # use clarg::{Arg, ArgParser};
+    /// let order = Arc::new(Mutex::new(Vec::new()));
+    /// let seen = Arc::clone(&order);
+    /// let parser = ArgParser::new("Link the objects.")
+    ///     .arg(Arg::string("include", Some('I'), false, "Header directory").multiple(true))
+    ///     .arg(Arg::string("lib", Some('L'), false, "Library directory").multiple(true))
+    ///     .on_arg(move |name, value| seen.lock().unwrap().push(format!("{name}={value}")));
+    /// parser.try_parse_from(["-I", "a", "-L", "b", "-I", "c"]).unwrap();
+    /// assert_eq!(*order.lock().unwrap(), ["include=a", "lib=b", "include=c"]);
+    /// ```
+    pub fn on_arg<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        self.on_arg = Some(Box::new(hook));
+        self
+    }
+
+    /// Add a check of the parsed arguments as a whole, for invariants spanning several of them
+    /// like `--start` before `--end`. It runs once every argument and group is valid, and the
+    /// message it returns is reported like the built-in errors, with the code `E_INVALID_ARGUMENTS`.
+    /// Several validators run in the order added, and all their failures are reported.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Print a range of lines.")
+    ///     .arg(Arg::unsigned("start", None, true, "First line"))
+    ///     .arg(Arg::unsigned("end", None, true, "Last line"))
+    ///     .validator(|args| match args.get::<u64>("start")? <= args.get::<u64>("end")? {
+    ///         true => Ok(()),
+    ///         false => Err("--start must not be after --end.".to_owned()),
+    ///     });
+    /// assert!(parser.try_parse_from(["--start", "3", "--end", "7"]).is_ok());
+    /// let error = parser.try_parse_from(["--start", "9", "--end", "7"]).unwrap_err();
+    /// assert_eq!(error.to_string(), "--start must not be after --end.");
+    /// ```
+    pub fn validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&ArgMap) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Hand what would be printed, like the help page, errors and warnings, to `hook` along with
+    /// the stream it was meant for, e.g. to capture it in tests or show it in a GUI. The text is
+    /// colored following [`ArgParser::color`]. Prompts still use the terminal.
+    pub fn on_output<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Stream, &str) + Send + Sync + 'static,
+    {
+        self.on_output = Some(Box::new(hook));
+        self
+    }
+
+    /// Call `hook` with the exit status instead of [`std::process::exit`] when [`ArgParser::parse`]
+    /// halts the execution, after help, version or an error. The hook cannot return, it can
+    /// unwind with the status, e.g. for tests to assert on it without spawning a process.
+    /// Long-running hosts rather use [`ArgParser::try_parse`] to get the error itself.
+    /// # Example
+    /// ```
+    /// use clarg::{ArgParser, host::Stream};
+    /// use std::{panic, sync::{Arc, Mutex}};
+    ///
+    /// let output = Arc::new(Mutex::new(String::new()));
+    /// let captured = Arc::clone(&output);
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .on_output(move |stream, text| {
+    ///         if stream == Stream::Stderr {
+    ///             captured.lock().unwrap().push_str(text);
+    ///         }
+    ///     })
+    ///     .on_exit(|status| panic::panic_any(status));
+    /// let halted = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+    ///     parser.parse_from_os(["--unknown"]);
+    /// }));
+    /// assert_eq!(halted.unwrap_err().downcast_ref::<i32>(), Some(&1));
+    /// assert!(output.lock().unwrap().contains("--unknown"));
+    /// ```
+    pub fn on_exit<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(i32) -> Infallible + Send + Sync + 'static,
+    {
+        self.on_exit = Some(Box::new(hook));
+        self
+    }
+
+    /// Set the version of the program, printed by `--version` or `-V` which are registered
+    /// along with `--help`. Arguments named `version` or using `V` as short name take precedence.
+    /// # Arguments
+    /// `version` Version of the program, usually `env!("CARGO_PKG_VERSION")`.
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_owned());
+        self
+    }
+
+    /// Set the commit the program was built from, e.g. `env!("GIT_COMMIT")` exported by a build
+    /// script, reported by `--version --format json`.
+    pub fn commit(mut self, commit: &str) -> Self {
+        self.commit = Some(commit.to_owned());
+        self
+    }
+
+    /// Set details printed by `--version --verbose` below the version, e.g. the build date or
+    /// the compiler used, exported by a build script. See [`ArgParser::render_long_version`].
+    pub fn long_version(mut self, text: &str) -> Self {
+        self.long_version = Some(text.to_owned());
+        self
+    }
+
+    /// Set the author of the program, e.g. `env!("CARGO_PKG_AUTHORS")`, shown at the end of
+    /// the help page and by `--version --verbose`.
+    pub fn author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_owned());
+        self
+    }
+
+    /// Set the license of the program, e.g. `env!("CARGO_PKG_LICENSE")`, shown at the end of
+    /// the help page and by `--version --verbose`.
+    pub fn license(mut self, license: &str) -> Self {
+        self.license = Some(license.to_owned());
+        self
+    }
+
+    /// Text printed for `--version --format json` or `--version=json`: the program name, version,
+    /// commit and target platform, e.g.
+    /// `{"name": "mytool", "version": "1.4.2", "commit": null, "target": "x86_64-linux"}`.
+    /// Lets deployment tools check the version of a binary without parsing free-form text.
+    pub fn render_version_json(&self) -> String {
+        let optional =
+            |value: &Option<String>| value.as_deref().map_or("null".to_owned(), json::string);
+        format!(
+            "{{\"name\": {}, \"version\": {}, \"commit\": {}, \"target\": {}}}",
+            json::string(&self.executable),
+            optional(&self.version),
+            optional(&self.commit),
+            json::string(&format!(
+                "{}-{}",
+                std::env::consts::ARCH,
+                std::env::consts::OS
+            ))
+        )
+    }
+
+    /// Text printed for `--version`: the program name followed by its version, e.g. `mytool 1.4.2`.
+    /// Empty when no version was set with [`ArgParser::version`].
+    pub fn render_version(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{} {}", self.executable, version),
+            None => String::new(),
+        }
+    }
+
+    /// Text printed for `--version --verbose`: the version line, the text set with
+    /// [`ArgParser::long_version`], then the commit, target platform, author and license, e.g.
+    /// ```text
+    /// mytool 1.4.2
+    /// Built on 2024-05-01 with rustc 1.78.0
+    /// Commit: 3f2a1c9
+    /// Target: x86_64-linux
+    /// License: MIT
+    /// ```
+    /// Empty when no version was set with [`ArgParser::version`].
+    pub fn render_long_version(&self) -> String {
+        if self.version.is_none() {
+            return String::new();
+        }
+        let mut lines = vec![self.render_version()];
+        lines.extend(self.long_version.clone());
+        let target = format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS);
+        let details = [
+            ("Commit", self.commit.as_ref()),
+            ("Target", Some(&target)),
+            ("Author", self.author.as_ref()),
+            ("License", self.license.as_ref()),
+        ];
+        for (label, value) in details {
+            if let Some(value) = value {
+                lines.push(format!("{label}: {value}"));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Short name of the built-in version flag, unless no version is set or the user took `-V`.
+    fn version_short(&self) -> Option<char> {
+        (self.version.is_some() && !self.args.iter().any(|arg| arg.answers_to_short('V')))
+            .then_some('V')
+    }
+
+    /// Whether the built-in `--version` flag is available.
+    fn version_long(&self) -> bool {
+        self.version.is_some() && !self.args.iter().any(|arg| arg.long_name == "version")
+    }
+
+    /// Other words users may look for this command by, e.g. `&["remove", "delete"]` for `rm`.
+    /// Only used for the commands of [`commands!`]: `myapp --help delete` lists the commands
+    /// matching a keyword, and an unknown command equal or close to a keyword suggests its command.
+    pub fn keywords(mut self, keywords: &[&str]) -> Self {
+        self.keywords
+            .extend(keywords.iter().map(|keyword| keyword.to_string()));
+        self
+    }
+
+    /// List this command under its own `category` heading, e.g. `"Maintenance"`, instead of
+    /// the flat list of commands of [`commands!`], in order of first use.
+    pub fn category(mut self, category: &str) -> Self {
+        self.category = Some(category.to_owned());
+        self
+    }
+
+    /// Former names of this command, e.g. `&["co"]` for `checkout`. Only used for the commands of
+    /// [`commands!`]: `myapp co` still runs `checkout`, after printing the warning
+    /// ``Warning: `myapp co` is deprecated, use `myapp checkout` instead`` on stderr. Former
+    /// names are not listed with the commands.
+    pub fn renamed_from(mut self, names: &[&str]) -> Self {
+        self.renamed_from
+            .extend(names.iter().map(|name| name.to_string()));
+        self
+    }
+
+    /// Mark this command of [`commands!`] as deprecated: it still runs, after printing a warning
+    /// to stderr, and the list of commands tags it with `[deprecated]`.
+    /// # Arguments
+    /// `note` What to use instead, e.g. `"use `myapp status` instead"`. It replaces the note
+    /// of [`ArgParser::renamed_from`].
+    pub fn deprecated_command(mut self, note: &str) -> Self {
+        self.command_deprecated = Some(note.to_owned());
+        self
+    }
+
+    /// Add a usage example, listed at the end of the help page.
+    /// Examples can be verified against the arguments with [`testing::check_examples`].
+    /// # Arguments
+    /// `args` Arguments of the example, without the executable name, e.g. `"--path /tmp --json"`.
+    pub fn example(mut self, args: &str) -> Self {
+        self.examples.push(args.to_owned());
+        self
+    }
+
+    /// Lay out the help page following `template`, where the following placeholders are replaced
+    /// by the parts of the built-in help page:
+    /// * `{name}` name of the executable, `{version}` version set with [`ArgParser::version`].
+    /// * `{about}` description of the program.
+    /// * `{usage}` usage line without the `Usage:` heading, e.g. `myapp [OPTIONS] <FILE>`.
+    /// * `{positionals}` and `{options}` lists of the positional arguments and options.
+    /// * `{groups}` notes on the argument groups.
+    /// * `{examples}` examples added with [`ArgParser::example`].
+    /// * `{after-help}` text set with [`ArgParser::after_help`].
+    /// * `{author}` and `{license}` set with [`ArgParser::author`] and [`ArgParser::license`].
+    ///
+    /// Other text, including unknown placeholders, is kept as is. Searches with `--help <pattern>`
+    /// keep the built-in layout.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .executable_name("fdup")
+    ///     .arg(Arg::boolean("json", None, "Format output as JSON"))
+    ///     .help_template("{about}\n\nUSAGE:\n  {usage}\n\nOPTIONS:\n{options}")
+    ///     .disable_help(true);
+    /// let mut help = String::new();
+    /// parser.write_help(&mut help).unwrap();
+    /// assert!(help.starts_with("Find duplicate files.\n\nUSAGE:\n  fdup [OPTIONS]"));
+    /// ```
+    pub fn help_template(mut self, template: &str) -> Self {
+        self.help_template = Some(template.to_owned());
+        self
+    }
+
+    /// Add text at the end of the help page, e.g. links to the documentation or bug tracker.
+    /// With a [`ArgParser::help_template`], it is placed at `{after-help}`.
+    pub fn after_help(mut self, text: &str) -> Self {
+        self.after_help = Some(text.to_owned());
+        self
+    }
+
+    /// Set a longer description of the program, shown below the description by
+    /// `--help --verbose` and in the Markdown documentation, like [`Arg::long_help`] for arguments.
+    pub fn long_about(mut self, text: &str) -> Self {
+        self.long_about = Some(text.to_owned());
+        self
+    }
+
+    /// Name of the environment variable for an argument, if the parser reads the environment.
+    fn env_name(&self, arg: &Arg) -> Option<String> {
+        self.env_prefix.as_ref().map(|prefix| {
+            format!(
+                "{prefix}_{}",
+                arg.long_name.to_uppercase().replace('-', "_")
+            )
+        })
+    }
+
+    /// Add a new argument requirement to the parser.
+    /// # Arguments
+    /// `arg` Argument requirements.
+    /// # Returns
+    ///  The argument parser itself. Useful for chaining.
+    pub fn arg(mut self, mut arg: Arg) -> Self {
+        // flags are never required, their absence means `false` or no occurrences.
+        if matches!(arg.kind, ArgKind::Boolean | ArgKind::Count) {
+            arg.required = false;
+        }
+        // arguments of other systems are only known to explain they can't be used.
+        if !arg.is_supported() {
+            arg.required = false;
+            arg.hidden = true;
+        }
+        arg.stdio = self.stdio;
+        arg.ignore_case = self.ignore_case;
+        // we don't allow overriding help
+        if !self.help_enabled || (arg.long_name != "help" && !arg.answers_to_short('h')) {
+            self.args.push(arg);
+        }
+        self
+    }
+
+    /// Add every argument and group of `set`, in order, as [`ArgParser::arg`] and
+    /// [`ArgParser::add_group`] do.
+    /// # Returns
+    ///  The argument parser itself. Useful for chaining.
+    pub fn args(mut self, set: ArgSet) -> Self {
+        for arg in set.args {
+            self = self.arg(arg);
+        }
+        self.groups.extend(set.groups);
+        self
+    }
+
+    /// Prints the program's usage. Nothing happens when stdout is closed.
+    pub fn usage(&self) {
+        let _ = self.emit(&self.usage_text(self.help_width()), Stream::Stdout);
+    }
+
+    /// Writes the program's usage line, as printed by [`ArgParser::usage`].
+    /// # Errors
+    /// If writing to `w` fails.
+    pub fn write_usage(&self, w: &mut impl std::fmt::Write) -> std::fmt::Result {
+        write!(w, "{}", self.usage_text(self.max_help_width))
+    }
+
+    /// Usage line as printed after usage errors, e.g. `Usage: myapp [OPTIONS] --path <PATH>`,
+    /// without colors nor final line break. Lets errors found by the application itself, like
+    /// checks across several arguments, end like the errors of the parser.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Copy files.").arg(Arg::string("to", None, true, "Destination"));
+    /// let footer = format!("{}\n{}", parser.usage_string(), parser.help_hint().unwrap());
+    /// assert_eq!(footer, "Usage: rust_out --to <TO>\nFor more information, try '--help'.");
+    /// ```
+    pub fn usage_string(&self) -> String {
+        self.usage_text(self.help_width())
+            .to_string()
+            .trim_end()
+            .to_owned()
+    }
+
+    /// Hint pointing at `--help` as printed after usage errors, following
+    /// [`ArgParser::error_help_style`], e.g. `For more information, try '--help'.`.
+    /// `None` when the built-in help is disabled.
+    pub fn help_hint(&self) -> Option<String> {
+        self.hint_text().map(|hint| hint.to_string())
+    }
+
+    /// Hint pointing at `--help`, see [`ArgParser::help_hint`].
+    fn hint_text(&self) -> Option<Styled> {
+        if !self.help_enabled {
+            return None;
+        }
+        let (template, help) = match self.error_help_style {
+            ErrorHelpStyle::Hint => (
+                "Try '{help}' for more information.",
+                format!("{} --help", self.executable),
+            ),
+            _ => ("For more information, try '{help}'.", "--help".to_owned()),
+        };
+        let template = self.message(template);
+        let (before, after) = template.split_once("{help}").unwrap_or((&template, ""));
+        let mut hint = Styled::new();
+        hint.plain(before).literal(help).plain(after);
+        Some(hint)
+    }
+
+    /// Usage line, e.g. `Usage: myapp [OPTIONS] --path <PATH>`. A usage longer than `width`
+    /// columns is wrapped, continuation lines aligned after the program name.
+    fn usage_text(&self, width: usize) -> Styled {
+        let heading = format!("{}:", self.message("Usage"));
+        // the heading and the space after it.
+        let prefix = heading.chars().count() + 1;
+        let mut usage = Styled::new();
+        let line = self.synopsis_text();
+        if prefix + line.width() <= width {
+            usage.heading(heading).plain(" ").append(line).plain("\n");
+            return usage;
+        }
+        let parts = self.synopsis_parts();
+        let indent = prefix + self.executable.chars().count() + 1;
+        let mut column = indent;
+        usage.heading(heading).plain(" ").literal(&self.executable);
+        if parts.options {
+            usage.plain(" [OPTIONS]");
+            column += "[OPTIONS]".len() + 1;
+        }
+        let items = parts
+            .required
+            .into_iter()
+            .chain(parts.groups)
+            .chain(parts.positionals);
+        for item in items {
+            // the first item of a line always fits, however long it is.
+            if column > indent && column + item.width() > width {
+                usage.plain(format!("\n{}", " ".repeat(indent)));
+                column = indent;
+            } else if column > indent {
+                usage.plain(" ");
+                column += 1;
+            }
+            column += item.width();
+            usage.append(item);
+        }
+        usage.plain("\n");
+        usage
+    }
+
+    /// Usage of the program, like `myapp [OPTIONS] --path <PATH> (--json | --csv) [FILES]...`.
+    fn synopsis_text(&self) -> Styled {
+        let parts = self.synopsis_parts();
+        let mut usage = Styled::new();
+        usage.literal(&self.executable);
+        if parts.options {
+            usage.plain(" [OPTIONS]");
+        }
+        let items = parts
+            .required
+            .into_iter()
+            .chain(parts.groups)
+            .chain(parts.positionals);
+        for item in items {
+            usage.plain(" ").append(item);
+        }
+        usage
+    }
+
+    /// Usage of the members of `group`, e.g. `(--json | --csv)`, nested groups in their place
+    /// like `(--token <TOKEN> | (--user <USER> --password <PASSWORD>))`.
+    fn group_synopsis(&self, group: &ArgGroup, depth: usize) -> Styled {
+        let separator = match group.kind() {
+            GroupKind::Exclusive | GroupKind::AnyOf => " | ",
+            GroupKind::OnlyWhen | GroupKind::AllOrNone => " ",
+        };
+        let mut usage = Styled::new();
+        usage.plain("(");
+        for (i, name) in group.args().iter().enumerate() {
+            if i > 0 {
+                usage.plain(separator);
+            }
+            match self.args.iter().find(|arg| arg.long_name == *name) {
+                Some(arg) => usage.append(arg.synopsis()),
+                // cycles, reported by `ArgParser::validate`, stop once every group was entered.
+                None => match self.group(name).filter(|_| depth < self.groups.len()) {
+                    Some(nested) => usage.append(self.group_synopsis(nested, depth + 1)),
+                    None => usage.literal(name),
+                },
+            };
+        }
+        usage.plain(")");
+        usage
+    }
+
+    /// Items of the usage, following [`ArgParser::collapse_usage`].
+    fn synopsis_parts(&self) -> SynopsisParts {
+        let options = self
+            .args
+            .iter()
+            .any(|arg| !arg.required && !arg.positional && !arg.hidden);
+        // one member of these groups is required, while the members themselves are optional.
+        let groups = self
+            .groups
+            .iter()
+            .filter(|group| group.is_required())
+            .filter(|group| matches!(group.kind(), GroupKind::Exclusive | GroupKind::AnyOf))
+            .map(|group| self.group_synopsis(group, 0))
+            .collect();
+        let mut required: Vec<Styled> = self
+            .args
+            .iter()
+            .filter(|arg| arg.required && !arg.positional && !arg.hidden)
+            .map(Arg::synopsis)
+            .collect();
+        if let Some(threshold) = self.collapse_usage {
+            if required.len() > threshold {
+                required.truncate(threshold);
+                required.push(Styled::span(Style::Plain, "..."));
+            }
+        }
+        let positionals = self
+            .args
+            .iter()
+            .filter(|arg| arg.positional && !arg.hidden)
+            .map(|arg| {
+                let mut usage = Styled::new();
+                let needed = arg.multiple && arg.min_values.is_some_and(|min| min > 0);
+                match (arg.required || needed, arg.multiple) {
+                    (true, _) => usage.append(arg.sample_usage()),
+                    (false, true) => usage
+                        .plain("[")
+                        .placeholder(arg.placeholder())
+                        .plain("]..."),
+                    (false, false) => usage.plain("[").placeholder(arg.placeholder()).plain("]"),
+                };
+                usage
+            })
+            .collect();
+        SynopsisParts {
+            options,
+            groups,
+            required,
+            positionals,
+        }
+    }
+
+    /// Prints the help page for this executable
+    /// The help page consists of:
+    /// * Example usage.
+    /// * Options description
+    ///
+    /// Nothing happens when stdout is closed, e.g. piped into `head`.
+    pub fn help(&self) {
+        let width = self.help_width();
+        let _ = self.emit(&self.help_text(None, false, width), Stream::Stdout);
+    }
+
+    /// Prints the help page keeping only the options whose name or description contains `pattern`,
+    /// ignoring case. Shown for `--help <pattern>`. When `pattern` names an argument, like
+    /// `output` or `--output`, the argument is described alone with its [`Arg::long_help`].
+    pub fn help_search(&self, pattern: &str) {
+        let width = self.help_width();
+        let _ = self.emit(&self.help_text(Some(pattern), false, width), Stream::Stdout);
+    }
+
+    /// Writes the help page, as printed by [`ArgParser::help`], e.g. to capture it for logging,
+    /// snapshot tests or an about dialog.
+    /// # Errors
+    /// If writing to `w` fails.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::boolean("json", None, "Format output as JSON"));
+    /// let mut help = String::new();
+    /// parser.write_help(&mut help).unwrap();
+    /// assert!(help.contains("Format output as JSON"));
+    /// ```
+    pub fn write_help(&self, w: &mut impl std::fmt::Write) -> std::fmt::Result {
+        write!(w, "{}", self.help_text(None, false, self.max_help_width))
+    }
+
+    /// Writes the help page filtered by `pattern`, as printed by [`ArgParser::help_search`].
+    /// # Errors
+    /// If writing to `w` fails.
+    pub fn write_help_search(
+        &self,
+        w: &mut impl std::fmt::Write,
+        pattern: &str,
+    ) -> std::fmt::Result {
+        write!(
+            w,
+            "{}",
+            self.help_text(Some(pattern), false, self.max_help_width)
+        )
+    }
+
+    /// Help page, keeping only the options matching `search` if any, with the long help of the
+    /// arguments when `verbose`.
+    /// Arguments in the order they are listed in the help page, following
+    /// [`ArgParser::required_first`], [`Arg::display_order`] and [`ArgParser::sort_help`].
+    /// Positional arguments stay in declaration order.
+    pub(crate) fn help_order(&self) -> Vec<&Arg> {
+        let mut args: Vec<&Arg> = self.args.iter().collect();
+        // the sort is stable, equal keys keep the declaration order.
+        args.sort_by_cached_key(|arg| match arg.positional {
+            true => (false, 0, String::new()),
+            false => (
+                self.required_first && !arg.required,
+                arg.display_order.unwrap_or(u32::MAX),
+                match self.sort_help {
+                    Sort::Declaration => String::new(),
+                    Sort::Alphabetical => arg.long_name.to_lowercase(),
+                },
+            ),
+        });
+        args
+    }
+
+    fn help_text(&self, search: Option<&str>, verbose: bool, width: usize) -> Styled {
+        if let Some(arg) = search.and_then(|name| self.documented_arg(name)) {
+            return self.arg_help_text(arg, width);
+        }
+        let search = search.map(str::to_lowercase);
+        let shown = |name: &str, description: &str| match &search {
+            Some(pattern) => {
+                name.to_lowercase().contains(pattern)
+                    || description.to_lowercase().contains(pattern)
+            }
+            None => true,
+        };
+        let mut help = Styled::new();
+        help.plain(format!("{}\n", self.description));
+        if let Some(text) = self.long_about.as_ref().filter(|_| verbose) {
+            help.plain(format!("\n{}\n", text::wrap(text, width).join("\n")));
+        }
+        help.append(self.usage_text(width));
+
+        // calculate the maximum width of the argument name.
+        let max_length = self
+            .args
+            .iter()
+            .filter(|arg| !arg.hidden)
+            .fold(0, |max, arg| match arg.kind {
+                // boolean arguments don't have to repeat their name, only count once
+                ArgKind::Boolean | ArgKind::Count => {
+                    max.max(arg.sample_usage().width() + ARG_PADDING)
+                }
+
+                // any other argument has 2 times the length + some padding when printed, account for it.
+                // we assume the maximum usage like "--Argument <ARGUMENT>" (arg.len * 2 + at least 5 args) and add some padding
+                _ => max.max(arg.sample_usage().width() - 3 + ARG_PADDING),
+            });
+
+        // Print each argument and it's description for the help message.
+        let mut positional_lines = Vec::new();
+        let mut option_lines = Vec::new();
+        // lines of the arguments set with `Arg::help_heading`, by heading in order of appearance.
+        let mut headed: Vec<(&str, Styled)> = Vec::new();
+        for arg in self
+            .help_order()
+            .into_iter()
+            .filter(|arg| !arg.hidden && shown(&arg.long_name, &arg.description))
+        {
+            let mut default = arg.alias_note();
+            let tag = |name: &str| self.message(name);
+            if arg.deprecated.is_some() {
+                default.push_str(&format!(" [{}]", tag("deprecated")));
+            }
+            for (other, value, fallback) in &arg.default_if {
+                default.push_str(&format!(
+                    " [{}: {} if --{other} {value}]",
+                    tag("default"),
+                    arg.display_value(fallback)
+                ));
+            }
+            if let Some(value) = &arg.default_value {
+                default.push_str(&format!(
+                    " [{}: {}]",
+                    tag("default"),
+                    arg.display_value(value)
+                ));
+            }
+            if let Some(range) = &arg.range {
+                default.push_str(&format!(" [{}: {range}]", tag("range")));
+            }
+            if !arg.required_unless.is_empty() {
+                default.push_str(&format!(
+                    " [{}: {}]",
+                    tag("required unless"),
+                    dashed(&arg.required_unless)
+                ));
+            }
+            if !arg.requires.is_empty() {
+                default.push_str(&format!(
+                    " [{}: {}]",
+                    tag("requires"),
+                    dashed(&arg.requires)
+                ));
+            }
+            if !arg.conflicts_with.is_empty() {
+                default.push_str(&format!(
+                    " [{}: {}]",
+                    tag("conflicts with"),
+                    dashed(&arg.conflicts_with)
+                ));
+            }
+            if let Some(env) = self.env_name(arg) {
+                default.push_str(&format!(" [{}: {env}]", tag("env")));
+            }
+
+            let description = wrap(
+                &format!("{}{}", arg.description, default),
+                max_length + 7,
+                width,
+            );
+            let mut line = Styled::new();
+            if arg.positional {
+                line.plain("    ")
+                    .padded(arg.sample_usage(), max_length + 2)
+                    .plain(format!(" {description}\n"));
+            } else {
+                option_name(&mut line, arg.short_name)
+                    .padded(arg.sample_usage(), max_length)
+                    .plain(format!(" {description}\n"));
+            }
+            let long_help = arg.long_help.as_ref().filter(|_| verbose);
+            if let Some(text) = long_help {
+                line.plain(indented(text, max_length + 7, width));
+            }
+            // examples are aligned with the description.
+            for example in &arg.examples {
+                line.plain(" ".repeat(max_length + 7))
+                    .plain("example: ")
+                    .literal(example)
+                    .plain("\n");
+            }
+            // long help sets the argument apart from the next one.
+            if long_help.is_some() {
+                line.plain("\n");
+            }
+            match (&arg.help_heading, arg.positional) {
+                (Some(heading), _) => match headed.iter_mut().find(|(title, _)| title == heading) {
+                    Some((_, lines)) => {
+                        lines.append(line);
+                    }
+                    None => headed.push((heading, line)),
+                },
+                (None, true) => positional_lines.push(line),
+                (None, false) => option_lines.push(line),
+            }
+        }
+        let profile_note = self.message("Select a configuration profile");
+        let show_profile = self.config_file.is_some() && shown("profile", &profile_note);
+        let preset_note = format!(
+            "{}: {}",
+            self.message("Select a preset of values"),
+            self.presets.join(", ")
+        );
+        let show_preset = !self.presets.is_empty() && shown("preset", &preset_note);
+        let again_note = self.message("Repeat the options of the last run");
+        let show_again = self.last_run.is_some() && shown("again", &again_note);
+        let help_aliases: Vec<String> = self
+            .help_short_aliases
+            .iter()
+            .map(|c| format!("-{c}"))
+            .collect();
+        let help_note = match help_aliases.as_slice() {
+            [] => self.message("Print this help message"),
+            [alias] => format!(
+                "{} (alias: {alias})",
+                self.message("Print this help message")
+            ),
+            aliases => format!(
+                "{} (aliases: {})",
+                self.message("Print this help message"),
+                aliases.join(", ")
+            ),
+        };
+        let show_help = self.help_enabled && shown("help", &help_note);
+        let version_note = self.message("Print version information");
+        let show_version = self.version_long() && shown("version", &version_note);
+        if let Some(pattern) = &search {
+            if positional_lines.is_empty()
+                && option_lines.is_empty()
+                && headed.is_empty()
+                && !show_profile
+                && !show_preset
+                && !show_again
+                && !show_help
+                && !show_version
+            {
+                help.plain(format!("\nNo options match `{pattern}`.\n"));
+                return help;
+            }
+        }
+        let mut positionals = Styled::new();
+        for line in positional_lines {
+            positionals.append(line);
+        }
+        let mut options = Styled::new();
+        for line in option_lines {
+            options.append(line);
+        }
+        if show_profile {
+            let mut usage = Styled::new();
+            usage.literal("profile").plain(" ").placeholder("<PROFILE>");
+            option_name(&mut options, None)
+                .padded(usage, max_length)
+                .plain(format!(" {profile_note}\n"));
+        }
+        if show_preset {
+            let mut usage = Styled::new();
+            usage.literal("preset").plain(" ").placeholder("<PRESET>");
+            option_name(&mut options, None)
+                .padded(usage, max_length)
+                .plain(format!(" {preset_note}\n"));
+        }
+        if show_again {
+            let mut usage = Styled::new();
+            usage.literal("again");
+            option_name(&mut options, None)
+                .padded(usage, max_length)
+                .plain(format!(" {again_note}\n"));
+        }
+        if show_version {
+            let mut usage = Styled::new();
+            usage.literal("version");
+            option_name(&mut options, self.version_short())
+                .padded(usage, max_length)
+                .plain(format!(" {version_note}\n"));
+        }
+        if show_help {
+            let mut usage = Styled::new();
+            usage.literal("help");
+            option_name(&mut options, Some('h'))
+                .padded(usage, max_length)
+                .plain(format!(" {help_note}\n"));
+        }
+        let mut sections = Styled::new();
+        for (title, lines) in headed {
+            sections
+                .plain("\n")
+                .heading(format!("{title}:\n{}", "-".repeat(title.chars().count())))
+                .plain("\n")
+                .append(lines);
+        }
+        let mut examples = Styled::new();
+        for example in &self.examples {
+            examples
+                .literal(format!("{} {}", self.executable, example))
+                .plain("\n");
+        }
+        // a search only lists the matching options, in the default layout.
+        if let (Some(template), None) = (&self.help_template, &search) {
+            return fill_template(template, |name| match name {
+                "name" => Some(Styled::span(Style::Literal, &self.executable)),
+                "version" => Some(Styled::span(
+                    Style::Plain,
+                    self.version.as_deref().unwrap_or(""),
+                )),
+                "about" => Some(Styled::span(Style::Plain, &self.description)),
+                "usage" => Some(self.synopsis_text()),
+                "positionals" => Some(positionals.clone()),
+                "options" => {
+                    let mut all = options.clone();
+                    all.append(sections.clone());
+                    Some(all)
+                }
+                "groups" => Some(self.group_notes()),
+                "examples" => Some(examples.clone()),
+                "after-help" => Some(Styled::span(
+                    Style::Plain,
+                    self.after_help.as_deref().unwrap_or(""),
+                )),
+                "author" => Some(Styled::span(
+                    Style::Plain,
+                    self.author.as_deref().unwrap_or(""),
+                )),
+                "license" => Some(Styled::span(
+                    Style::Plain,
+                    self.license.as_deref().unwrap_or(""),
+                )),
+                _ => None,
+            });
+        }
+        if !positionals.is_empty() {
+            help.plain("\n")
+                .heading(self.section_title("arguments"))
+                .plain("\n")
+                .append(positionals);
+        }
+        // a search may only match arguments listed under headings.
+        if search.is_none() || !options.is_empty() {
+            help.plain("\n")
+                .heading(self.section_title("options"))
+                .plain("\n")
+                .append(options);
+        }
+        help.append(sections);
+        if search.is_some() {
+            return help;
+        }
+        if !self.groups.is_empty() {
+            help.plain("\n").append(self.group_notes());
+        }
+        if !examples.is_empty() {
+            help.plain("\n")
+                .heading(self.section_title("examples"))
+                .plain("\n")
+                .append(examples);
+        }
+        if let Some(text) = &self.after_help {
+            help.plain(format!("\n{text}\n"));
+        }
+        if self.author.is_some() || self.license.is_some() {
+            help.plain("\n");
+        }
+        if let Some(author) = &self.author {
+            help.plain(format!("Author: {author}\n"));
+        }
+        if let Some(license) = &self.license {
+            help.plain(format!("License: {license}\n"));
+        }
+        help
+    }
+
+    /// Argument shown in the help page named `name`, with or without dashes, e.g. `--output`,
+    /// `output` or `-o`.
+    fn documented_arg(&self, name: &str) -> Option<&Arg> {
+        let bare = name.trim_start_matches('-');
+        self.args.iter().filter(|arg| !arg.hidden).find(|arg| {
+            arg.answers_to(bare)
+                || (name.starts_with('-')
+                    && bare.chars().count() == 1
+                    && bare.chars().next().is_some_and(|c| arg.answers_to_short(c)))
+        })
+    }
+
+    /// Help page of `arg` alone, for `--help <name>`: its usage, description and long help,
+    /// followed by its default, environment variable and constraints.
+    fn arg_help_text(&self, arg: &Arg, width: usize) -> Styled {
+        let mut help = Styled::new();
+        if arg.positional {
+            help.plain("    ").append(arg.sample_usage());
+        } else {
+            option_name(&mut help, arg.short_name).append(arg.sample_usage());
+        }
+        help.plain("\n").plain(indented(&arg.description, 4, width));
+        if let Some(text) = &arg.long_help {
+            help.plain("\n").plain(indented(text, 4, width));
+        }
+        let tag = |name: &str| self.message(name);
+        let mut details = Vec::new();
+        if arg.required {
+            details.push(tag("required"));
+        }
+        let aliases = arg.alias_names();
+        if !aliases.is_empty() {
+            details.push(format!("{}: {}", tag("aliases"), aliases.join(", ")));
+        }
+        if let Some(note) = &arg.deprecated {
+            details.push(format!("{}: {note}", tag("deprecated")));
+        }
+        for (other, value, fallback) in &arg.default_if {
+            details.push(format!(
+                "{}: {} if --{other} {value}",
+                tag("default"),
+                arg.display_value(fallback)
+            ));
+        }
+        if let Some(value) = &arg.default_value {
+            details.push(format!("{}: {}", tag("default"), arg.display_value(value)));
+        }
+        if !arg.choices.is_empty() {
+            details.push(format!("{}: {}", tag("choices"), arg.choices.join(", ")));
+        }
+        if let Some(range) = &arg.range {
+            details.push(format!("{}: {range}", tag("range")));
+        }
+        if let Some(env) = self.env_name(arg) {
+            details.push(format!("{}: {env}", tag("env")));
+        }
+        if !arg.required_unless.is_empty() {
+            details.push(format!(
+                "{}: {}",
+                tag("required unless"),
+                dashed(&arg.required_unless)
+            ));
+        }
+        if !arg.requires.is_empty() {
+            details.push(format!("{}: {}", tag("requires"), dashed(&arg.requires)));
+        }
+        if !arg.conflicts_with.is_empty() {
+            details.push(format!(
+                "{}: {}",
+                tag("conflicts with"),
+                dashed(&arg.conflicts_with)
+            ));
+        }
+        if !details.is_empty() || !arg.examples.is_empty() {
+            help.plain("\n");
+        }
+        for detail in details {
+            help.plain(format!("    {detail}\n"));
+        }
+        for example in &arg.examples {
+            help.plain(format!("    {}: ", tag("example")))
+                .literal(example)
+                .plain("\n");
+        }
+        help
+    }
+
+    /// Notes of the help page explaining the argument groups.
+    /// One section per group, headed by its name, telling how its members combine.
+    fn group_notes(&self) -> Styled {
+        let mut help = Styled::new();
+        for (i, group) in self.groups.iter().enumerate() {
+            let list = |names: &[String]| {
+                let mut list = Styled::new();
+                for arg in self
+                    .args
+                    .iter()
+                    .filter(|arg| names.contains(&arg.long_name))
+                {
+                    list.plain("\t").append(arg.synopsis()).plain("\n");
+                }
+                for name in names.iter().filter(|name| self.group(name).is_some()) {
+                    let options = self
+                        .message("the options of {group}")
+                        .replace("{group}", name);
+                    list.plain(format!("\t{options}\n"));
+                }
+                list
+            };
+            if i > 0 {
+                help.plain("\n");
+            }
+            help.heading(self.section_title(group.name())).plain("\n");
+            let line = |english: &str| format!("{}\n", self.message(english));
+            match group.kind() {
+                GroupKind::Exclusive => help
+                    .plain(line("The following option(s) are mutually exclusive and cannot be used together:"))
+                    .append(list(group.args())),
+                GroupKind::OnlyWhen => help
+                    .plain(line("The option(s):"))
+                    .append(list(group.args()))
+                    .plain(line("Can only be used in conjunction with:"))
+                    .append(list(group.parents())),
+                GroupKind::AnyOf => help
+                    .plain(line("At least one of the following option(s) must be used:"))
+                    .append(list(group.args())),
+                GroupKind::AllOrNone => help
+                    .plain(line("The following option(s) must be used together or not at all:"))
+                    .append(list(group.args())),
+            };
+        }
+        help
+    }
+
+    /// Describes the whole definition as JSON: the program, every argument with its kind, default,
+    /// choices and relations, the groups and the examples, for tools like documentation generators
+    /// or graphical wrappers. Defaults of sensitive arguments are left out.
+    /// The parsers of [`commands!`] are described together by the generated `to_json()`.
+    /// With the `serde` feature, the parser serializes the same description with `serde::Serialize`.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::integer("depth", Some('d'), false, "Maximum depth").default_value("4"));
+    /// assert!(parser.to_json().contains(r#""name": "depth", "short": "d", "kind": "integer""#));
+    /// ```
+    pub fn to_json(&self) -> String {
+        definition::render(self)
+    }
+
+    /// Documentation of the interface as Markdown, ready for a README: the usage line, a table of
+    /// the arguments and options with their short name, type, default value, requirement,
+    /// environment variable, choices or range and description, the argument groups and the
+    /// examples. Hidden arguments are left out and defaults of sensitive arguments are masked.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .executable_name("fdup")
+    ///     .arg(Arg::integer("depth", Some('d'), false, "Maximum depth").default_value("4"));
+    /// let markdown = parser.render_markdown();
+    /// assert!(markdown.contains("| `--depth <DEPTH>` | `-d` | integer | `4` | no |  |  | Maximum depth |"));
+    /// ```
+    pub fn render_markdown(&self) -> String {
+        markdown::render(self)
+    }
+
+    /// Writes a completion script for `shell` listing the options of this parser and their choices.
+    /// # Arguments
+    /// `shell` Shell the script is written for.
+    /// `out` Destination of the script, e.g. `std::io::stdout()`.
+    /// # Errors
+    /// If writing to `out` fails.
+    pub fn generate_completions(
+        &self,
+        shell: completions::Shell,
+        out: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        completions::generate(self, shell, out)
+    }
+
+    /// Writes a shell function wrapping the program, which prepends the options of
+    /// `<PREFIX>_DEFAULT_ARGS` and lets the program change the directory of the calling shell,
+    /// see [`wrapper`].
+    /// # Arguments
+    /// `shell` Shell the function is written for, bash, zsh or fish.
+    /// `out` Destination of the function, e.g. `std::io::stdout()`.
+    /// # Errors
+    /// If the shell is not supported, or writing to `out` fails.
+    pub fn generate_wrapper(
+        &self,
+        shell: completions::Shell,
+        out: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        wrapper::generate(self, shell, out)
+    }
+
+    /// Writes a completion script for the shell named `shell`, either built-in like `bash` or
+    /// added with [`completions::register`]. Handy to expose every supported shell through
+    /// a single `--completions <SHELL>` option, listing [`completions::backends`] as choices.
+    /// # Errors
+    /// If the shell is not supported, or writing to `out` fails.
+    pub fn generate_completions_for(
+        &self,
+        shell: &str,
+        out: &mut impl std::io::Write,
+    ) -> std::io::Result<()> {
+        completions::generate_named(self, shell, out)
+    }
+
+    /// Parse user command line arguments into a Map struct.
+    /// This parsing follows the argument requirements selected and consumes the parser.
+    /// # Returns
+    /// A map with all the parsed arguments.
+    ///
+    /// # Panics
+    /// It errors and stops execution when the argument requirements cannot be enforced.
+    /// Not being able to parse the arguments is considered a fatal error and the program
+    /// execution halts with a call to exit(0).
+    pub fn parse(self) -> ArgMap {
+        self.try_parse()
+            .unwrap_or_else(|error| self.exit_with(error))
+    }
+
+    /// Prints the help, version or `error` on behalf of [`ArgParser::parse`] and halts the execution.
+    fn exit_with(&self, error: ParseError) -> ! {
+        match error {
+            ParseError::HelpRequested { search } => {
+                let width = self.help_width();
+                let help = self.help_text(search.as_deref(), false, width);
+                self.halt_after(self.emit(&help, Stream::Stdout), 0)
+            }
+            ParseError::VerboseHelpRequested => {
+                let width = self.help_width();
+                let help = self.help_text(None, true, width);
+                self.halt_after(self.emit(&help, Stream::Stdout), 0)
+            }
+            ParseError::VersionRequested => {
+                let mut version = Styled::new();
+                version.plain(format!("{}\n", self.render_version()));
+                self.halt_after(self.emit(&version, Stream::Stdout), 0)
+            }
+            ParseError::VersionJsonRequested => {
+                let mut version = Styled::new();
+                version.plain(format!("{}\n", self.render_version_json()));
+                self.halt_after(self.emit(&version, Stream::Stdout), 0)
+            }
+            ParseError::VerboseVersionRequested => {
+                let mut version = Styled::new();
+                version.plain(format!("{}\n", self.render_long_version()));
+                self.halt_after(self.emit(&version, Stream::Stdout), 0)
+            }
+            ParseError::DebugRequested { dump } => {
+                let mut debug = Styled::new();
+                debug.plain(dump);
+                self.halt_after(self.emit(&debug, Stream::Stdout), 0)
+            }
+            error if self.emit_json_error(&error) => self.halt(self.error_exit_code.into()),
+            error => {
+                let mut message = Styled::new();
+                message.error(self.error_line(&error)).plain("\n");
+                if let (ErrorHelpStyle::Hint, Some(hint)) =
+                    (self.error_help_style, self.hint_text())
+                {
+                    message.append(hint).plain("\n");
+                }
+                let _ = self.emit(&message, Stream::Stderr);
+                match self.error_help_style {
+                    ErrorHelpStyle::Hint if self.help_enabled => {}
+                    ErrorHelpStyle::FullHelp => match &self.on_help {
+                        Some(hook) => hook(self),
+                        None => self.help(),
+                    },
+                    _ => {
+                        let mut usage = self.usage_text(self.help_width());
+                        if let Some(hint) = self.hint_text() {
+                            usage.append(hint).plain("\n");
+                        }
+                        let _ = self.emit(&usage, Stream::Stdout);
+                    }
+                }
+                self.halt(self.error_exit_code.into())
+            }
+        }
+    }
+
+    /// Parse user command line arguments like [`ArgParser::parse`], along with a report of where
+    /// every value came from, the groups in use and the time spent.
+    /// Help, version requests and errors halt the execution like [`ArgParser::parse`].
+    /// # Returns
+    /// A map with all the parsed arguments and the report of the parse.
+    /// # Example
+    /// ```no_run
+    /// # use clarg::{Arg, ArgParser};
+    /// let (args, report) = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::boolean("debug-cli", None, "Print how the arguments were parsed"))
+    ///     .parse_with_report();
+    /// if args.get::<bool>("debug-cli").unwrap_or(false) {
+    ///     eprint!("{report}");
+    /// }
+    /// ```
+    pub fn parse_with_report(self) -> (ArgMap, ParseReport) {
+        self.parse_and_report()
+    }
+
+    /// Parses the command line on behalf of [`ArgParser::parse_with_report`] and [`Cli`].
+    fn parse_and_report(&self) -> (ArgMap, ParseReport) {
+        let start = Instant::now();
+        let parsed = self.try_parse();
+        let elapsed = start.elapsed();
+        let map = parsed.unwrap_or_else(|error| self.exit_with(error));
+        let matched: Vec<(String, ValueSource)> = self
+            .args
+            .iter()
+            .filter_map(|arg| Some((arg.long_name.clone(), *map.sources.get(&arg.long_name)?)))
+            .collect();
+        let groups = self
+            .groups
+            .iter()
+            .filter(|group| {
+                matched.iter().any(|(name, source)| {
+                    !matches!(
+                        source,
+                        ValueSource::Default | ValueSource::Preset | ValueSource::Computed
+                    ) && group
+                        .args()
+                        .iter()
+                        .any(|member| self.member_args(member).contains(&name.as_str()))
+                })
+            })
+            .map(|group| group.name().clone())
+            .collect();
+        let report = ParseReport {
+            matched,
+            groups,
+            warnings: map.warnings.clone(),
+            elapsed,
+        };
+        (map, report)
+    }
+
+    /// Parse user command line arguments like [`ArgParser::parse`], without halting the execution.
+    /// Nothing is printed, so the caller decides how to report problems.
+    /// # Errors
+    /// The first problem found, or [`ParseError::HelpRequested`] when help was requested,
+    /// told apart from real problems with [`ParseError::is_benign`].
+    pub fn try_parse(&self) -> Result<ArgMap, ParseError> {
+        // skip executable name
+        self.try_parse_from_os(std::env::args_os().skip(1))
+    }
+
+    /// Parse the given arguments like [`ArgParser::parse`], halting the execution on errors.
+    /// Arguments that are not valid UTF-8 are accepted, see [`ArgParser::try_parse_from_os`].
+    /// # Arguments
+    /// `args` Arguments to parse, not including the executable name.
+    pub fn parse_from_os<I, S>(self, args: I) -> ArgMap
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.try_parse_from_os(args)
+            .unwrap_or_else(|error| self.exit_with(error))
+    }
+
+    /// Parse the given arguments like [`ArgParser::try_parse_from`], accepting arguments that
+    /// are not valid UTF-8, e.g. file names on Linux. Such values are validated with the invalid
+    /// parts replaced by `U+FFFD`, except that paths are looked for as given, and retrieved
+    /// untouched with [`ArgMap::get_os`] or as a `PathBuf` with [`ArgMap::get`]. The items of a
+    /// value split with [`Arg::value_delimiter`] keep the replacement characters.
+    /// # Arguments
+    /// `args` Arguments to parse, not including the executable name.
+    /// # Errors
+    /// The first problem found, or [`ParseError::HelpRequested`] when help was requested,
+    /// told apart from real problems with [`ParseError::is_benign`].
+    pub fn try_parse_from_os<I, S>(&self, args: I) -> Result<ArgMap, ParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let (args, mut originals): (Vec<String>, Vec<Option<OsString>>) = args
+            .into_iter()
+            .map(|arg| {
+                let arg = arg.as_ref();
+                match arg.to_str() {
+                    Some(text) => (text.to_owned(), None),
+                    None => (arg.to_string_lossy().into_owned(), Some(arg.to_owned())),
+                }
+            })
+            .unzip();
+        if originals.iter().all(Option::is_none) {
+            originals = Vec::new();
+        }
+        self.parsed(self.run(args.into_iter(), originals, true, false))
+    }
+
+    /// Parse a command line given as one string like [`ArgParser::parse`], halting the execution
+    /// on errors. See [`ArgParser::try_parse_str`].
+    /// # Arguments
+    /// `line` Arguments to parse, not including the executable name, e.g. `--path '/tmp/my dir'`.
+    pub fn parse_str(self, line: &str) -> ArgMap {
+        self.try_parse_str(line)
+            .unwrap_or_else(|error| self.exit_with(error))
+    }
+
+    /// Parse a command line given as one string like [`ArgParser::try_parse_from`], for input
+    /// typed in a REPL or a web page rather than passed by a shell. The line is split following
+    /// POSIX shell quoting: single quotes keep their content literally, double quotes and `\`
+    /// escape characters. Variables and globs are not expanded.
+    /// # Arguments
+    /// `line` Arguments to parse, not including the executable name, e.g. `--path '/tmp/my dir'`.
+    /// # Errors
+    /// A [`ParseError::InvalidQuoting`] when a quote is left open or the line ends with a lone `\`,
+    /// otherwise like [`ArgParser::try_parse_from`].
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::string("path", Some('p'), true, "Directory to scan"));
+    /// let args = parser.try_parse_str(r#"--path "/tmp/my dir""#).unwrap();
+    /// assert_eq!(args.get::<String>("path").unwrap(), "/tmp/my dir");
+    /// ```
+    pub fn try_parse_str(&self, line: &str) -> Result<ArgMap, ParseError> {
+        let args = shell::split(line).map_err(|message| ParseError::InvalidQuoting { message })?;
+        self.try_parse_from(args)
+    }
+
+    /// Parse the given arguments like [`ArgParser::try_parse`].
+    /// The environment and configuration files are consulted as usual.
+    ///
+    /// Parsing takes time linear in the number of arguments, times the number of options declared
+    /// for option lookups. Values are moved rather than copied and valid values allocate nothing,
+    /// so tens of thousands of file names from `xargs` or a shell glob parse in milliseconds,
+    /// see `benches/huge_argv.rs`.
+    /// # Arguments
+    /// `args` Arguments to parse, not including the executable name.
+    /// # Errors
+    /// The first problem found, or [`ParseError::HelpRequested`] when help was requested,
+    /// told apart from real problems with [`ParseError::is_benign`].
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser, ParseError};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::integer("depth", Some('d'), false, "Maximum depth"));
+    /// let error = parser.try_parse_from(["--depth", "deep"]).unwrap_err();
+    /// assert!(matches!(error, ParseError::TypeMismatch { .. }));
+    /// assert_eq!(error.arg(), Some("depth"));
+    /// ```
+    pub fn try_parse_from<I, S>(&self, args: I) -> Result<ArgMap, ParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let args = args.into_iter().map(|arg| arg.as_ref().to_owned());
+        self.parsed(self.run(args, Vec::new(), true, false))
+    }
+
+    /// The outcome of parsing, from the result of [`ArgParser::run`].
+    fn parsed(&self, scan: Result<Scan, Vec<ParseError>>) -> Result<ArgMap, ParseError> {
+        match scan {
+            Ok(Scan::Parsed(mut map)) => {
+                self.remember(&mut map);
+                Ok(*map)
+            }
+            Ok(Scan::Help {
+                verbose: true,
+                search: None,
+            }) => Err(ParseError::VerboseHelpRequested),
+            Ok(Scan::Help { search, .. }) => Err(ParseError::HelpRequested { search }),
+            Ok(Scan::Version { json: true, .. }) => Err(ParseError::VersionJsonRequested),
+            Ok(Scan::Version { verbose: true, .. }) => Err(ParseError::VerboseVersionRequested),
+            Ok(Scan::Version { .. }) => Err(ParseError::VersionRequested),
+            Ok(Scan::Debug(mut map)) if self.debug_action == DebugAction::Continue => {
+                let _ = self.write(Stream::Stderr, &map.debug_dump());
+                self.remember(&mut map);
+                Ok(*map)
+            }
+            Ok(Scan::Debug(map)) => Err(ParseError::DebugRequested {
+                dump: map.debug_dump(),
+            }),
+            Ok(Scan::HelpShown(map)) => Ok(*map),
+            Err(mut errors) => Err(errors.swap_remove(0)),
+        }
+    }
+
+    /// Parse the arguments the parser knows and leave the others, for a second parser whose
+    /// arguments depend on the first ones, e.g. options registered by the plugin chosen with
+    /// `--plugin`. Unknown arguments are never an error here, and `--help` and `--version` are
+    /// left for the second pass, so its help page lists every option.
+    /// # Arguments
+    /// `args` Arguments without the executable name.
+    /// # Returns
+    /// The parsed arguments, and the words left over in order, followed by `--` and the trailing
+    /// arguments, if any. The value of an unknown option is left over unless the parser has a
+    /// positional argument to give it to.
+    /// # Errors
+    /// The first problem with the arguments the parser knows, as for [`ArgParser::try_parse_from`].
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let loader = ArgParser::new("Archive files.")
+    ///     .arg(Arg::string("plugin", Some('p'), true, "Archive format"));
+    /// let (args, rest) = loader.parse_known(["--plugin", "zip", "--level", "9", "--help"]).unwrap();
+    /// assert_eq!(args.get::<String>("plugin").unwrap(), "zip");
+    /// assert_eq!(rest, ["--level", "9", "--help"]);
+    ///
+    /// let zip = ArgParser::new("Archive files as zip.")
+    ///     .arg(Arg::integer("level", Some('l'), false, "Compression level"));
+    /// assert!(zip.try_parse_from(&rest).unwrap_err().is_benign());
+    /// ```
+    pub fn parse_known<I, S>(&self, args: I) -> Result<(ArgMap, Vec<String>), ParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        match self.run(
+            args.into_iter().map(|arg| arg.as_ref().to_owned()),
+            Vec::new(),
+            true,
+            true,
+        ) {
+            Ok(Scan::Parsed(mut map)) => {
+                let mut rest = std::mem::take(&mut map.unknown);
+                if !map.trailing.is_empty() {
+                    rest.push("--".to_owned());
+                    rest.append(&mut map.trailing);
+                }
+                Ok((*map, rest))
+            }
+            // help, version and debug output are only requested through the leftovers.
+            Ok(_) => unreachable!("help and version are left over"),
+            Err(mut errors) => Err(errors.swap_remove(0)),
+        }
+    }
+
+    /// Saves the options of `map` for `--again`, see [`ArgParser::remember_last`].
+    fn remember(&self, map: &mut ArgMap) {
+        let Some(path) = &self.last_run else {
+            return;
+        };
+        let words = map.args_of(
+            |arg| {
+                let source = map.sources.get(&arg.name);
+                !map.is_sensitive(&arg.name)
+                    && matches!(
+                        source,
+                        Some(ValueSource::CommandLine | ValueSource::LastRun)
+                    )
+            },
+            false,
+        );
+        let words: Vec<String> = words.iter().map(|word| response::quote(word)).collect();
+        if let Err(e) = std::fs::write(path, words.join(" ") + "\n") {
+            map.warnings.push(format!(
+                "Cannot save the arguments to `{}`: {e}",
+                path.display()
+            ));
+        }
+    }
+
+    /// Parse the options of `args` up front and read the values of the last positional argument
+    /// one at a time, when it takes multiple values, so tens of thousands of file names given by
+    /// `xargs` are never held at once. The options must come before the streamed values.
+    /// # Arguments
+    /// `args` Arguments without the executable name, e.g. `std::env::args().skip(1)`.
+    /// # Errors
+    /// The first error in the options, or a request for help or the version, as for
+    /// [`ArgParser::try_parse_from`]. Invalid values are reported by the stream, one at a time.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Compress files.")
+    ///     .arg(Arg::integer("level", Some('l'), false, "Compression level").default_value("6"))
+    ///     .arg(Arg::path("file", None, true, "Files to compress").positional(true).multiple(true));
+    /// let mut stream = parser.scan(["-l", "9", "a.txt", "b.txt"]).unwrap();
+    /// assert_eq!(stream.options().get::<i64>("level").unwrap(), 9);
+    /// let files: Vec<String> = stream.by_ref().map(Result::unwrap).collect();
+    /// assert_eq!(files, ["a.txt", "b.txt"]);
+    /// ```
+    pub fn scan<'a, I, S>(&'a self, args: I) -> Result<ArgStream<'a>, ParseError>
+    where
+        I: IntoIterator<Item = S>,
+        I::IntoIter: 'a,
+        S: AsRef<str>,
+    {
+        let args = args.into_iter().map(|arg| arg.as_ref().to_owned());
+        stream::scan(self, Box::new(args))
+    }
+
+    /// Validate an argument vector against the argument requirements without building an [`ArgMap`].
+    /// Neither the environment nor configuration files are consulted, and the execution is never halted.
+    /// Requesting help is considered valid, and the [`ArgParser::validator`] closures are not run
+    /// since they need the [`ArgMap`].
+    /// # Arguments
+    /// `args` Arguments to validate, not including the executable name.
+    /// # Errors
+    /// Every problem found in the arguments, in the order they were found.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::string("path", Some('p'), true, "Directory to examine"))
+    ///     .validator(|args| match args.get::<String>("path")?.starts_with('/') {
+    ///         true => Ok(()),
+    ///         false => Err("--path must be absolute.".to_owned()),
+    ///     });
+    /// assert!(parser.check(["--path", "/tmp"]).is_ok());
+    /// assert!(parser.check(["--path", "tmp"]).is_ok());
+    /// assert_eq!(parser.check(["--bogus"]).unwrap_err().len(), 2);
+    /// ```
+    pub fn check<I, S>(&self, args: I) -> Result<(), Vec<ParseError>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.scan_checked(
+            args.into_iter().map(|arg| arg.as_ref().to_owned()),
+            Vec::new(),
+            false,
+            false,
+        )
+        .map(|_| ())
+    }
+
+    /// Freezes the definition into a [`Cli`], which parses, prints help and generates completions
+    /// any number of times and is cheap to clone. The definition is checked once here with
+    /// [`ArgParser::validate`], in every build.
+    /// # Panics
+    /// If the definition is invalid, listing every problem found.
+    pub fn build(mut self) -> Cli {
+        if let Err(problems) = self.validate() {
+            panic!("Invalid definition of {}:\n{problems}", self.executable);
+        }
+        self.validated = true;
+        Cli::new(self)
+    }
+
+    /// Checks the definition of the parser itself: long or short names used by two arguments
+    /// (aliases and `--no-` forms included) or by an argument and a built-in option, group names
+    /// used twice or by an argument, and groups or relations naming unknown arguments.
+    /// Parsing runs this check in debug builds and panics on problems, release builds skip it.
+    /// # Errors
+    /// Every problem found, one per line.
+    /// # Example
+    /// ```
+    /// # use clarg::{Arg, ArgParser};
+    /// let parser = ArgParser::new("Find duplicate files.")
+    ///     .arg(Arg::boolean("verbose", Some('v'), "Verbose execution"))
+    ///     .arg(Arg::string("version-file", Some('v'), false, "File holding the version"));
+    /// assert!(parser.validate().unwrap_err().contains("`-v`"));
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        let mut problems = Vec::new();
+        // owner of each name, e.g. "argument `verbose`".
+        let mut longs: HashMap<String, String> = HashMap::new();
+        let mut shorts: HashMap<char, String> = HashMap::new();
+        if self.config_file.is_some() {
+            longs.insert(
+                "profile".to_owned(),
+                "the built-in profile option".to_owned(),
+            );
+        }
+        if !self.presets.is_empty() {
+            longs.insert("preset".to_owned(), "the built-in preset option".to_owned());
+        }
+        if self.last_run.is_some() {
+            longs.insert("again".to_owned(), "the built-in again flag".to_owned());
+        }
+        if self.help_enabled {
+            for &c in &self.help_short_aliases {
+                shorts.insert(c, "the built-in help flag".to_owned());
+            }
+        }
+        if self.debug_enabled {
+            longs.insert(
+                self.debug_flag.clone(),
+                "the built-in debug flag".to_owned(),
+            );
+        }
+        for arg in &self.args {
+            let mut names: Vec<String> = std::iter::once(&arg.long_name)
+                .chain(&arg.aliases)
+                .chain(&arg.hidden_aliases)
+                .cloned()
+                .collect();
+            if arg.negatable {
+                names.push(format!("no-{}", arg.long_name));
+            }
+            for mut name in names {
+                if self.ignore_case {
+                    name.make_ascii_lowercase();
+                }
+                match longs.get(&name) {
+                    Some(owner) => problems.push(format!(
+                        "Option `--{name}` of argument `{}` is already used by {owner}.",
+                        arg.long_name
+                    )),
+                    None => {
+                        longs.insert(name, format!("argument `{}`", arg.long_name));
+                    }
+                }
+            }
+            let own_shorts = arg.short_name.iter().chain(&arg.short_aliases);
+            for &c in own_shorts.chain(&arg.hidden_short_aliases) {
+                match shorts.get(&c) {
+                    Some(owner) => problems.push(format!(
+                        "Short option `-{c}` of argument `{}` is already used by {owner}.",
+                        arg.long_name
+                    )),
+                    None => {
+                        shorts.insert(c, format!("argument `{}`", arg.long_name));
+                    }
+                }
+            }
+            let conditions: Vec<String> = arg
+                .default_if
+                .iter()
+                .map(|(other, ..)| other.clone())
+                .collect();
+            let relations = [
+                ("default_value_if", &conditions),
+                ("requires", &arg.requires),
+                ("conflicts_with", &arg.conflicts_with),
+                ("overrides_with", &arg.overrides_with),
+                ("required_unless", &arg.required_unless),
+            ];
+            for (relation, names) in relations {
+                for name in names.iter().filter(|name| !self.has_arg(name)) {
+                    problems.push(format!(
+                        "Argument `{}` refers to unknown argument `{name}` in `{relation}`.",
+                        arg.long_name
+                    ));
+                }
+            }
+            if let Some(name) = arg.alias_of.as_ref().filter(|name| !self.has_arg(name)) {
+                problems.push(format!(
+                    "Argument `{}` is a deprecated alias of unknown argument `{name}`.",
+                    arg.long_name
+                ));
+            }
+        }
+        for name in &self.presets {
+            for (arg, _) in self.preset_values[name]
+                .iter()
+                .filter(|(arg, _)| !self.has_arg(arg))
+            {
+                problems.push(format!(
+                    "Preset `{name}` refers to unknown argument `{arg}`."
+                ));
+            }
+        }
+        let mut groups = HashSet::new();
+        for group in &self.groups {
+            if !groups.insert(group.name()) {
+                problems.push(format!("Group `{}` is defined twice.", group.name()));
+            }
+            if self.has_arg(group.name()) {
+                problems.push(format!(
+                    "Group `{}` has the name of an argument.",
+                    group.name()
+                ));
+            }
+            for name in group
+                .args()
+                .iter()
+                .chain(group.parents())
+                .filter(|name| !self.has_arg(name) && self.group(name).is_none())
+            {
+                problems.push(format!(
+                    "Group `{}` refers to unknown argument or group `{name}`.",
+                    group.name()
+                ));
+            }
+            let mut pending: Vec<&String> = group.args().iter().collect();
+            let mut entered = HashSet::new();
+            while let Some(name) = pending.pop() {
+                if name == group.name() {
+                    problems.push(format!("Group `{name}` contains itself."));
+                    break;
+                }
+                if let Some(nested) = self.group(name).filter(|_| entered.insert(name)) {
+                    pending.extend(nested.args());
+                }
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("\n"))
+        }
+    }
+
+    /// Error for a name given to `--preset` that no preset has, suggesting the closest one.
+    fn unknown_preset(&self, value: String) -> ParseError {
+        let suggestion =
+            suggest::closest(&value, self.presets.iter().map(String::as_str)).map(str::to_owned);
+        let hint = match &suggestion {
+            Some(name) => format!(". Did you mean `{name}`?"),
+            None => String::new(),
+        };
+        ParseError::InvalidChoice {
+            arg: "preset".to_owned(),
+            message: format!(
+                "Invalid value `{value}` for argument: --preset. Valid choices are: [{}]{hint}",
+                self.presets.join(", ")
+            ),
+            value,
+            choices: self.presets.clone(),
+            suggestion,
+        }
+    }
+
+    /// Checks whether `token` requests the built-in help.
+    fn is_help_flag(&self, token: &str) -> bool {
+        let alias = token.strip_prefix('-').is_some_and(|name| {
+            let mut chars = name.chars();
+            matches!((chars.next(), chars.next()), (Some(c), None) if self.help_short_aliases.contains(&c))
+        });
+        self.help_enabled
+            && (token == "--help" || token == "-h" || alias || token.starts_with("--help="))
+    }
+
+    /// Checks whether `token` requests the built-in version, possibly in a given format.
+    fn is_version_flag(&self, token: &str) -> bool {
+        (matches!(token, "--version" | "--version=json" | "--version=text") && self.version_long())
+            || (token == "-V" && self.version_short().is_some())
+    }
+
+    /// Group named `name`, if any.
+    fn group(&self, name: &str) -> Option<&ArgGroup> {
+        self.groups.iter().find(|group| group.name() == name)
+    }
+
+    /// Arguments standing for the member `name` of a group: the argument itself, or the
+    /// arguments of the group named `name`, with those of its own nested groups in their place.
+    fn member_args<'a>(&'a self, name: &'a str) -> Vec<&'a str> {
+        let mut args = Vec::new();
+        let mut pending = vec![(name, 0)];
+        while let Some((name, depth)) = pending.pop() {
+            match self.group(name) {
+                // cycles, reported by `ArgParser::validate`, stop once every group was entered.
+                Some(group) if depth <= self.groups.len() => pending.extend(
+                    group
+                        .args()
+                        .iter()
+                        .rev()
+                        .map(|member| (member.as_str(), depth + 1)),
+                ),
+                Some(_) => {}
+                None => args.push(name),
+            }
+        }
+        args
+    }
+
+    /// Checks whether an argument is declared with the long name `name`.
+    fn has_arg(&self, name: &str) -> bool {
+        self.args.iter().any(|arg| arg.long_name == name)
+    }
+
+    /// Scans and validates the arguments.
+    /// # Arguments
+    /// `args` Arguments to scan, not including the executable name.
+    /// `originals` The arguments that are not valid UTF-8 as given, at their position in `args`,
+    /// empty when they all are.
+    /// `sources` Whether the environment and configuration files provide values.
+    /// `known` Whether only the arguments of the parser are taken, see [`ArgParser::parse_known`].
+    /// # Returns
+    /// The parsed arguments, or the search pattern, if any, when help was requested.
+    /// # Errors
+    /// Every problem found, in the order they were found.
+    fn run(
+        &self,
+        args: impl Iterator<Item = String>,
+        originals: Vec<Option<OsString>>,
+        sources: bool,
+        known: bool,
+    ) -> Result<Scan, Vec<ParseError>> {
+        match self.scan_checked(args, originals, sources, known)? {
+            ControlFlow::Break(scan) => Ok(scan),
+            ControlFlow::Continue(state) => self.build_map(state),
+        }
+    }
+
+    /// Scans the arguments and checks them up to their relations, without building the
+    /// [`ArgMap`] nor running the validators, which is all [`ArgParser::check`] needs.
+    /// Takes the same arguments as [`ArgParser::run`].
+    /// # Returns
+    /// The scan when it ended early, e.g. for help, or the state to build the map from.
+    /// # Errors
+    /// Every problem found, in the order they were found.
+    fn scan_checked(
+        &self,
+        args: impl Iterator<Item = String>,
+        originals: Vec<Option<OsString>>,
+        sources: bool,
+        known: bool,
+    ) -> Result<ControlFlow<Scan, Scanning>, Vec<ParseError>> {
+        // a mistake in the definition, not in the arguments, so it is caught during development.
+        if cfg!(debug_assertions) && !self.validated {
+            if let Err(problems) = self.validate() {
+                panic!("Invalid definition of {}:\n{problems}", self.executable);
+            }
+        }
+        let mut state = Scanning {
+            sources,
+            known,
+            unknown_args: match (known, self.unknown_args) {
+                (true, UnknownArgPolicy::Error) => UnknownArgPolicy::Collect,
+                (_, policy) => policy,
+            },
+            originals,
+            ..Scanning::default()
+        };
+        let words = self.expand_words(args, &mut state).map_err(|e| vec![e])?;
+        if let Some(scan) = self.match_words(words, &mut state)? {
+            return Ok(ControlFlow::Break(scan));
+        }
+        // the arguments given before help are not complete, so they are not checked any further.
+        if state.help_shown {
+            return Ok(ControlFlow::Continue(state));
+        }
+        self.fill_sources(&mut state);
+        self.check_values(&mut state);
+        self.fill_defaults(&mut state);
+        self.check_relations(&mut state);
+        if !state.errors.is_empty() {
+            return Err(state.errors);
+        }
+        Ok(ControlFlow::Continue(state))
+    }
+
+    /// Words to scan: the options of [`ArgParser::env_args`] and of the last run for `--again`,
+    /// then `args` with their response files expanded.
+    /// # Errors
+    /// A [`ParseError`] when a response file can't be read.
+    fn expand_words<'w>(
+        &self,
+        args: impl Iterator<Item = String> + 'w,
+        state: &mut Scanning,
+    ) -> Result<Box<dyn Iterator<Item = String> + 'w>, ParseError> {
+        // options of `ArgParser::env_args` come first, so the command line overrides them.
+        let mut injected = Vec::new();
+        if let Some(variable) = self.env_args.as_ref().filter(|_| state.sources) {
+            let value = self.host.var(variable).unwrap_or_default();
+            match shell::split(&value.to_string_lossy()) {
+                Ok(words) => injected = words,
+                Err(message) => state.errors.push(ParseError::Environment {
+                    variable: variable.clone(),
+                    error: Box::new(ParseError::Config { message }),
+                }),
+            }
+        }
+        state.injected = injected.len();
+        let mut args: Box<dyn Iterator<Item = String> + 'w> = Box::new(args);
+        if self.response_files {
+            let literal = |option: &str| self.file_ref_option(option);
+            let expanded = response::expand(args.collect(), 0, &literal)?;
+            if !state.originals.is_empty() {
+                state.originals = expanded
+                    .iter()
+                    .map(|(_, at)| at.and_then(|at| state.originals[at].take()))
+                    .collect();
+            }
+            args = Box::new(expanded.into_iter().map(|(word, _)| word));
+        }
+        // then the options of the last run for `--again`, see `ArgParser::remember_last`.
+        if let Some(path) = &self.last_run {
+            let mut words: Vec<String> = args.collect();
+            let end = words.iter().position(|word| word == "--");
+            let again = words[..end.unwrap_or(words.len())]
+                .iter()
+                .position(|word| word == "--again");
+            if let Some(at) = again {
+                words.remove(at);
+                if at < state.originals.len() {
+                    state.originals.remove(at);
+                }
+            }
+            if again.is_some() && state.sources {
+                let saved = std::fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|line| shell::split(&line));
+                match saved {
+                    Ok(saved) => injected.extend(saved),
+                    Err(e) => state.errors.push(ParseError::Config {
+                        message: format!(
+                            "No last run to repeat, `{}` cannot be read: {e}",
+                            path.display()
+                        ),
+                    }),
+                }
+            }
+            args = Box::new(words.into_iter());
+        }
+        state.replayed = injected.len();
+        Ok(Box::new(injected.into_iter().chain(args)))
+    }
+
+    /// Matches `words` against the options and positional arguments, storing their values.
+    /// # Returns
+    /// What was requested instead of parsing, like help or the version, if anything.
+    /// # Errors
+    /// The limit exceeded, see [`ArgParser::max_args`] and [`ArgParser::max_value_length`].
+    fn match_words(
+        &self,
+        words: impl Iterator<Item = String>,
+        state: &mut Scanning,
+    ) -> Result<Option<Scan>, Vec<ParseError>> {
+        // flags expanded from a cluster of short names like `-vvv`, scanned before the next argument.
+        let mut clustered: VecDeque<String> = VecDeque::new();
+        // positional arguments are filled in declaration order, a multi-value one takes every remaining word.
+        let positionals: Vec<&Arg> = self.args.iter().filter(|arg| arg.positional).collect();
+        let mut position = 0;
+        // whether positional values given after those of the last run filled them again.
+        let mut restarted = false;
+        let (injected, replayed) = (state.injected, state.replayed);
+        // words taken so far, to tell injected ones apart.
+        let pulled = std::cell::Cell::new(0);
+        // position and length of a word over `ArgParser::max_value_length`, which ends the scan.
+        let oversized = std::cell::Cell::new(None);
+        let max_length = self.max_value_length.unwrap_or(usize::MAX);
+        let mut arguments = words
+            .inspect(|word| {
+                pulled.set(pulled.get() + 1);
+                if word.len() > max_length {
+                    oversized.set(Some((pulled.get(), word.len())));
+                }
+            })
+            .take_while(|word| word.len() <= max_length)
+            // one word past `ArgParser::max_args` tells there are too many.
+            .take(
+                self.max_args
+                    .map_or(usize::MAX, |max| max.saturating_add(1)),
+            )
+            .peekable();
+        let mut source = ValueSource::CommandLine;
+        while let Some(mut arg) = clustered.pop_front().or_else(|| {
+            let arg = arguments.next();
+            // nothing is peeked right after `next`, so the last word pulled is `arg`.
+            source = match pulled.get() {
+                pulled if pulled <= injected => ValueSource::Environment,
+                pulled if pulled <= replayed => ValueSource::LastRun,
+                _ => ValueSource::CommandLine,
+            };
+            arg
+        }) {
+            // the word just pulled, or the cluster of short names it came from.
+            let at = pulled.get();
+            if let Some(option) = self.slash_option(&arg) {
+                arg = option;
+            }
+            if let Some(flags) = scan::cluster(&self.args, &arg) {
+                clustered.extend(flags);
+                continue;
+            }
+            match self.expand_abbreviation(&arg) {
+                Ok(Some(option)) => arg = option,
+                Ok(None) => {}
+                Err(e) => {
+                    state.errors.push(e);
+                    continue;
+                }
+            }
+            // help and version take precedence over any problem found before, like in GNU tools.
+            // the pass over the leftovers handles help and version.
+            if state.known && (self.is_help_flag(&arg) || self.is_version_flag(&arg)) {
+                state.unknown.push(arg);
+                continue;
+            }
+            if self.is_help_flag(&arg) {
+                if let Some(hook) = self.on_help.as_ref().filter(|_| state.sources) {
+                    hook(self);
+                    state.help_shown = true;
+                    break;
+                }
+                // `--help=<topic>`, `--help <topic>` or `--help --verbose`.
+                if let Some((_, topic)) = arg.split_once('=') {
+                    return Ok(Some(Scan::Help {
+                        search: Some(topic.to_owned()),
+                        verbose: false,
+                    }));
+                }
+                let verbose = arguments.next_if(|value| value == "--verbose").is_some();
+                let search = arguments.next_if(|value| !value.starts_with('-'));
+                return Ok(Some(Scan::Help { search, verbose }));
+            }
+            if self.is_version_flag(&arg) {
+                // `--version --format json`, or `--version=json`.
+                let format = match arg.split_once('=') {
+                    Some((_, format)) => Some(format.to_owned()),
+                    None => arguments
+                        .next_if(|value| value == "--format")
+                        .and_then(|_| arguments.next()),
+                };
+                let verbose = arguments.next_if(|value| value == "--verbose").is_some();
+                return Ok(Some(Scan::Version {
+                    json: format.as_deref() == Some("json"),
+                    verbose,
+                }));
+            }
+
+            // everything after the separator is collected verbatim.
+            if arg == "--" {
+                // replacing the trailing arguments of the last run, if any.
+                state.trailing.clear();
+                if source == ValueSource::LastRun {
+                    // the separator of the last run only ends its own words.
+                    state
+                        .trailing
+                        .extend(arguments.by_ref().take(replayed - pulled.get()));
+                    continue;
+                }
+                state.trailing.extend(arguments.by_ref());
+                break;
+            }
+
+            // `--output=file.txt`, `-n5` or `-ooutput.txt` give the value right after the name.
+            let mut inline = None;
+            if let Some((option, value)) = scan::inline_value(&self.args, &arg) {
+                (arg, inline) = (option, Some(value));
+            }
+
+            if self.config_file.is_some() && arg == "--profile" {
+                let value = inline.or_else(|| arguments.next_if(|value| !value.starts_with('-')));
+                match value {
+                    Some(value) => state.profile = Some(value),
+                    None => state.errors.push(ParseError::MissingValue {
+                        arg: "profile".to_owned(),
+                        option: arg,
+                    }),
+                }
+                continue;
+            }
+
+            if self.debug_enabled
+                && !state.known
+                && arg.strip_prefix("--") == Some(self.debug_flag.as_str())
+            {
+                state.debug = true;
+                continue;
+            }
+
+            if !self.presets.is_empty() && arg == "--preset" {
+                let value = inline.or_else(|| arguments.next_if(|value| !value.starts_with('-')));
+                match value {
+                    Some(value) if self.preset_values.contains_key(&value) => {
+                        state.preset = Some(value)
+                    }
+                    Some(value) => state.errors.push(self.unknown_preset(value)),
+                    None => state.errors.push(ParseError::MissingValue {
+                        arg: "preset".to_owned(),
+                        option: arg,
+                    }),
+                }
+                continue;
+            }
+
+            // positional values of the command line replace the ones of the last run.
+            if !restarted
+                && source == ValueSource::CommandLine
+                && !arg.starts_with('-')
+                && positionals
+                    .iter()
+                    .any(|inner| state.scanned.get(&inner.long_name) == Some(&ValueSource::LastRun))
+            {
+                restarted = true;
+                position = 0;
+                for inner in &positionals {
+                    if state.scanned.get(&inner.long_name) == Some(&ValueSource::LastRun) {
+                        state.argument_map.remove(&inner.long_name);
+                        state.occurrences.remove(&inner.long_name);
+                        state.scanned.remove(&inner.long_name);
+                    }
+                }
+            }
+            // negative numbers are values of numeric positional arguments.
+            if scan::is_option(&arg, positionals.get(position).copied()) {
+                if !self.match_option(state, arg, inline, source, &mut arguments, &pulled) {
+                    break;
+                }
+            } else if let Some(inner) = positionals.get(position) {
+                if !inner.multiple {
+                    position += 1;
+                }
+                self.match_positional(state, inner, arg, at, source);
+            } else {
+                match state.unknown_args {
+                    UnknownArgPolicy::Error => state
+                        .errors
+                        .push(ParseError::UnexpectedArgument { value: arg }),
+                    UnknownArgPolicy::Collect => state.unknown.push(arg),
+                    UnknownArgPolicy::Passthrough => {
+                        state.unknown.push(arg);
+                        state.unknown.extend(arguments.by_ref());
+                        break;
+                    }
+                }
+            }
+        }
+        // hostile input ends the parsing right away, whatever was found before.
+        if let Some((position, length)) = oversized.get() {
+            return Err(vec![ParseError::LimitExceeded {
+                arg: None,
+                limit: max_length,
+                code: "E_VALUE_TOO_LONG",
+                message: format!(
+                    "Argument #{position} is {length} bytes long, at most {max_length} are accepted."
+                ),
+            }]);
+        }
+        if let Some(max) = self.max_args.filter(|max| pulled.get() > *max) {
+            return Err(vec![ParseError::LimitExceeded {
+                arg: None,
+                limit: max,
+                code: "E_TOO_MANY_ARGS",
+                message: format!("Too many arguments, at most {max} are accepted."),
+            }]);
+        }
+        Ok(None)
+    }
+
+    /// Matches the option `arg`, given its value after `=` or the short name in `inline`, and
+    /// takes the values it needs from `arguments`, of which `pulled` counts the words taken.
+    /// # Returns
+    /// Whether the scan goes on, the remaining words being passed through otherwise, see
+    /// [`UnknownArgPolicy::Passthrough`].
+    fn match_option<I: Iterator<Item = String>>(
+        &self,
+        state: &mut Scanning,
+        mut arg: String,
+        mut inline: Option<String>,
+        source: ValueSource,
+        arguments: &mut std::iter::Peekable<I>,
+        pulled: &std::cell::Cell<usize>,
+    ) -> bool {
+        // the word holding the option, or the cluster of short names it came from.
+        let at = pulled.get();
+        let arg_name = arg.trim_start_matches('-').to_owned();
+        let mut actual_argument = scan::find(&self.args, &arg);
+        // `--no-<name>` turns off negatable flags.
+        let mut negated = false;
+        let positive = match arg_name.get(..3) {
+            Some(prefix) if self.ignore_case && prefix.eq_ignore_ascii_case("no-") => {
+                Some(&arg_name[3..])
+            }
+            _ => arg_name.strip_prefix("no-"),
+        };
+        if let (None, Some(name)) = (actual_argument, positive) {
+            actual_argument = self
+                .args
+                .iter()
+                .find(|arg| arg.negatable && arg.kind == ArgKind::Boolean && arg.answers_to(name));
+            negated = actual_argument.is_some();
+        }
+        if let Some(inner) = actual_argument.filter(|inner| !inner.is_supported()) {
+            // its value, if any, goes along with it.
+            if !matches!(inner.kind, ArgKind::Boolean | ArgKind::Count)
+                && inner.optional_value.is_none()
+                && inline.is_none()
+            {
+                arguments.next_if(|value| !value.starts_with('-'));
+            }
+            state.errors.push(ParseError::UnsupportedOs {
+                arg: inner.long_name.clone(),
+                option: arg,
+                os: Os::current()
+                    .map_or_else(|| std::env::consts::OS.to_owned(), |os| os.to_string()),
+            });
+            return true;
+        }
+        let Some(inner) = actual_argument else {
+            // an unknown option is kept as given.
+            if let Some(value) = inline.take() {
+                arg = format!("{arg}={value}");
+            }
+            match state.unknown_args {
+                UnknownArgPolicy::Error => {
+                    // Got an unexpected argument, suggest the closest long name in case of a typo.
+                    let suggestion = self
+                        .suggest_option(&arg_name)
+                        .filter(|_| arg_name.chars().count() > 1);
+                    state.errors.push(ParseError::UnknownOption {
+                        option: arg,
+                        suggestion,
+                    });
+                }
+                UnknownArgPolicy::Collect => state.unknown.push(arg),
+                UnknownArgPolicy::Passthrough => {
+                    state.unknown.push(arg);
+                    state.unknown.extend(arguments.by_ref());
+                    return false;
+                }
+            }
+            return true;
+        };
+        if let Some(note) = inner.deprecated.as_ref().filter(|_| state.sources) {
+            if state.warned.insert(inner.long_name.clone()) {
+                self.warn(&format!("`{arg}` is deprecated, {note}"));
+            }
+        }
+        // flags take no value, e.g. `--verbose=yes`.
+        if matches!(inner.kind, ArgKind::Boolean | ArgKind::Count) {
+            if let Some(value) = inline.take() {
+                state.errors.push(ParseError::UnexpectedValue {
+                    arg: inner.long_name.clone(),
+                    option: arg,
+                    value: inner.display_value(&value).to_owned(),
+                });
+                return true;
+            }
+        }
+        // the command line replaces the values of the last run, counts start over.
+        let replayed = source != ValueSource::LastRun
+            && state.scanned.get(&inner.long_name) == Some(&ValueSource::LastRun);
+        // validate the type of argument we got
+        match inner.kind {
+            // this is a boolean flag, having listed, means we set it.
+            ArgKind::Boolean => {
+                state.scanned.insert(inner.long_name.clone(), source); // we got this value, don't expect
+                let value = if negated { "false" } else { "true" };
+                self.notify(state, &inner.long_name, value, at);
+                state
+                    .argument_map
+                    .insert(inner.long_name.clone(), vec![value.to_owned()]);
+            }
+            ArgKind::Count => {
+                state.scanned.insert(inner.long_name.clone(), source);
+                let count = match replayed {
+                    true => 1,
+                    false => {
+                        state
+                            .occurrences
+                            .get(&inner.long_name)
+                            .copied()
+                            .unwrap_or(0)
+                            + 1
+                    }
+                };
+                self.notify(state, &inner.long_name, &count.to_string(), at);
+                state
+                    .argument_map
+                    .insert(inner.long_name.clone(), vec![count.to_string()]);
+            }
+            _ => {
+                // a word taken right after the option was the last one pulled.
+                let (value, index) = match (&inner.optional_value, inline.take()) {
+                    (Some(_), Some(value)) => (value, at),
+                    // the next word is only taken when it is one of the choices.
+                    (Some(present), None) if inner.require_equals => (present.clone(), at),
+                    (Some(present), None) => {
+                        match arguments.next_if(|value| inner.choice(value).is_some()) {
+                            Some(value) => (value, pulled.get()),
+                            None => (present.clone(), at),
+                        }
+                    }
+                    (None, Some(value)) => (value, at),
+                    (None, None) => {
+                        // `--path --help` asks for help rather than giving `--help` as the path.
+                        let next = scan::next_value(inner, arguments, |value| {
+                            self.is_help_flag(value) || self.is_version_flag(value)
+                        });
+                        match next {
+                            Ok(value) => (value, pulled.get()),
+                            Err(Some(value)) => {
+                                state.errors.push(ParseError::UnexpectedValue {
+                                    arg: inner.long_name.clone(),
+                                    option: arg,
+                                    value: inner.display_value(&value).to_owned(),
+                                });
+                                return true;
+                            }
+                            Err(None) => {
+                                state.errors.push(ParseError::MissingValue {
+                                    arg: inner.long_name.clone(),
+                                    option: arg,
+                                });
+                                return true;
+                            }
+                        }
+                    }
+                };
+                let mut values = vec![value];
+                let mut indices = vec![index];
+
+                // multi-value arguments keep consuming values until the next option.
+                if inner.multiple && inner.optional_value.is_none() {
+                    while let Some(value) = scan::next_item(inner, arguments) {
+                        values.push(value);
+                        indices.push(pulled.get());
+                    }
+                }
+                // the items of a value share its index.
+                let indices: Vec<usize> = values
+                    .iter()
+                    .zip(indices)
+                    .flat_map(|(value, index)| {
+                        let items = inner
+                            .value_delimiter
+                            .map_or(1, |delimiter| value.split(delimiter).count());
+                        std::iter::repeat_n(index, items)
+                    })
+                    .collect();
+                let values = match inner.split_items(values) {
+                    Ok(values) => values,
+                    Err(e) => {
+                        state.errors.push(e);
+                        return true;
+                    }
+                };
+                let invalid: Vec<ParseError> = values
+                    .iter()
+                    .zip(&indices)
+                    .filter_map(|(value, &index)| {
+                        let original = state.original(inner, index, value);
+                        inner.check_value_os(value, original.as_deref()).err()
+                    })
+                    .collect();
+                if !invalid.is_empty() {
+                    state.errors.extend(invalid);
+                    return true;
+                }
+                // the command line overrides options of `ArgParser::env_args`.
+                let repeated = !inner.multiple
+                    && state.occurrences.contains_key(&inner.long_name)
+                    && state.scanned.get(&inner.long_name) == Some(&source);
+                if repeated && inner.on_repeat == RepeatPolicy::Error {
+                    state.errors.push(ParseError::RepeatedArgument {
+                        arg: inner.long_name.clone(),
+                        option: arg,
+                    });
+                    return true;
+                }
+                state.scanned.insert(inner.long_name.clone(), source); // we got this value, don't expect
+                for (value, &index) in values.iter().zip(&indices) {
+                    self.notify(state, &inner.long_name, value, index);
+                }
+                let stored = state
+                    .argument_map
+                    .entry(inner.long_name.clone())
+                    .or_default();
+                if replayed || (!inner.multiple && inner.on_repeat != RepeatPolicy::Append) {
+                    stored.clear();
+                }
+                stored.extend(values);
+            }
+        }
+        // the arguments it overrides are dropped as if they were never given.
+        for other in self.args.iter().filter(|other| {
+            other.long_name != inner.long_name
+                && (inner.overrides_with.contains(&other.long_name)
+                    || other.overrides_with.contains(&inner.long_name))
+        }) {
+            state.argument_map.remove(&other.long_name);
+            state.scanned.remove(&other.long_name);
+            state.occurrences.remove(&other.long_name);
         }
+        *state
+            .occurrences
+            .entry(inner.long_name.clone())
+            .or_default() += 1;
+        true
     }
-    pub fn add_group(mut self, group: ArgGroup) -> Self {
-        self.groups.push(group);
-        self
+
+    /// Stores `arg`, the word numbered `at`, as a value of the positional argument `inner`.
+    fn match_positional(
+        &self,
+        state: &mut Scanning,
+        inner: &Arg,
+        arg: String,
+        at: usize,
+        source: ValueSource,
+    ) {
+        // values are only split when asked to, file lists can be huge.
+        let stored = if inner.value_delimiter.is_some() {
+            inner.checked_items(vec![arg]).map(|items| {
+                for item in &items {
+                    self.notify(state, &inner.long_name, item, at);
+                }
+                slot(&mut state.argument_map, &inner.long_name).extend(items)
+            })
+        } else {
+            let original = state.original(inner, at, &arg);
+            inner.check_value_os(&arg, original.as_deref()).map(|()| {
+                self.notify(state, &inner.long_name, &arg, at);
+                slot(&mut state.argument_map, &inner.long_name).push(arg)
+            })
+        };
+        if let Err(e) = stored {
+            state.errors.push(e);
+            return;
+        }
+        // the name is only copied for the first value.
+        if !state.scanned.contains_key(&inner.long_name) {
+            state.scanned.insert(inner.long_name.clone(), source);
+        }
+        *slot(&mut state.occurrences, &inner.long_name) += 1;
     }
 
-    /// Add a new argument requirement to the parser.
-    /// # Arguments
-    /// `arg` Argument requirements.
-    /// # Returns
-    ///  The argument parser itself. Useful for chaining.
-    pub fn arg(mut self, arg: Arg) -> Self {
-        // we don't allow overriding help
-        if arg.long_name != "help" && arg.short_name != Some('h') {
-            self.args.push(arg);
+    /// Records the value `value` of the argument `name` taken from the word numbered `at` among
+    /// all the words pulled, and passes it to the hook of [`ArgParser::on_arg`].
+    fn notify(&self, state: &mut Scanning, name: &str, value: &str, at: usize) {
+        if let Some(hook) = self.on_arg.as_ref().filter(|_| state.sources) {
+            hook(name, value);
+        }
+        if !state.originals.is_empty() {
+            state.given.push((name.to_owned(), value.to_owned(), at));
+        }
+        if let Some(index) = at.checked_sub(state.replayed + 1).filter(|_| state.sources) {
+            state.order.push((name.to_owned(), value.to_owned(), index));
         }
-        self
     }
 
-    /// Prints the program's usage.
-    pub fn usage(&self) {
-        let group_example = self
-            .groups
-            .iter()
-            .filter(|group| group.is_required())
-            .map(|g| match g.kind() {
-                GroupKind::Exclusive => format!("<{}>", g.args().join(" | ")),
-                GroupKind::OnlyWhen => String::new(),
-            })
-            .fold(String::new(), |mut acc, new| {
-                // match group being required but argument's that are group, should not be marked as such
-                acc.push_str(&new);
-                acc
-            });
+    /// Hands the values of deprecated aliases over to the arguments they stand for, then fills
+    /// the arguments left without a value from the environment, configuration files and prompts.
+    fn fill_sources(&self, state: &mut Scanning) {
+        // deprecated aliases hand their values over to the argument they stand for.
+        for alias in self.args.iter().filter(|arg| arg.alias_of.is_some()) {
+            let Some(values) = state.argument_map.remove(&alias.long_name) else {
+                continue;
+            };
+            let source = state
+                .scanned
+                .remove(&alias.long_name)
+                .unwrap_or(ValueSource::CommandLine);
+            let count = state.occurrences.remove(&alias.long_name).unwrap_or(1);
+            let Some(target) = self
+                .args
+                .iter()
+                .find(|arg| alias.alias_of.as_ref() == Some(&arg.long_name))
+                .filter(|target| !state.scanned.contains_key(&target.long_name))
+            else {
+                continue;
+            };
+            let values: Vec<String> = match &alias.rewrite {
+                Some(rewrite) => values.iter().map(|value| rewrite(value)).collect(),
+                None => values,
+            };
+            if let Some(e) = values
+                .iter()
+                .find_map(|value| target.check_value(value).err())
+            {
+                state.errors.push(e);
+                continue;
+            }
+            state.scanned.insert(target.long_name.clone(), source);
+            state.occurrences.insert(target.long_name.clone(), count);
+            state.argument_map.insert(target.long_name.clone(), values);
+        }
+        if state.sources {
+            self.read_env(
+                &mut state.argument_map,
+                &mut state.scanned,
+                &mut state.errors,
+            );
+            self.read_config(
+                state.profile.as_deref(),
+                &mut state.argument_map,
+                &mut state.scanned,
+                &mut state.errors,
+            );
+            // only bother the user when the rest of the arguments are valid.
+            if state.errors.is_empty() {
+                self.read_prompts(
+                    &mut state.argument_map,
+                    &mut state.scanned,
+                    &mut state.skipped,
+                    &mut state.errors,
+                );
+            }
+            if state.errors.is_empty() {
+                self.resolve_references(&mut state.argument_map, &mut state.errors);
+            }
+        }
+    }
+
+    /// Stores the choices as declared and clamps the values out of range, warns about secrets
+    /// given on the command line and checks the number of values of multi-value arguments.
+    fn check_values(&self, state: &mut Scanning) {
+        for arg in &self.args {
+            for value in state
+                .argument_map
+                .get_mut(&arg.long_name)
+                .into_iter()
+                .flatten()
+            {
+                // choices given in another case are stored as declared.
+                if let Some(choice) = arg.choice(value).filter(|choice| *choice != value) {
+                    *value = choice.clone();
+                }
+                if let Some(clamped) = arg.clamp(value) {
+                    state.warnings.push(format!(
+                        "Value `{}` for argument: --{}{} is out of range {}, using `{}` instead.",
+                        arg.display_value(value),
+                        arg.long_name,
+                        arg.alias_note(),
+                        arg.range.expect("only ranged values are clamped"),
+                        arg.display_value(&clamped)
+                    ));
+                    *value = clamped;
+                }
+            }
+        }
+        // values on the command line are visible to other users, e.g. with `ps`.
+        for arg in self.args.iter().filter(|arg| {
+            arg.secret && state.scanned.get(&arg.long_name) == Some(&ValueSource::CommandLine)
+        }) {
+            let alternative = match self.env_name(arg) {
+                Some(variable) => format!("the environment variable `{variable}`"),
+                None if arg.prompt.is_some() || self.prompt_missing => "the prompt".to_owned(),
+                None => "another source".to_owned(),
+            };
+            let warning = format!(
+                "Secret argument: --{} was given on the command line, where other users can see it. Prefer {alternative}.",
+                arg.long_name
+            );
+            if state.sources {
+                self.warn(&warning);
+            }
+            state.warnings.push(warning);
+        }
+        for arg in self.args.iter().filter(|arg| arg.multiple) {
+            let Some(values) = state.argument_map.get_mut(&arg.long_name) else {
+                // a positional argument given no words has no values, unless a default stands in.
+                let needed = arg.min_values.filter(|min| {
+                    *min > 0 && arg.positional && !arg.required && arg.default_value.is_none()
+                });
+                if let Some(min) = needed {
+                    state.errors.push(ParseError::TooFewValues {
+                        arg: arg.long_name.clone(),
+                        min,
+                        count: 0,
+                    });
+                }
+                continue;
+            };
+            if let Some(max) = arg.max_values.filter(|max| values.len() > *max) {
+                state.errors.push(ParseError::TooManyValues {
+                    arg: arg.long_name.clone(),
+                    max,
+                    count: values.len(),
+                });
+                continue;
+            }
+            if let Some(min) = arg.min_values.filter(|min| values.len() < *min) {
+                state.errors.push(ParseError::TooFewValues {
+                    arg: arg.long_name.clone(),
+                    min,
+                    count: values.len(),
+                });
+                continue;
+            }
+            if let Some(max) = self.max_values.filter(|max| values.len() > *max) {
+                state.errors.push(ParseError::LimitExceeded {
+                    arg: Some(arg.long_name.clone()),
+                    limit: max,
+                    code: "E_TOO_MANY_VALUES",
+                    message: format!(
+                        "Too many values for argument: --{}, at most {max} are accepted.",
+                        arg.long_name
+                    ),
+                });
+                continue;
+            }
+            if let Err(e) = arg.check_values(values) {
+                state.errors.push(e);
+            }
+        }
+    }
+
+    /// Values of the preset `name` selected with `--preset`, by argument.
+    fn selected_preset(&self, name: Option<&str>) -> HashMap<&str, &str> {
+        name.and_then(|name| self.preset_values.get(name))
+            .into_iter()
+            .flatten()
+            .map(|(arg, value)| (arg.as_str(), value.as_str()))
+            .collect()
+    }
 
-        let example = self
+    /// Fills the arguments left without a value from the selected preset, their default or
+    /// conditional default, then resolves the dates.
+    fn fill_defaults(&self, state: &mut Scanning) {
+        // values of the preset selected with `--preset` take the place of the defaults.
+        let preset_values = self.selected_preset(state.preset.as_deref());
+        for arg in self
+            .args
+            .iter()
+            .filter(|arg| !state.scanned.contains_key(&arg.long_name))
+        {
+            if let Some(Err(e)) = preset_values
+                .get(arg.long_name.as_str())
+                .map(|value| arg.check_value(value))
+            {
+                state.errors.push(e);
+            }
+        }
+        // defaults fill in whatever was not given on the command line or configuration.
+        for arg in self
             .args
             .iter()
-            .filter(|arg| arg.required)
-            .map(|arg| format!("--{} <{}> ", arg.long_name, arg.long_name.to_uppercase()))
-            .fold(String::new(), |mut old: String, new| {
-                old.push(' ');
-                old.push_str(&new);
-                old
+            .filter(|arg| !state.skipped.contains(&arg.long_name))
+        {
+            let preset_value = preset_values.get(arg.long_name.as_str()).copied();
+            if let Some(default) = preset_value.or(arg.default_value.as_deref()) {
+                state
+                    .argument_map
+                    .entry(arg.long_name.clone())
+                    .or_insert_with(|| {
+                        match preset_value {
+                            Some(_) => state.preset_filled.push(arg.long_name.clone()),
+                            None => state.filled.push(arg.long_name.clone()),
+                        }
+                        vec![default.to_owned()]
+                    });
+            } else if let Some(compute) = &arg.default_with {
+                if !state.argument_map.contains_key(&arg.long_name) {
+                    let value = compute();
+                    match arg.check_value(&value) {
+                        Ok(()) => {
+                            state.computed.push(arg.long_name.clone());
+                            state
+                                .argument_map
+                                .insert(arg.long_name.clone(), vec![value]);
+                        }
+                        Err(e) => state.errors.push(e),
+                    }
+                }
+            }
+        }
+        // conditional defaults depend on the values resolved above, defaults included.
+        for arg in self.args.iter().filter(|arg| {
+            !arg.default_if.is_empty()
+                && !state.skipped.contains(&arg.long_name)
+                && !state.scanned.contains_key(&arg.long_name)
+                && !preset_values.contains_key(arg.long_name.as_str())
+        }) {
+            let matched = arg.default_if.iter().find(|(other, value, _)| {
+                state
+                    .argument_map
+                    .get(other)
+                    .and_then(|values| values.last())
+                    == Some(value)
             });
-        let options = if self.args.iter().any(|arg| !arg.required) {
-            " [options] "
-        } else {
-            " "
-        };
-        println!(
-            "Usage: {}{}{}{}",
-            self.executable, options, group_example, example
-        );
+            if let Some((_, _, default)) = matched {
+                state
+                    .argument_map
+                    .insert(arg.long_name.clone(), vec![default.clone()]);
+                state.computed.retain(|name| *name != arg.long_name);
+                if !state.filled.contains(&arg.long_name) {
+                    state.filled.push(arg.long_name.clone());
+                }
+            }
+        }
+        // dates are stored resolved, so relative ones don't drift while the program runs.
+        let now = self
+            .clock
+            .as_ref()
+            .map_or_else(SystemTime::now, |clock| clock());
+        for arg in self.args.iter().filter(|arg| arg.kind == ArgKind::DateTime) {
+            for value in state
+                .argument_map
+                .get_mut(&arg.long_name)
+                .into_iter()
+                .flatten()
+            {
+                if let Ok(moment) = datetime::parse(value) {
+                    let timestamp = datetime::resolve(moment, now, self.utc_offset);
+                    *value = datetime::format(timestamp, self.utc_offset);
+                }
+            }
+        }
     }
 
-    /// Prints the help page for this executable
-    /// The help page consists of:
-    /// * Example usage.
-    /// * Options description
-    pub fn help(&self) {
-        println!("{}", self.description);
-        self.usage();
-        println!("\noptions:");
-        println!("-------");
-
-        // calculate the maximum width of the argument name.
-        let max_length = self.args.iter().fold(0, |max, arg| match arg.kind {
-            // boolean arguments don't have to repeat their name, only count once
-            ArgKind::Boolean => max.max(arg.long_name.len() + ARG_PADDING),
-
-            // any other argument has 2 times the length + some padding when printed, account for it.
-            // we assume the maximum usage like "--Argument <ARGUMENT>" (arg.len * 2 + at least 5 args) and add some padding
-            _ => max.max(arg.long_name.len() * 2 + ARG_PADDING),
-        });
+    /// Checks the groups and the relations between arguments: requirements and conflicts.
+    fn check_relations(&self, state: &mut Scanning) {
+        let preset_values = self.selected_preset(state.preset.as_deref());
+        // a boolean turned off, e.g. with `--no-upload`, is not in use for groups and
+        // the relations between arguments.
+        let in_use = |name: &str| {
+            state.scanned.contains_key(name)
+                && !self.args.iter().any(|arg| {
+                    arg.long_name == name
+                        && arg.kind == ArgKind::Boolean
+                        && state
+                            .argument_map
+                            .get(name)
+                            .and_then(|values| values.last())
+                            .is_some_and(|value| value == "false")
+                })
+        };
+        // a nested group is in use when any of its arguments is.
+        let member_in_use = |name: &str| self.member_args(name).into_iter().any(in_use);
+        for group in &self.groups {
+            // an argument repeated, like `-vvv`, is used once, its occurrences are only reported.
+            let used: Vec<(String, usize)> = group
+                .args()
+                .iter()
+                .flat_map(|name| self.member_args(name))
+                .filter(|name| in_use(name))
+                .map(|name| {
+                    let count = state.occurrences.get(name).copied().unwrap_or(1);
+                    (name.to_owned(), count)
+                })
+                .collect();
+            let use_count = group
+                .args()
+                .iter()
+                .filter(|name| member_in_use(name))
+                .count();
+            let parents_in_use = group
+                .parents()
+                .iter()
+                .filter(|name| member_in_use(name))
+                .count();
+            let message = match (group.kind(), group.is_required()) {
+                (GroupKind::Exclusive, true) if use_count > 1 => format!("Misuse of exclusive argument(s). Only one of the following must be used: [{}], used: [{}]", group.args().join(", "), counted(&used)),
+                (GroupKind::Exclusive, true) if use_count == 0 => format!("Missing required exclusive argument(s). One of the following must be used: [{}]", group.args().join(", ")),
+                (GroupKind::OnlyWhen, true) if use_count == 0 => format!("Missing matching argument(s). One of the following must be used: [{}]", group.args().join(", ")),
+                (GroupKind::OnlyWhen, true) if parents_in_use == 0 => format!("Missing matching parent argument. Options like [{}] need to be used with: [{}].", group.args().join(", "), group.parents().join(", ")),
+                (GroupKind::Exclusive, false) if use_count > 1 => format!(
+                    "Cannot use the following arguments together: [{}], used: [{}]",
+                    group.args().join(", "),
+                    counted(&used)
+                ),
+                (GroupKind::AnyOf, _) if use_count == 0 => format!("Missing required argument(s). At least one of the following must be used: [{}]", group.args().join(", ")),
+                (GroupKind::AllOrNone, true) if use_count == 0 => format!("Missing required arguments. All of the following must be used: [{}]", group.args().join(", ")),
+                (GroupKind::AllOrNone, _) if use_count > 0 && use_count < group.args().len() => {
+                    let missing: Vec<&str> = group
+                        .args()
+                        .iter()
+                        .filter(|name| !member_in_use(name))
+                        .map(String::as_str)
+                        .collect();
+                    format!(
+                        "Incomplete arguments. The following must be used together: [{}], missing: [{}]",
+                        group.args().join(", "),
+                        missing.join(", ")
+                    )
+                }
+                (GroupKind::OnlyWhen, false) if use_count > 0 && parents_in_use == 0 => format!("Missing arguments. Options like [{}] need to be used with: [{}].", group.args().join(", "), group.parents().join(", ")),
+                _ => continue,
+            };
+            state.errors.push(ParseError::GroupViolation {
+                group: group.name().clone(),
+                used,
+                message,
+            });
+        }
+        for arg in self
+            .args
+            .iter()
+            .filter(|arg| !state.skipped.contains(&arg.long_name))
+        {
+            let has_default = arg.default_value.is_some()
+                || arg.default_with.is_some()
+                || state.filled.contains(&arg.long_name)
+                || preset_values.contains_key(arg.long_name.as_str());
+            if arg.required && !state.scanned.contains_key(&arg.long_name) && !has_default {
+                state.errors.push(ParseError::MissingRequired {
+                    arg: arg.long_name.clone(),
+                });
+            } else if !arg.required_unless.is_empty()
+                && !state.scanned.contains_key(&arg.long_name)
+                && !has_default
+                && !arg
+                    .required_unless
+                    .iter()
+                    .any(|other| state.scanned.contains_key(other))
+            {
+                state.errors.push(ParseError::RequiredUnless {
+                    arg: arg.long_name.clone(),
+                    others: arg.required_unless.clone(),
+                });
+            }
+        }
+        for arg in self.args.iter().filter(|arg| in_use(&arg.long_name)) {
+            for other in arg.requires.iter().filter(|other| !in_use(other)) {
+                state.errors.push(ParseError::Requires {
+                    arg: arg.long_name.clone(),
+                    other: other.clone(),
+                });
+            }
+        }
+        // each conflicting pair is reported once, even when both arguments declare it.
+        let mut conflicts: HashSet<(&str, &str)> = HashSet::new();
+        for arg in self.args.iter().filter(|arg| in_use(&arg.long_name)) {
+            for other in arg.conflicts_with.iter().filter(|other| in_use(other)) {
+                let pair = if arg.long_name <= *other {
+                    (arg.long_name.as_str(), other.as_str())
+                } else {
+                    (other.as_str(), arg.long_name.as_str())
+                };
+                if conflicts.insert(pair) {
+                    state.errors.push(ParseError::Conflict {
+                        arg: arg.long_name.clone(),
+                        other: other.clone(),
+                    });
+                }
+            }
+        }
+    }
 
-        // Print each argument and it's description for the help message.
+    /// Builds the map of the values found, checked by the validators of
+    /// [`ArgParser::validator`] unless they were asked for with the debug flag or help was shown.
+    /// # Errors
+    /// The problems reported by the validators.
+    fn build_map(&self, mut state: Scanning) -> Result<Scan, Vec<ParseError>> {
+        let filled = state.filled.drain(..);
+        state
+            .scanned
+            .extend(filled.map(|name| (name, ValueSource::Default)));
+        let preset_filled = state.preset_filled.drain(..);
+        state
+            .scanned
+            .extend(preset_filled.map(|name| (name, ValueSource::Preset)));
+        let computed = state.computed.drain(..);
+        state
+            .scanned
+            .extend(computed.map(|name| (name, ValueSource::Computed)));
+        let values = self
+            .args
+            .iter()
+            .filter_map(|arg| {
+                let raw = state.argument_map.get(&arg.long_name)?;
+                let value = Value::parse_all(arg.kind, arg.multiple, raw)?;
+                Some((arg.long_name.clone(), value))
+            })
+            .collect();
+        // the last values of an argument are the last ones given, unless a later step replaced them.
+        let mut os_values = HashMap::new();
         for arg in &self.args {
-            let sample_usage = match arg.kind {
-                ArgKind::Boolean => arg.long_name.clone(),
-                _ => format!("{} <{}>", arg.long_name, arg.long_name.to_uppercase()),
+            let Some(values) = state.argument_map.get(&arg.long_name) else {
+                continue;
             };
+            let mut taken = state
+                .given
+                .iter()
+                .rev()
+                .filter(|(name, ..)| *name == arg.long_name);
+            let found: Vec<Option<OsString>> = values
+                .iter()
+                .rev()
+                .map_while(|value| {
+                    let (_, text, at) = taken.next().filter(|(_, text, _)| text == value)?;
+                    Some(state.original(arg, *at, text))
+                })
+                .collect();
+            if found.iter().any(Option::is_some) {
+                let mut originals = vec![None; values.len() - found.len()];
+                originals.extend(found.into_iter().rev());
+                os_values.insert(arg.long_name.clone(), originals);
+            }
+        }
+        let map = Box::new(ArgMap {
+            names: self
+                .args
+                .iter()
+                .map(|arg| Arc::from(arg.long_name.as_str()))
+                .collect(),
+            strict: self.strict_lookups,
+            values,
+            occurrences: state.occurrences,
+            sensitive: self
+                .args
+                .iter()
+                .filter(|arg| arg.sensitive)
+                .map(|arg| arg.long_name.clone())
+                .collect(),
+            trailing: state.trailing,
+            unknown: state.unknown,
+            declared: self
+                .args
+                .iter()
+                .map(|arg| Declared {
+                    name: arg.long_name.clone(),
+                    kind: arg.kind,
+                    multiple: arg.multiple,
+                    negatable: arg.negatable,
+                    positional: arg.positional,
+                })
+                .collect(),
+            stdio: self
+                .args
+                .iter()
+                .filter(|arg| {
+                    arg.kind == ArgKind::Path
+                        && state
+                            .argument_map
+                            .get(&arg.long_name)
+                            .and_then(|values| values.last())
+                            .is_some_and(|value| self.stdio.is_stdio(value))
+                })
+                .map(|arg| arg.long_name.clone())
+                .collect(),
+            inner: state.argument_map,
+            sources: state.scanned,
+            os_values,
+            warnings: state.warnings,
+            order: state.order,
+        });
+        if state.debug {
+            return Ok(Scan::Debug(map));
+        }
+        if state.help_shown {
+            return Ok(Scan::HelpShown(map));
+        }
+        let failed: Vec<ParseError> = self
+            .validators
+            .iter()
+            .filter_map(|validator| validator(&map).err())
+            .map(|message| ParseError::InvalidArguments { message })
+            .collect();
+        if !failed.is_empty() {
+            return Err(failed);
+        }
+        Ok(Scan::Parsed(map))
+    }
 
-            // format the shortname, if available
-            let short_name = match arg.short_name {
-                Some(c) => format!("-{c},"),
-                None => format!("   "),
+    /// Option abbreviated by `arg` following [`ArgParser::allow_abbrev`], e.g. `--verbose` for
+    /// `--verb`, keeping a value given with `=`.
+    /// # Returns
+    /// `None` when `arg` is not the abbreviation of a long option.
+    /// # Errors
+    /// A [`ParseError::AmbiguousOption`] when it abbreviates options of several arguments.
+    fn expand_abbreviation(&self, arg: &str) -> Result<Option<String>, ParseError> {
+        let Some(given) = arg.strip_prefix("--").filter(|_| self.allow_abbrev) else {
+            return Ok(None);
+        };
+        let (name, value) = match given.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (given, None),
+        };
+        if name.is_empty() {
+            return Ok(None);
+        }
+        let same = |a: &str, b: &str| match self.ignore_case {
+            true => a.eq_ignore_ascii_case(b),
+            false => a == b,
+        };
+        // options of the arguments and the built-in ones, along with the option they stand for.
+        let mut options: Vec<(String, String)> = Vec::new();
+        for arg in self.args.iter().filter(|arg| !arg.positional) {
+            // hidden arguments and aliases only take part as exact matches.
+            let abbreviable = |full: &str| match arg.hidden {
+                true => String::new(),
+                false => full.to_owned(),
             };
+            for own in std::iter::once(&arg.long_name).chain(&arg.aliases) {
+                options.push((own.clone(), abbreviable(&arg.long_name)));
+            }
+            for alias in &arg.hidden_aliases {
+                options.push((alias.clone(), String::new()));
+            }
+            if arg.negatable && arg.kind == ArgKind::Boolean {
+                let negated = format!("no-{}", arg.long_name);
+                options.push((negated.clone(), abbreviable(&negated)));
+            }
+        }
+        let built_in = [
+            ("help", self.help_enabled),
+            ("version", self.version_long()),
+            ("profile", self.config_file.is_some()),
+            ("preset", !self.presets.is_empty()),
+        ];
+        for (option, _) in built_in.iter().filter(|(_, enabled)| *enabled) {
+            options.push(((*option).to_owned(), (*option).to_owned()));
+        }
+        if options.iter().any(|(option, _)| same(option, name)) {
+            return Ok(None);
+        }
+        // arguments, not options, are told apart: `--verb` abbreviating `--verbose` and its
+        // alias `--verbosity` is not ambiguous.
+        let mut candidates: Vec<&str> = Vec::new();
+        for (option, full) in &options {
+            let abbreviates = option
+                .get(..name.len())
+                .is_some_and(|start| same(start, name));
+            if abbreviates && !full.is_empty() && !candidates.contains(&full.as_str()) {
+                candidates.push(full);
+            }
+        }
+        match candidates[..] {
+            [] => Ok(None),
+            [full] => Ok(Some(match value {
+                Some(value) => format!("--{full}={value}"),
+                None => format!("--{full}"),
+            })),
+            _ => Err(ParseError::AmbiguousOption {
+                option: format!("--{name}"),
+                candidates: candidates.iter().map(|full| format!("--{full}")).collect(),
+            }),
+        }
+    }
 
-            println!(
-                "{} --{:<width$} {}",
-                short_name,
-                sample_usage,
-                arg.description,
-                width = max_length
-            );
+    /// Checks whether `option`, like `--token` or `-t`, names an argument reading its value
+    /// from `@path`, see [`Arg::allow_file_ref`].
+    fn file_ref_option(&self, option: &str) -> bool {
+        let long = option.strip_prefix("--");
+        let short = option
+            .strip_prefix('-')
+            .filter(|name| name.chars().count() == 1)
+            .and_then(|name| name.chars().next());
+        self.args.iter().any(|arg| {
+            arg.allow_file_ref
+                && !arg.positional
+                && (long.is_some_and(|name| arg.answers_to(name))
+                    || short.is_some_and(|c| arg.answers_to_short(c)))
+        })
+    }
+
+    /// Translates an option given with a slash, like `/verbose`, `/v` or `/?`, following
+    /// [`ArgParser::allow_slash_prefix`].
+    /// # Returns
+    /// The option spelled with dashes, or `None` if `arg` does not name an option after the
+    /// slash, like a path.
+    fn slash_option(&self, arg: &str) -> Option<String> {
+        let name = arg.strip_prefix('/').filter(|_| self.slash_prefix)?;
+        let mut chars = name.chars();
+        let short = match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        };
+        if (matches!(name, "?" | "help") && self.help_enabled)
+            || (name == "version" && self.version_long())
+        {
+            return Some(format!("--{}", name.replace('?', "help")));
         }
-        println!(
-            "-h, --{:<width$} Print this help message",
-            "help",
-            width = max_length
-        );
-        if !self.groups.is_empty() {
-            println!("\nNotes on argument groups:");
-            for group in &self.groups {
-                let arguments: Vec<String> = self
-                    .args
-                    .iter()
-                    .filter(|arg| group.contains(&arg.long_name))
-                    .map(|arg| match arg.kind {
-                        ArgKind::Boolean => format!("--{}", arg.long_name.clone()),
-                        _ => format!("--{} <{}>", arg.long_name, arg.long_name.to_uppercase()),
-                    })
-                    .collect();
-                let parent_arguments: Vec<String> = self
+        if short.is_some()
+            && (short == self.version_short()
+                || self
                     .args
                     .iter()
-                    .filter(|arg| group.parents().contains(&arg.long_name))
-                    .map(|arg| match arg.kind {
-                        ArgKind::Boolean => format!("--{}", arg.long_name.clone()),
-                        _ => format!("--{} <{}>", arg.long_name, arg.long_name.to_uppercase()),
+                    .any(|arg| !arg.positional && short.is_some_and(|c| arg.answers_to_short(c))))
+        {
+            return Some(format!("-{name}"));
+        }
+        let positive = name.strip_prefix("no-");
+        self.args
+            .iter()
+            .filter(|arg| !arg.positional)
+            .any(|arg| {
+                arg.answers_to(name)
+                    || positive.is_some_and(|positive| {
+                        arg.negatable && arg.kind == ArgKind::Boolean && arg.answers_to(positive)
                     })
-                    .collect();
-                match group.kind() {
-                    GroupKind::Exclusive => println!("The following option(s) are mutually exclusive and cannot be used together:\n\t{}", arguments.join("\n\t")),
-                    GroupKind::OnlyWhen => println!("The option(s): \n\t{}\nCan only be used in conjunction with: \n\t{}", arguments.join("\n\t"), parent_arguments.join("\n\t")) 
+            })
+            .then(|| format!("--{name}"))
+    }
+
+    /// Long name of the option closest to `name`, if it looks like a typo of one.
+    fn suggest_option(&self, name: &str) -> Option<String> {
+        let negated: Vec<String> = self
+            .args
+            .iter()
+            .filter(|arg| arg.negatable && arg.kind == ArgKind::Boolean && !arg.hidden)
+            .map(|arg| format!("no-{}", arg.long_name))
+            .collect();
+        let mut candidates: Vec<&str> = self
+            .args
+            .iter()
+            .filter(|arg| !arg.positional && !arg.hidden)
+            .flat_map(|arg| std::iter::once(&arg.long_name).chain(&arg.aliases))
+            .map(String::as_str)
+            .collect();
+        candidates.extend(negated.iter().map(String::as_str));
+        if self.config_file.is_some() {
+            candidates.push("profile");
+        }
+        if !self.presets.is_empty() {
+            candidates.push("preset");
+        }
+        if self.version_long() {
+            candidates.push("version");
+        }
+        if self.help_enabled {
+            candidates.push("help");
+        }
+        suggest::closest(name, candidates).map(str::to_owned)
+    }
+
+    /// Fills the arguments not given on the command line from the environment.
+    fn read_env(
+        &self,
+        argument_map: &mut HashMap<String, Vec<String>>,
+        scanned: &mut HashMap<String, ValueSource>,
+        errors: &mut Vec<ParseError>,
+    ) {
+        let Some(prefix) = &self.env_prefix else {
+            return;
+        };
+        for inner in &self.args {
+            let Some(name) = self.env_name(inner) else {
+                continue;
+            };
+            // values from the command line take precedence.
+            if scanned.contains_key(&inner.long_name) {
+                continue;
+            }
+            let Some(value) = self
+                .host
+                .var(&name)
+                .and_then(|value| value.into_string().ok())
+            else {
+                continue;
+            };
+            match inner.checked_items(vec![value]) {
+                Ok(values) => {
+                    scanned.insert(inner.long_name.clone(), ValueSource::Environment);
+                    argument_map.insert(inner.long_name.clone(), values);
+                }
+                Err(e) => errors.push(ParseError::Environment {
+                    variable: name,
+                    error: Box::new(e),
+                }),
+            }
+        }
+        if self.unknown_env != UnknownEnvPolicy::Ignore {
+            let prefix = format!("{prefix}_");
+            let mut unknown: Vec<String> = self
+                .host
+                .vars()
+                .into_iter()
+                .filter_map(|(key, _)| key.into_string().ok())
+                .filter(|key| key.starts_with(&prefix))
+                .filter(|key| {
+                    !self
+                        .args
+                        .iter()
+                        .any(|arg| self.env_name(arg).as_ref() == Some(key))
+                })
+                .collect();
+            unknown.sort();
+            for key in unknown {
+                let error = ParseError::UnknownEnv { variable: key };
+                match self.unknown_env {
+                    UnknownEnvPolicy::Warn => {
+                        self.warn(&error.to_string());
+                    }
+                    _ => errors.push(error),
                 }
             }
         }
     }
 
-    /// Parse user command line arguments into a Map struct.
-    /// This parsing follows the argument requirements selected and consumes the parser.
-    /// # Returns
-    /// A map with all the parsed arguments.
-    ///
-    /// # Panics
-    /// It errors and stops execution when the argument requirements cannot be enforced.
-    /// Not being able to parse the arguments is considered a fatal error and the program
-    /// execution halts with a call to exit(0).
-    pub fn parse(mut self) -> ArgMap {
-        let mut argument_map: HashMap<String, String> = HashMap::new();
-
-        // skip executable name
-        let mut arguments = std::env::args().skip(1);
-        while let Some(arg) = arguments.next() {
-            if arg == "--help" || arg == "-h" {
-                self.help();
-                exit(0);
-            }
-
-            if arg.starts_with("-") {
-                let arg_name: String = arg.chars().skip_while(|c| *c == '-').collect();
-                let actual_argument = self.args.iter_mut().find(|arg| {
-                    arg.long_name == arg_name
-                        || (arg_name.len() == 1 && arg_name.chars().nth(0) == arg.short_name)
-                });
-                if let Some(inner) = actual_argument {
-                    // validate the type of argument we got
-                    match inner.kind {
-                        ArgKind::String => match arguments.next() {
-                            // got a string, don't worry about any conversion
-                            Some(value) => {
-                                if value.starts_with('-') {
-                                    eprintln!(
-                                        "Unexpected value `{value}` for argument: --{}",
-                                        arg_name
-                                    );
-                                    self.usage();
-                                    exit(1)
-                                }
-                                inner.scanned = true; // we got this value, don't expect
-                                argument_map.insert(inner.long_name.clone(), value);
-                            }
-                            _ => {
-                                eprintln!("Missing value for argument: --{}", arg_name);
-                                self.usage();
-                                exit(1)
-                            }
-                        },
-
-                        // we got an integer, ensure we can at least parse it properly
-                        ArgKind::Integer => match arguments.next() {
-                            Some(value) => {
-                                if value.parse::<i32>().is_ok() {
-                                    inner.scanned = true; // we got this value, don't expect
-                                    argument_map.insert(inner.long_name.clone(), value);
-                                } else {
-                                    eprintln!("Cannot convert `{}` into integer.", value);
-                                    self.usage();
-                                    exit(1)
-                                }
-                            }
-                            _ => {
-                                eprintln!("Missing value for argument: --{}", arg_name);
-                                self.usage();
-                                exit(1)
-                            }
-                        },
-
-                        // we got a floating point number, ensure we can at least parse it properly
-                        ArgKind::Float => match arguments.next() {
-                            Some(value) => {
-                                if value.parse::<f32>().is_ok() {
-                                    inner.scanned = true; // we got this value, don't expect
-                                    argument_map.insert(inner.long_name.clone(), value);
-                                } else {
-                                    eprintln!(
-                                        "Cannot convert `{}` into floating point number.",
-                                        value
-                                    );
-                                    self.usage();
-                                    exit(1)
-                                }
-                            }
-                            _ => {
-                                eprintln!("Missing value for argument: --{}", arg_name);
-                                self.usage();
-                                exit(1)
-                            }
-                        },
-
-                        // this is a boolean flag, having listed, means we set it.
-                        ArgKind::Boolean => {
-                            inner.scanned = true; // we got this value, don't expect
-                            argument_map.insert(inner.long_name.clone(), "true".to_owned());
+    /// Asks the user for the arguments with a prompt that no other source provided,
+    /// and the missing required ones with [`ArgParser::prompt_missing`].
+    /// Invalid answers are reported and asked again.
+    fn read_prompts(
+        &self,
+        argument_map: &mut HashMap<String, Vec<String>>,
+        scanned: &mut HashMap<String, ValueSource>,
+        skipped: &mut HashSet<String>,
+        errors: &mut Vec<ParseError>,
+    ) {
+        let mut prompter: Option<prompt::Prompter> = None;
+        for inner in &self.args {
+            if scanned.contains_key(&inner.long_name) {
+                continue;
+            }
+            let message = match &inner.prompt {
+                Some(message) => message.clone(),
+                None if self.prompt_missing && inner.required && inner.default_value.is_none() => {
+                    let name = if inner.positional {
+                        inner.long_name.clone()
+                    } else {
+                        format!("--{}", inner.long_name)
+                    };
+                    self.message("Enter value for {name}")
+                        .replace("{name}", &name)
+                }
+                None => continue,
+            };
+            let prompter = prompter.get_or_insert_with(|| {
+                prompt::Prompter::new(Arc::clone(&self.host), self.prompt_timeout)
+            });
+            let question = match &inner.default_value {
+                Some(value) => format!("{message} [{}]", inner.display_value(value)),
+                None => message,
+            };
+            loop {
+                let show = |text: &str| {
+                    let _ = self.write(Stream::Stderr, text);
+                };
+                let Some(answer) = prompter.ask(&question, inner.secret, &show) else {
+                    // a missing argument is reported as such by the usual checks.
+                    if inner.prompt.is_none() {
+                        break;
+                    }
+                    match self.prompt_fallback {
+                        PromptFallback::Error => errors.push(ParseError::PromptUnanswered {
+                            arg: inner.long_name.clone(),
+                        }),
+                        PromptFallback::Default => {}
+                        PromptFallback::Skip => {
+                            skipped.insert(inner.long_name.clone());
                         }
                     }
-                } else {
-                    // Got an unexpected argument, error now.
-                    eprintln!("Unrecognized option `{arg}` passed.");
-                    self.usage();
-                    exit(1);
+                    break;
+                };
+                if answer.is_empty() {
+                    break;
+                }
+                match inner.check_value(&answer) {
+                    Ok(()) => {
+                        scanned.insert(inner.long_name.clone(), ValueSource::Prompt);
+                        argument_map.insert(inner.long_name.clone(), vec![answer]);
+                        break;
+                    }
+                    Err(e) => {
+                        let mut message = Styled::new();
+                        message.error(self.error_line(&e)).plain("\n");
+                        let _ = self.emit(&message, Stream::Stderr);
+                    }
                 }
-            } else {
-                // Got an unexpected argument, error now.
-                eprintln!("Unexpected argument option `{arg}` passed.");
-                self.usage();
-                exit(1);
             }
         }
-        if !self.groups.is_empty() {
-            for group in &self.groups {
-                if group.is_required() {
-                    match group.kind() {
-                        GroupKind::Exclusive => {
-                            let use_count = self.args.iter().fold(0, |sum, item| {
-                                if group.args().contains(&item.long_name) && item.scanned {
-                                    sum + 1
-                                } else {
-                                    sum
-                                }
-                            });
-                            if use_count > 1 {
-                                eprintln!("Misuse of exclusive argument(s). Only one of the following must be used: [{}]", group.args().join(", "));
-                                self.usage();
-                                exit(1)
-                            } else if use_count == 0 {
-                                eprintln!("Missing required exclusive argument(s). One of the following must be used: [{}]", group.args().join(", "));
-                                self.usage();
-                                exit(1)
-                            }
-                        }
-                        GroupKind::OnlyWhen => {
-                            let use_count = self.args.iter().fold(0, |sum, item| {
-                                if group.args().contains(&item.long_name) && item.scanned {
-                                    sum + 1
-                                } else {
-                                    sum
-                                }
-                            });
-                            let parent_count = self.args.iter().fold(0, |sum, item| {
-                                if group.parents().contains(&item.long_name) && item.scanned {
-                                    sum + 1
-                                } else {
-                                    sum
-                                }
-                            });
-                            if use_count == 0 {
-                                eprintln!("Missing matching argument(s). One of the following must be used: [{}]", group.args().join(", "));
-                                self.usage();
-                                exit(1)
-                            } else if parent_count == 0 {
-                                eprintln!("Missing matching parent argument. Options like [{}] need to be used with: [{}].",group.args().join(", "), group.parents().join(", "));
-                                self.usage();
-                                exit(1)
-                            }
-                        }
+    }
+
+    /// Replaces the values referring to stdin or a file, following [`Arg::allow_stdin`] and
+    /// [`Arg::allow_file_ref`], by what they refer to.
+    fn resolve_references(
+        &self,
+        argument_map: &mut HashMap<String, Vec<String>>,
+        errors: &mut Vec<ParseError>,
+    ) {
+        // argument that read stdin, which can only be read once.
+        let mut stdin_reader: Option<&str> = None;
+        let referring = self
+            .args
+            .iter()
+            .filter(|arg| arg.allow_stdin || arg.allow_file_ref);
+        for arg in referring {
+            for value in argument_map.get_mut(&arg.long_name).into_iter().flatten() {
+                let (content, source) = match arg.reference(value) {
+                    None => continue,
+                    Some(Reference::Stdin) => match stdin_reader.replace(&arg.long_name) {
+                        None => (self.host.read_stdin(), "stdin".to_owned()),
+                        Some(reader) => (
+                            Err(io::Error::other(format!("already read for --{reader}"))),
+                            "stdin".to_owned(),
+                        ),
+                    },
+                    Some(Reference::File(path)) => {
+                        (std::fs::read_to_string(path), format!("file `{path}`"))
                     }
-                } else {
-                    match group.kind() {
-                        GroupKind::Exclusive => {
-                            let use_count = self.args.iter().fold(0, |sum, item| {
-                                if group.contains(&item.long_name) && item.scanned {
-                                    sum + 1
-                                } else {
-                                    sum
-                                }
-                            });
-                            if use_count > 1 {
-                                eprintln!(
-                                    "Cannot use the following arguments together: [{}]",
-                                    group.args().join(", ")
-                                );
-                                self.usage();
-                                exit(1)
-                            }
-                        }
-                        GroupKind::OnlyWhen => {
-                            let use_count = self.args.iter().fold(0, |sum, item| {
-                                if group.contains(&item.long_name) && item.scanned {
-                                    sum + 1
-                                } else {
-                                    sum
-                                }
-                            });
-                            let parents_in_use = self.args.iter().fold(0, |sum, item| {
-                                if group.parents().contains(&item.long_name) && item.scanned {
-                                    sum + 1
-                                } else {
-                                    sum
-                                }
-                            });
-                            if use_count > 0 && parents_in_use == 0 {
-                                eprintln!("Missing arguments. Options like [{}] need to be used with: [{}].",group.args().join(", "), group.parents().join(", "));
-                                self.usage();
-                                exit(1)
+                };
+                let content = match content {
+                    Ok(mut content) => {
+                        if content.ends_with('\n') {
+                            content.pop();
+                            if content.ends_with('\r') {
+                                content.pop();
                             }
                         }
+                        content
+                    }
+                    Err(e) => {
+                        errors.push(ParseError::UnreadableValue {
+                            arg: arg.long_name.clone(),
+                            value: value.clone(),
+                            message: format!(
+                                "Cannot read the value of argument: --{}{} from {source}: {e}",
+                                arg.long_name,
+                                arg.alias_note()
+                            ),
+                        });
+                        continue;
                     }
+                };
+                match arg.check_content(&content, None) {
+                    Ok(()) => *value = content,
+                    Err(e) => errors.push(e),
                 }
             }
         }
-        self.args.iter().for_each(|arg| {
-            if arg.required && !arg.scanned {
-                eprintln!("Missing required argument: `{}`", arg.long_name);
-                self.usage();
-                exit(1);
+    }
+
+    /// Fills the arguments not given on the command line or environment from the configuration
+    /// file, then from the sources added with [`ArgParser::config_source`].
+    fn read_config(
+        &self,
+        profile: Option<&str>,
+        argument_map: &mut HashMap<String, Vec<String>>,
+        scanned: &mut HashMap<String, ValueSource>,
+        errors: &mut Vec<ParseError>,
+    ) {
+        let path = self
+            .config_file
+            .as_deref()
+            .map(|path| config::expand_home(path, &*self.host));
+        let file = match &path {
+            // a missing configuration file simply provides no values, unless a profile was requested.
+            Some(path) if path.exists() || profile.is_some() => {
+                match config::FileSource::load(path, profile) {
+                    Ok(file) => Some(file),
+                    Err(message) => {
+                        errors.push(ParseError::Config { message });
+                        return;
+                    }
+                }
             }
-        });
-        ArgMap {
-            inner: argument_map,
+            _ => None,
+        };
+        let sources: Vec<(&dyn ConfigSource, ValueSource)> = file
+            .iter()
+            .map(|file| (file as &dyn ConfigSource, ValueSource::ConfigFile))
+            .chain(
+                self.config_sources
+                    .iter()
+                    .map(|source| (source.as_ref(), ValueSource::ConfigSource)),
+            )
+            .collect();
+        for (source, _) in &sources {
+            let mut keys = source.keys();
+            keys.sort();
+            for key in keys.iter().filter(|key| !self.has_arg(key)) {
+                errors.push(ParseError::Config {
+                    message: format!(
+                        "Unrecognized configuration key `{key}` in `{}`.",
+                        source.name()
+                    ),
+                });
+            }
+        }
+        for inner in &self.args {
+            let key = &inner.long_name;
+            // values from the command line and environment take precedence.
+            if scanned.contains_key(key) {
+                continue;
+            }
+            let Some((value, origin)) = sources
+                .iter()
+                .find_map(|(source, origin)| Some((source.get_all(key)?, *origin)))
+            else {
+                continue;
+            };
+            if value.len() > 1 && !inner.multiple {
+                errors.push(ParseError::Config {
+                    message: format!("Configuration value for `{key}` must be a single value."),
+                });
+                continue;
+            }
+            let invalid: Vec<ParseError> = value
+                .iter()
+                .filter_map(|item| inner.check_value(item).err())
+                .map(|e| ParseError::ConfigValue {
+                    key: key.clone(),
+                    error: Box::new(e),
+                })
+                .collect();
+            if !invalid.is_empty() {
+                errors.extend(invalid);
+                continue;
+            }
+            scanned.insert(key.clone(), origin);
+            argument_map.insert(key.clone(), value);
         }
     }
 }
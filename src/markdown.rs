@@ -0,0 +1,264 @@
+//! Documentation of the command line interface as Markdown, see [`crate::ArgParser::render_markdown`].
+use std::fmt::Write;
+
+use crate::{dashed, Arg, ArgParser, GroupKind};
+
+/// Renders the documentation page of `parser`: description, usage, tables of the arguments
+/// and options, notes on the groups and examples.
+pub(crate) fn render(parser: &ArgParser) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", parser.executable);
+    if !parser.description.is_empty() {
+        let _ = writeln!(out, "{}\n", parser.description);
+    }
+    if let Some(text) = &parser.long_about {
+        let _ = writeln!(out, "{text}\n");
+    }
+    let _ = writeln!(
+        out,
+        "## Usage\n\n```\n{}\n```\n",
+        parser.synopsis_text().to_string().trim_end()
+    );
+
+    let shown: Vec<&Arg> = parser
+        .help_order()
+        .into_iter()
+        .filter(|arg| !arg.hidden)
+        .collect();
+    let positionals: Vec<&Arg> = shown
+        .iter()
+        .copied()
+        .filter(|arg| arg.positional && arg.help_heading.is_none())
+        .collect();
+    if !positionals.is_empty() {
+        let _ = writeln!(out, "## Arguments\n");
+        table(&mut out, parser, &positionals);
+    }
+    let mut options: Vec<String> = shown
+        .iter()
+        .filter(|arg| !arg.positional && arg.help_heading.is_none())
+        .map(|arg| row(parser, arg))
+        .collect();
+    if parser.config_file.is_some() {
+        options.push(built_in(
+            "--profile <PROFILE>",
+            "",
+            "string",
+            "Select a configuration profile",
+        ));
+    }
+    if !parser.presets.is_empty() {
+        options.push(built_in(
+            "--preset <PRESET>",
+            "",
+            "string",
+            &format!("Select a preset of values: {}", parser.presets.join(", ")),
+        ));
+    }
+    if parser.last_run.is_some() {
+        options.push(built_in(
+            "--again",
+            "",
+            "boolean",
+            "Repeat the options of the last run",
+        ));
+    }
+    if parser.version_long() {
+        let short = parser
+            .version_short()
+            .map(|c| format!("-{c}"))
+            .unwrap_or_default();
+        options.push(built_in(
+            "--version",
+            &short,
+            "boolean",
+            "Print version information",
+        ));
+    }
+    if parser.help_enabled {
+        options.push(built_in(
+            "--help",
+            "-h",
+            "boolean",
+            "Print this help message",
+        ));
+    }
+    if !options.is_empty() {
+        let _ = writeln!(out, "## Options\n");
+        let _ = writeln!(out, "{HEADER}");
+        for line in options {
+            let _ = writeln!(out, "{line}");
+        }
+        out.push('\n');
+    }
+    // sections set with `Arg::help_heading`, in order of first use.
+    let mut headings: Vec<&str> = Vec::new();
+    for heading in shown.iter().filter_map(|arg| arg.help_heading.as_deref()) {
+        if !headings.contains(&heading) {
+            headings.push(heading);
+        }
+    }
+    for heading in headings {
+        let args: Vec<&Arg> = shown
+            .iter()
+            .copied()
+            .filter(|arg| arg.help_heading.as_deref() == Some(heading))
+            .collect();
+        let _ = writeln!(out, "## {}\n", escape(heading));
+        table(&mut out, parser, &args);
+    }
+
+    if !parser.groups.is_empty() {
+        let _ = writeln!(out, "## Argument groups\n");
+        for group in &parser.groups {
+            let args = members(parser, group.args());
+            let _ = match group.kind() {
+                GroupKind::Exclusive => {
+                    writeln!(out, "- {args} are mutually exclusive.")
+                }
+                GroupKind::OnlyWhen => writeln!(
+                    out,
+                    "- {args} can only be used together with {}.",
+                    members(parser, group.parents())
+                ),
+                GroupKind::AnyOf => writeln!(out, "- At least one of {args} must be used."),
+                GroupKind::AllOrNone => {
+                    writeln!(out, "- {args} must be used together or not at all.")
+                }
+            };
+        }
+        out.push('\n');
+    }
+
+    if !parser.examples.is_empty() {
+        let _ = writeln!(out, "## Examples\n\n```");
+        for example in &parser.examples {
+            let _ = writeln!(out, "{} {example}", parser.executable);
+        }
+        let _ = writeln!(out, "```\n");
+    }
+    let trimmed = out.trim_end().len();
+    out.truncate(trimmed);
+    out.push('\n');
+    out
+}
+
+const HEADER: &str =
+    "| Name | Short | Type | Default | Required | Environment | Values | Description |\n\
+     |------|-------|------|---------|----------|-------------|--------|-------------|";
+
+/// Table of `args` followed by a blank line.
+fn table(out: &mut String, parser: &ArgParser, args: &[&Arg]) {
+    let _ = writeln!(out, "{HEADER}");
+    for arg in args {
+        let _ = writeln!(out, "{}", row(parser, arg));
+    }
+    out.push('\n');
+}
+
+/// Members of a group as code spans, e.g. "`--json`, `--csv`", nested groups as "the options of `auth`".
+fn members(parser: &ArgParser, names: &[String]) -> String {
+    let names: Vec<String> = names
+        .iter()
+        .map(|name| match parser.group(name) {
+            Some(_) => format!("the options of `{name}`"),
+            None => format!("`--{name}`"),
+        })
+        .collect();
+    names.join(", ")
+}
+
+/// Row of the table describing `arg`, with its relations, aliases and examples noted after the description.
+fn row(parser: &ArgParser, arg: &Arg) -> String {
+    let short = arg
+        .short_name
+        .map(|c| format!("`-{c}`"))
+        .unwrap_or_default();
+    // conditional defaults first, as they take precedence.
+    let defaults: Vec<String> = arg
+        .default_if
+        .iter()
+        .map(|(other, value, default)| {
+            let default = escape(arg.display_value(default));
+            format!("`{default}` if `--{other} {}`", escape(value))
+        })
+        .chain(
+            arg.default_value
+                .iter()
+                .map(|value| format!("`{}`", escape(arg.display_value(value)))),
+        )
+        .collect();
+    let default = defaults.join(", ");
+    let required = if arg.required { "yes" } else { "no" };
+    let env = parser
+        .env_name(arg)
+        .map(|env| format!("`{env}`"))
+        .unwrap_or_default();
+    // the choices, or the range of a numeric argument.
+    let values = if arg.choices.is_empty() {
+        arg.range
+            .as_ref()
+            .map(|range| format!("`{range}`"))
+            .unwrap_or_default()
+    } else {
+        arg.choices
+            .iter()
+            .map(|choice| format!("`{}`", escape(choice)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut description = escape(&arg.description);
+    let mut notes = Vec::new();
+    let aliases = arg.alias_names();
+    if !aliases.is_empty() {
+        notes.push(format!("Aliases: `{}`", aliases.join(", ")));
+    }
+    if !arg.required_unless.is_empty() {
+        notes.push(format!(
+            "Required unless: `{}`",
+            dashed(&arg.required_unless)
+        ));
+    }
+    if !arg.requires.is_empty() {
+        notes.push(format!("Requires: `{}`", dashed(&arg.requires)));
+    }
+    if !arg.conflicts_with.is_empty() {
+        notes.push(format!("Conflicts with: `{}`", dashed(&arg.conflicts_with)));
+    }
+    if let Some(note) = &arg.deprecated {
+        notes.push(format!("Deprecated: {}", escape(note)));
+    }
+    for example in &arg.examples {
+        notes.push(format!(
+            "Example: `{} {}`",
+            parser.executable,
+            escape(example)
+        ));
+    }
+    for note in notes {
+        if !description.is_empty() {
+            description.push_str("<br>");
+        }
+        description.push_str(&note);
+    }
+    format!(
+        "| `{}` | {short} | {} | {default} | {required} | {env} | {values} | {description} |",
+        escape(&arg.synopsis().to_string()),
+        arg.kind
+    )
+}
+
+/// Row of a built-in option.
+fn built_in(option: &str, short: &str, kind: &str, description: &str) -> String {
+    let short = if short.is_empty() {
+        String::new()
+    } else {
+        format!("`{short}`")
+    };
+    format!("| `{option}` | {short} | {kind} |  | no |  |  | {description} |")
+}
+
+/// Text safe inside a table cell: pipes are escaped and line breaks become `<br>`.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
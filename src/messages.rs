@@ -0,0 +1,81 @@
+//! Translation of the messages printed by the parser, see [`crate::ArgParser::messages`].
+//!
+//! Fixed texts are looked up by their English wording, like the message ids of gettext, and errors
+//! are handed over whole so their values can be placed where the language wants them.
+//! Whatever [`Messages`] leaves out stays in English.
+//! ```
+//! # use clarg::{Arg, ArgParser, ParseError, messages::Messages};
+//! struct French;
+//!
+//! impl Messages for French {
+//!     fn text(&self, english: &str) -> Option<String> {
+//!         let text = match english {
+//!             "Usage" => "Utilisation",
+//!             "options" => "options",
+//!             "default" => "défaut",
+//!             "Print this help message" => "Affiche cette aide",
+//!             _ => return None,
+//!         };
+//!         Some(text.to_owned())
+//!     }
+//!
+//!     fn error(&self, error: &ParseError) -> Option<String> {
+//!         match error {
+//!             ParseError::MissingRequired { arg } => Some(format!("Argument obligatoire manquant : `{arg}`")),
+//!             _ => None,
+//!         }
+//!     }
+//! }
+//!
+//! let parser = ArgParser::new("Copie des fichiers.")
+//!     .arg(Arg::integer("jobs", Some('j'), false, "Copies en parallèle").default_value("4"))
+//!     .messages(French);
+//! let mut help = String::new();
+//! parser.write_help(&mut help).unwrap();
+//! assert!(help.contains("Utilisation: "));
+//! assert!(help.contains("[défaut: 4]"));
+//! ```
+use crate::ParseError;
+
+/// Texts of the help page and errors in the language of the user.
+///
+/// The fixed texts given to [`Messages::text`] are:
+/// * the headings `Usage`, `arguments`, `options` and `examples`, and the names of the groups
+///   heading their own section, along with `the options of {group}` listing a nested group.
+/// * the tags of arguments in the help page: `default`, `range`, `required unless`, `requires`,
+///   `conflicts with`, `env` and `deprecated`, as well as `required`, `aliases`, `choices` and
+///   `example` in the help of a single argument, `--help <name>`.
+/// * the descriptions of the built-in options: `Print this help message`,
+///   `Print version information`, `Select a configuration profile`, `Select a preset of values`
+///   and `Repeat the options of the last run`.
+/// * the notes on argument groups: `The following option(s) are mutually exclusive and cannot be
+///   used together:`, `The option(s):`, `Can only be used in conjunction with:`,
+///   `At least one of the following option(s) must be used:` and
+///   `The following option(s) must be used together or not at all:`.
+/// * the question asked for a missing required argument, `Enter value for {name}`, where `{name}`
+///   stands for the argument.
+/// * the hints after errors, `For more information, try '{help}'.` and
+///   `Try '{help}' for more information.`, where `{help}` stands for the help option.
+pub trait Messages: Send + Sync {
+    /// `english`, one of the fixed texts listed above, in the language of the user.
+    /// # Returns
+    /// The translation, or `None` to keep the English text.
+    fn text(&self, english: &str) -> Option<String> {
+        let _ = english;
+        None
+    }
+
+    /// Message printed for `error` by [`crate::ArgParser::parse`], and for invalid answers to prompts.
+    /// [`ParseError::to_json`] keeps the English message, for programs rather than people.
+    /// # Returns
+    /// The translation, or `None` to keep the English message.
+    fn error(&self, error: &ParseError) -> Option<String> {
+        let _ = error;
+        None
+    }
+}
+
+/// Messages of the parser when none are set, all in English.
+pub(crate) struct English;
+
+impl Messages for English {}
@@ -0,0 +1,222 @@
+//! Diagnostics of numbers that fail to convert, pointing at the character at fault: a unicode
+//! minus sign pasted from a document, a thousands separator, a decimal comma or a stray space.
+//! Also holds the bounds of numeric ranges.
+
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use core::cmp::Ordering;
+
+/// Numbers told apart by the scanner.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Number {
+    Integer,
+    Unsigned,
+    Float,
+}
+
+/// Explains why `value` is not a number of the kind given, e.g.
+/// "thousands separator `,` at position 2". Positions count characters from 1.
+/// # Returns
+/// The reason, or `None` when the value is made of valid characters in the wrong order.
+pub(crate) fn explain(value: &str, number: Number) -> Option<String> {
+    let chars: Vec<char> = value.chars().collect();
+    let (Some(first), Some(last)) = (chars.first(), chars.last()) else {
+        return Some("the value is empty".to_owned());
+    };
+    if first.is_whitespace() {
+        return Some(format!("leading {} at position 1", space(*first)));
+    }
+    if last.is_whitespace() {
+        return Some(format!(
+            "trailing {} at position {}",
+            space(*last),
+            chars.len()
+        ));
+    }
+    let digit_around = |at: usize| {
+        let before = at > 0 && chars[at - 1].is_ascii_digit();
+        before && chars.get(at + 1).is_some_and(char::is_ascii_digit)
+    };
+    let mut point = false;
+    let mut exponent = false;
+    for (at, &c) in chars.iter().enumerate() {
+        let position = at + 1;
+        // a sign starts the number or, for floats, its exponent.
+        let sign_expected = at == 0 || (exponent && matches!(chars[at - 1], 'e' | 'E'));
+        match c {
+            '0'..='9' => {}
+            '+' if sign_expected => {}
+            '-' if sign_expected && number == Number::Unsigned => {
+                return Some(format!(
+                    "`-` at position {position}, negative numbers are not accepted"
+                ))
+            }
+            '-' if sign_expected => {}
+            '\u{2212}' | '\u{2010}'..='\u{2015}' | '\u{fe63}' | '\u{ff0d}' if sign_expected => {
+                return Some(format!(
+                    "{} at position {position} is not the ASCII minus sign `-`",
+                    shown(c)
+                ))
+            }
+            '.' if number != Number::Float => {
+                return Some(format!(
+                    "decimal point `.` at position {position}, a whole number is expected"
+                ))
+            }
+            '.' if !point && !exponent => point = true,
+            'e' | 'E' if number == Number::Float && !exponent && at > 0 => exponent = true,
+            ',' if number == Number::Float && !point && !digit_around_group(&chars, at) => {
+                return Some(format!(
+                    "decimal comma `,` at position {position}, use a dot `.` instead"
+                ))
+            }
+            ',' | '_' | '\'' | '\u{a0}' | '\u{2009}' | '\u{202f}' | ' ' if digit_around(at) => {
+                return Some(format!(
+                    "thousands separator {} at position {position} is not accepted",
+                    shown(c)
+                ))
+            }
+            c if c.is_numeric() => {
+                return Some(format!(
+                    "{} at position {position} is not an ASCII digit",
+                    shown(c)
+                ))
+            }
+            c if c.is_whitespace() => return Some(format!("{} at position {position}", space(c))),
+            c => return Some(format!("unexpected {} at position {position}", shown(c))),
+        }
+    }
+    match number {
+        _ if !chars.iter().any(char::is_ascii_digit) => Some("the value has no digits".to_owned()),
+        Number::Integer => Some("the number is out of the 64-bit range".to_owned()),
+        Number::Unsigned => Some("the number is too large".to_owned()),
+        Number::Float => None,
+    }
+}
+
+/// Whether the comma at `at` splits digits in groups of three, like `1,000`, rather than
+/// separating decimals, like `3,5`.
+fn digit_around_group(chars: &[char], at: usize) -> bool {
+    let group = chars.get(at + 1..at + 4);
+    group.is_some_and(|group| group.iter().all(char::is_ascii_digit))
+        && chars.get(at + 4).is_none_or(|c| !c.is_ascii_digit())
+}
+
+/// `c` quoted, followed by its code point unless it is ASCII, e.g. "`−` (U+2212)".
+fn shown(c: char) -> String {
+    match c.is_ascii() {
+        true => format!("`{c}`"),
+        false => format!("`{c}` (U+{:04X})", u32::from(c)),
+    }
+}
+
+/// Name of the whitespace character `c`, which would not show quoted.
+fn space(c: char) -> String {
+    match c {
+        ' ' => "space".to_owned(),
+        '\t' => "tab".to_owned(),
+        c => format!("whitespace (U+{:04X})", u32::from(c)),
+    }
+}
+
+/// Bound of a range of numbers, see [`crate::Arg::range`]. Integers are kept exactly, as an
+/// `f64` can't tell apart integers beyond 2^53.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy)]
+pub enum Limit {
+    Integer(i128),
+    Float(f64),
+}
+
+#[cfg(feature = "std")]
+impl Limit {
+    /// Orders `value` against the limit.
+    /// # Returns
+    /// `None` when `value` is not a number.
+    pub(crate) fn compare(self, value: &str) -> Option<Ordering> {
+        match self {
+            Limit::Integer(limit) => match value.parse::<i128>() {
+                Ok(number) => Some(number.cmp(&limit)),
+                // above every `i128`.
+                Err(_) if value.parse::<u128>().is_ok() => Some(Ordering::Greater),
+                Err(_) => value.parse::<f64>().ok()?.partial_cmp(&(limit as f64)),
+            },
+            Limit::Float(limit) => value.parse::<f64>().ok()?.partial_cmp(&limit),
+        }
+    }
+
+    /// Whether the limit is an integer out of the range of both `i64` and `u64`.
+    pub(crate) fn is_wide(self) -> bool {
+        let narrow = i128::from(i64::MIN)..=i128::from(u64::MAX);
+        matches!(self, Limit::Integer(limit) if !narrow.contains(&limit))
+    }
+
+    /// Closest value to the limit on the side given, the limit itself unless `excluded`, as an
+    /// integer when `integer` is set.
+    pub(crate) fn nearest(self, side: Ordering, excluded: bool, integer: bool) -> String {
+        let above = side == Ordering::Greater;
+        match self {
+            Limit::Integer(limit) if !excluded => limit.to_string(),
+            Limit::Integer(limit) if integer && above => limit.saturating_add(1).to_string(),
+            Limit::Integer(limit) if integer => limit.saturating_sub(1).to_string(),
+            Limit::Integer(limit) => Limit::Float(limit as f64).nearest(side, excluded, integer),
+            Limit::Float(limit) if integer && above => match excluded {
+                true => (limit.floor() + 1.0).to_string(),
+                false => limit.ceil().to_string(),
+            },
+            Limit::Float(limit) if integer => match excluded {
+                true => (limit.ceil() - 1.0).to_string(),
+                false => limit.floor().to_string(),
+            },
+            Limit::Float(limit) if !excluded => limit.to_string(),
+            Limit::Float(limit) if above => limit.next_up().to_string(),
+            Limit::Float(limit) => limit.next_down().to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for Limit {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Limit::Integer(limit) => write!(f, "{limit}"),
+            Limit::Float(limit) => write!(f, "{limit}"),
+        }
+    }
+}
+
+/// Numbers accepted as bounds by [`crate::Arg::range`]: every primitive integer and float type.
+/// `u128` bounds above `i128::MAX` are capped to it.
+#[cfg(feature = "std")]
+pub trait Bounded: Copy {
+    #[doc(hidden)]
+    fn limit(self) -> Limit;
+}
+
+#[cfg(feature = "std")]
+macro_rules! bounded {
+    ($($ty:ty),*) => {$(
+        impl Bounded for $ty {
+            fn limit(self) -> Limit {
+                Limit::Integer(i128::try_from(self).unwrap_or(i128::MAX))
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "std")]
+bounded!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+#[cfg(feature = "std")]
+impl Bounded for f32 {
+    fn limit(self) -> Limit {
+        Limit::Float(f64::from(self))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Bounded for f64 {
+    fn limit(self) -> Limit {
+        Limit::Float(self)
+    }
+}
@@ -0,0 +1,80 @@
+//! Interactive prompts for the arguments missing from every other source.
+use std::{
+    sync::{
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    host::{Host, Stream},
+    terminal,
+};
+
+/// Asks the user for values on the terminal, giving up once a prompt can't be answered.
+pub(crate) struct Prompter {
+    host: Arc<dyn Host>,
+    timeout: Option<Duration>,
+    /// set when stdin is not a terminal, was closed or a prompt timed out.
+    closed: bool,
+}
+
+impl Prompter {
+    pub(crate) fn new(host: Arc<dyn Host>, timeout: Option<Duration>) -> Self {
+        Self {
+            closed: !host.is_terminal(Stream::Stdin),
+            host,
+            timeout,
+        }
+    }
+
+    /// Shows `message` with `show`, which writes to stderr, and waits for a line of input, not
+    /// echoed when `secret`.
+    /// # Returns
+    /// The line without its terminator, or `None` when stdin is not a terminal, reached its end,
+    /// or the timeout elapsed. Every later prompt is then unanswered as well.
+    pub(crate) fn ask(
+        &mut self,
+        message: &str,
+        secret: bool,
+        show: &dyn Fn(&str),
+    ) -> Option<String> {
+        if self.closed {
+            return None;
+        }
+        show(&format!("{message}: "));
+        // the line is read on its own thread so the wait can be bounded,
+        // after a timeout the thread stays blocked until the user presses enter.
+        let line = match self.timeout {
+            Some(timeout) => self.read_line(secret).recv_timeout(timeout).ok().flatten(),
+            None => self.read_line(secret).recv().ok().flatten(),
+        };
+        if line.is_none() {
+            self.closed = true;
+            // the blocked read of a secret would leave the terminal silent.
+            terminal::restore_echo();
+            show("\n");
+        } else if secret {
+            // the line break typed by the user was not echoed either.
+            show("\n");
+        }
+        line
+    }
+
+    /// Reads a line from the host on its own thread.
+    fn read_line(&self, secret: bool) -> Receiver<Option<String>> {
+        let (sender, receiver) = mpsc::channel();
+        let host = Arc::clone(&self.host);
+        thread::spawn(move || {
+            let line = if secret {
+                host.read_secret()
+            } else {
+                host.read_line()
+            };
+            sender.send(line).ok();
+        });
+        receiver
+    }
+}
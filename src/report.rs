@@ -0,0 +1,98 @@
+//! Summary of a parse, to print behind a `--debug-cli` flag or attach to support bundles.
+use std::{fmt, time::Duration};
+
+/// Where the value of an argument came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ValueSource {
+    /// Given on the command line.
+    CommandLine,
+    /// Read from an environment variable, see [`crate::ArgParser::env_prefix`].
+    Environment,
+    /// Read from the configuration file, see [`crate::ArgParser::config_file`].
+    ConfigFile,
+    /// Read from a backend added with [`crate::ArgParser::config_source`].
+    ConfigSource,
+    /// Taken from the preset selected with `--preset`, see [`crate::ArgParser::preset`].
+    Preset,
+    /// Answered at an interactive prompt, see [`crate::Arg::prompt`].
+    Prompt,
+    /// Filled in from [`crate::Arg::default_value`].
+    Default,
+    /// Computed at parse time, see [`crate::Arg::required_or_else`].
+    Computed,
+    /// Repeated from the last run with `--again`, see [`crate::ArgParser::remember_last`].
+    LastRun,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ValueSource::CommandLine => "command line",
+            ValueSource::Environment => "environment",
+            ValueSource::ConfigFile => "config file",
+            ValueSource::ConfigSource => "config source",
+            ValueSource::Preset => "preset",
+            ValueSource::Prompt => "prompt",
+            ValueSource::Default => "default",
+            ValueSource::Computed => "computed",
+            ValueSource::LastRun => "last run",
+        })
+    }
+}
+
+/// What happened during a parse, returned by [`crate::ArgParser::parse_with_report`].
+/// Values only appear in warnings and are masked for sensitive arguments, so the report is safe to log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseReport {
+    pub(crate) matched: Vec<(String, ValueSource)>,
+    pub(crate) groups: Vec<String>,
+    pub(crate) warnings: Vec<String>,
+    pub(crate) elapsed: Duration,
+}
+
+impl ParseReport {
+    /// Arguments given a value, with where the value came from, in declaration order.
+    pub fn matched(&self) -> &[(String, ValueSource)] {
+        &self.matched
+    }
+
+    /// Arguments whose value is their default, in declaration order.
+    pub fn defaults(&self) -> impl Iterator<Item = &str> {
+        self.matched
+            .iter()
+            .filter(|(_, source)| *source == ValueSource::Default)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Names of the groups with at least one of their arguments given by the user.
+    pub fn groups(&self) -> &[String] {
+        &self.groups
+    }
+
+    /// Problems that did not prevent parsing, see [`crate::ArgMap::warnings`].
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Time spent parsing, including reading the environment, configuration file and prompts.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "parsed in {:?}", self.elapsed)?;
+        for (name, source) in &self.matched {
+            writeln!(f, "  --{name}: {source}")?;
+        }
+        if !self.groups.is_empty() {
+            writeln!(f, "groups: {}", self.groups.join(", "))?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "warning: {warning}")?;
+        }
+        Ok(())
+    }
+}
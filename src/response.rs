@@ -0,0 +1,111 @@
+//! Response files: arguments stored in a file and passed as `@file`,
+//! to get around the limits of the platform on the length of a command line.
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{shell, ParseError};
+
+/// Deepest nesting of response files naming other response files, which also stops cycles.
+const MAX_DEPTH: usize = 8;
+
+/// Replaces every `@file` argument before the `--` separator with the arguments read from the
+/// file, split following POSIX shell rules, expanding the response files named there in turn.
+/// # Arguments
+/// `literal` Whether the argument following the one given is a value to keep as is, like the
+/// `@path` of [`crate::Arg::allow_file_ref`].
+/// # Returns
+/// The arguments, each with the position in `args` of the one it is, `None` for the arguments
+/// read from files.
+/// # Errors
+/// If a file cannot be read or split, or response files are nested too deep.
+pub(crate) fn expand(
+    args: Vec<String>,
+    depth: usize,
+    literal: &dyn Fn(&str) -> bool,
+) -> Result<Vec<(String, Option<usize>)>, ParseError> {
+    let mut expanded: Vec<(String, Option<usize>)> = Vec::with_capacity(args.len());
+    let mut args = args.into_iter().enumerate();
+    while let Some((at, arg)) = args.next() {
+        if arg == "--" {
+            expanded.push((arg, Some(at)));
+            expanded.extend(args.map(|(at, arg)| (arg, Some(at))));
+            break;
+        }
+        let file = arg.strip_prefix('@').filter(|file| {
+            !file.is_empty() && !expanded.last().is_some_and(|(last, _)| literal(last))
+        });
+        let Some(file) = file else {
+            expanded.push((arg, Some(at)));
+            continue;
+        };
+        let error = |message: String| ParseError::ResponseFile {
+            file: file.to_owned(),
+            message,
+        };
+        if depth == MAX_DEPTH {
+            return Err(error(format!(
+                "response files are nested more than {MAX_DEPTH} levels deep."
+            )));
+        }
+        let content = fs::read_to_string(file).map_err(|e| error(e.to_string()))?;
+        let words = shell::split(&content).map_err(error)?;
+        let words = expand(words, depth + 1, literal)?;
+        expanded.extend(words.into_iter().map(|(word, _)| (word, None)));
+    }
+    Ok(expanded)
+}
+
+/// Approximate length of the command line made of `args`, accounting for separators
+/// and the quotes the platform may add around each argument.
+pub(crate) fn command_line_length(args: &[String]) -> usize {
+    args.iter().map(|arg| arg.len() + 3).sum()
+}
+
+/// Writes `args` to a new response file in `dir`, one argument per line, quoted following
+/// POSIX shell rules when needed. The file is only readable by its owner on Unix, since
+/// the arguments may hold secrets, and is never an existing file, e.g. a link placed in a
+/// shared directory.
+/// # Returns
+/// The path of the file written.
+pub(crate) fn write(dir: &Path, args: &[String]) -> io::Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or(0);
+    let path = dir.join(format!(
+        "clarg-{}-{}-{}.rsp",
+        std::process::id(),
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut content = String::new();
+    for arg in args {
+        content.push_str(&quote(arg));
+        content.push('\n');
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(&path)?.write_all(content.as_bytes())?;
+    Ok(path)
+}
+
+/// Quotes `arg` so it is read back as a single argument.
+pub(crate) fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if plain {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
@@ -0,0 +1,516 @@
+//! Scanning and validation of command line words with `alloc` only.
+//!
+//! [`crate::ArgParser`] reads the environment, configuration files and terminals, and exits the
+//! process, all of which take `std`. This module is the part that doesn't: words given as a slice
+//! are matched against a list of [`Spec`], and their values checked against their kind. The
+//! parser matches its own words with the same functions, so clusters of short flags, values
+//! given with `=` or after a short name, negative numbers and the errors are the same. Without
+//! the default `std` feature, it is all the crate offers, e.g. on embedded or WebAssembly targets.
+//!
+//! # Example
+//! ```
+//! use clarg::{scan::{self, Spec}, ArgKind};
+//!
+//! let specs = [
+//!     Spec::option("depth", Some('d'), ArgKind::Integer),
+//!     Spec::flag("verbose", Some('v')),
+//!     Spec::positional("path", ArgKind::Path).required(true),
+//! ];
+//! let scanned = scan::scan(&specs, ["-v", "--depth=2", "src"]).unwrap();
+//! assert_eq!(scanned.value("depth"), Some("2"));
+//! assert_eq!(scanned.value("verbose"), Some("true"));
+//! assert_eq!(scanned.values("path"), ["src"]);
+//! ```
+use alloc::{
+    borrow::ToOwned,
+    collections::VecDeque,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::iter::Peekable;
+
+use crate::{
+    datetime, formats,
+    numbers::{self, Number},
+    suggest, units, ArgKind, ParseError, MASK,
+};
+
+/// Argument known to [`scan`].
+#[derive(Clone, Debug)]
+pub struct Spec {
+    name: String,
+    short: Option<char>,
+    kind: ArgKind,
+    positional: bool,
+    multiple: bool,
+    required: bool,
+}
+
+impl Spec {
+    /// Option given as `--name value`, `--name=value`, `-s value` or `-svalue`. Options of the
+    /// [`ArgKind::Boolean`] and [`ArgKind::Count`] kinds take no value, see [`Spec::flag`].
+    pub fn option(name: &str, short: Option<char>, kind: ArgKind) -> Spec {
+        Spec {
+            name: name.to_owned(),
+            short,
+            kind,
+            positional: false,
+            multiple: false,
+            required: false,
+        }
+    }
+
+    /// Flag given as `--name` or `-s`, possibly among other short flags like `-vq`.
+    /// Its value is `true` once given.
+    pub fn flag(name: &str, short: Option<char>) -> Spec {
+        Spec::option(name, short, ArgKind::Boolean)
+    }
+
+    /// Argument given as a bare word, positional arguments being filled in the order of the specs.
+    pub fn positional(name: &str, kind: ArgKind) -> Spec {
+        Spec {
+            positional: true,
+            ..Spec::option(name, None, kind)
+        }
+    }
+
+    /// Let the argument collect several values, either by repeating it or by listing the values
+    /// after it. A positional argument taking several values takes every remaining word.
+    pub fn multiple(mut self, multiple: bool) -> Spec {
+        self.multiple = multiple;
+        self
+    }
+
+    /// Set whether the argument must be given.
+    pub fn required(mut self, required: bool) -> Spec {
+        self.required = required;
+        self
+    }
+}
+
+/// Values found by [`scan`], in the order they were given.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Scanned {
+    values: Vec<(String, Vec<String>)>,
+    trailing: Vec<String>,
+}
+
+impl Scanned {
+    /// Last value of the argument `name`, or `None` when it was not given.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values(name).last().map(String::as_str)
+    }
+
+    /// Every value of the argument `name`, none when it was not given.
+    pub fn values(&self, name: &str) -> &[String] {
+        self.values
+            .iter()
+            .find(|(given, _)| given == name)
+            .map_or(&[], |(_, values)| values.as_slice())
+    }
+
+    /// Words given after `--`, as they are.
+    pub fn trailing(&self) -> &[String] {
+        &self.trailing
+    }
+
+    fn slot(&mut self, name: &str) -> &mut Vec<String> {
+        let at = match self.values.iter().position(|(given, _)| given == name) {
+            Some(at) => at,
+            None => {
+                self.values.push((name.to_owned(), Vec::new()));
+                self.values.len() - 1
+            }
+        };
+        &mut self.values[at].1
+    }
+}
+
+/// Matches `words`, not including the executable name, against `specs` and checks their values.
+/// A lone `-`, standing for a standard stream, is a value of string and path arguments.
+/// # Errors
+/// Every problem found, in the order they were found.
+/// # Example
+/// ```
+/// use clarg::{scan::{self, Spec}, ArgKind, ParseError};
+///
+/// let specs = [Spec::flag("verbose", Some('v')), Spec::positional("input", ArgKind::Path)];
+/// let scanned = scan::scan(&specs, ["-v", "-"]).unwrap();
+/// assert_eq!(scanned.value("input"), Some("-"));
+///
+/// let errors = scan::scan(&specs[..1], ["-"]).unwrap_err();
+/// assert!(matches!(&errors[..], [ParseError::UnknownOption { option, .. }] if option == "-"));
+/// ```
+pub fn scan<I, S>(specs: &[Spec], words: I) -> Result<Scanned, Vec<ParseError>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut words = words
+        .into_iter()
+        .map(|word| word.as_ref().to_owned())
+        .peekable();
+    let mut scanned = Scanned::default();
+    let mut errors = Vec::new();
+    let positionals: Vec<&Spec> = specs.iter().filter(|spec| spec.positional).collect();
+    let mut position = 0;
+    // flags expanded from a cluster of short names like `-vq`, scanned before the next word.
+    let mut clustered: VecDeque<String> = VecDeque::new();
+    while let Some(mut word) = clustered.pop_front().or_else(|| words.next()) {
+        if let Some(flags) = cluster(specs, &word) {
+            clustered.extend(flags);
+            continue;
+        }
+        // everything after the separator is collected verbatim.
+        if word == "--" {
+            scanned.trailing.extend(words.by_ref());
+            break;
+        }
+        let mut inline = None;
+        if let Some((option, value)) = inline_value(specs, &word) {
+            (word, inline) = (option, Some(value));
+        }
+        let positional = positionals.get(position).copied();
+        if !is_option(&word, positional) {
+            let Some(spec) = positional else {
+                errors.push(ParseError::UnexpectedArgument { value: word });
+                continue;
+            };
+            if !spec.multiple {
+                position += 1;
+            }
+            match check(&spec.name, spec.kind, &word, false, false) {
+                Ok(()) => scanned.slot(&spec.name).push(word),
+                Err(e) => errors.push(e),
+            }
+            continue;
+        }
+        let Some(spec) = find(specs, &word) else {
+            let name = word.trim_start_matches('-');
+            let names = specs
+                .iter()
+                .filter(|spec| !spec.positional)
+                .map(|spec| spec.name.as_str());
+            let suggestion = suggest::closest(name, names)
+                .filter(|_| name.chars().count() > 1)
+                .map(str::to_owned);
+            if let Some(value) = inline {
+                word = format!("{word}={value}");
+            }
+            errors.push(ParseError::UnknownOption {
+                option: word,
+                suggestion,
+            });
+            continue;
+        };
+        if spec.is_flag() {
+            if let Some(value) = inline {
+                errors.push(ParseError::UnexpectedValue {
+                    arg: spec.name.clone(),
+                    option: word,
+                    value,
+                });
+                continue;
+            }
+            let value = match spec.kind {
+                ArgKind::Count => {
+                    let count = scanned.value(&spec.name).and_then(|v| v.parse().ok());
+                    (count.unwrap_or(0u32) + 1).to_string()
+                }
+                _ => "true".to_owned(),
+            };
+            *scanned.slot(&spec.name) = Vec::from([value]);
+            continue;
+        }
+        let value = match inline.map_or_else(|| next_value(spec, &mut words, |_| false), Ok) {
+            Ok(value) => value,
+            Err(Some(value)) => {
+                errors.push(ParseError::UnexpectedValue {
+                    arg: spec.name.clone(),
+                    option: word,
+                    value,
+                });
+                continue;
+            }
+            Err(None) => {
+                errors.push(ParseError::MissingValue {
+                    arg: spec.name.clone(),
+                    option: word,
+                });
+                continue;
+            }
+        };
+        let mut values = Vec::from([value]);
+        if spec.multiple {
+            values.extend(core::iter::from_fn(|| next_item(spec, &mut words)));
+        }
+        let invalid: Vec<ParseError> = values
+            .iter()
+            .filter_map(|value| check(&spec.name, spec.kind, value, false, false).err())
+            .collect();
+        if !invalid.is_empty() {
+            errors.extend(invalid);
+            continue;
+        }
+        let stored = scanned.slot(&spec.name);
+        if !spec.multiple {
+            stored.clear();
+        }
+        stored.extend(values);
+    }
+    for spec in specs {
+        if spec.required && scanned.value(&spec.name).is_none() {
+            errors.push(ParseError::MissingRequired {
+                arg: spec.name.clone(),
+            });
+        }
+    }
+    match errors.is_empty() {
+        true => Ok(scanned),
+        false => Err(errors),
+    }
+}
+
+/// Argument words are matched against, a [`Spec`] or an argument of [`crate::ArgParser`].
+pub(crate) trait Known {
+    /// Kind of the values of the argument.
+    fn value_kind(&self) -> ArgKind;
+
+    /// Checks whether the argument is given as a bare word rather than an option.
+    fn is_positional(&self) -> bool;
+
+    /// Checks whether `--<name>` refers to this argument.
+    fn answers_to(&self, name: &str) -> bool;
+
+    /// Checks whether `-<c>` refers to this argument.
+    fn answers_to_short(&self, c: char) -> bool;
+
+    /// Checks whether a word following the argument is one of its values rather than the next
+    /// option.
+    fn takes_token(&self, word: &str) -> bool;
+
+    /// Checks whether the argument is a flag, taking no value.
+    fn is_flag(&self) -> bool {
+        matches!(self.value_kind(), ArgKind::Boolean | ArgKind::Count)
+    }
+}
+
+impl Known for Spec {
+    fn value_kind(&self) -> ArgKind {
+        self.kind
+    }
+
+    fn is_positional(&self) -> bool {
+        self.positional
+    }
+
+    fn answers_to(&self, name: &str) -> bool {
+        self.name == name
+    }
+
+    fn answers_to_short(&self, c: char) -> bool {
+        self.short == Some(c)
+    }
+
+    fn takes_token(&self, word: &str) -> bool {
+        (word == "-" && matches!(self.kind, ArgKind::String | ArgKind::Path))
+            || takes(self.kind, word)
+    }
+}
+
+/// Whether `word` is an option rather than a value of `positional`, the next positional
+/// argument to fill, if any.
+pub(crate) fn is_option<A: Known>(word: &str, positional: Option<&A>) -> bool {
+    word.starts_with('-') && !positional.is_some_and(|arg| arg.takes_token(word))
+}
+
+/// Option among `args` named by `option`, e.g. `--depth` or `-d`.
+pub(crate) fn find<'a, A: Known>(args: &'a [A], option: &str) -> Option<&'a A> {
+    let name = option.trim_start_matches('-');
+    args.iter().filter(|arg| !arg.is_positional()).find(|arg| {
+        arg.answers_to(name)
+            || (name.chars().count() == 1 && name.chars().all(|c| arg.answers_to_short(c)))
+    })
+}
+
+/// Splits a cluster of short flags like `-rvv` into `-r`, `-v`, `-v`. The cluster may end with
+/// an option taking a value and the value itself, e.g. `-vn5` into `-v`, `-n5`.
+/// # Returns
+/// The separate flags, or `None` if `word` does not start with at least one short name of a
+/// flag among `args`, all the way to its end or to an option taking a value.
+pub(crate) fn cluster<A: Known>(args: &[A], word: &str) -> Option<Vec<String>> {
+    let names = word.strip_prefix('-').filter(|names| {
+        !names.starts_with('-')
+            && names.chars().count() > 1
+            && !args.iter().any(|arg| arg.answers_to(names))
+    })?;
+    let mut flags = Vec::new();
+    for (at, c) in names.char_indices() {
+        let inner = args
+            .iter()
+            .find(|arg| arg.answers_to_short(c) && !arg.is_positional())?;
+        if !inner.is_flag() {
+            // the rest of the cluster is its value, like `-n5` on its own.
+            if flags.is_empty() {
+                return None;
+            }
+            flags.push(format!("-{}", &names[at..]));
+            break;
+        }
+        flags.push(format!("-{c}"));
+    }
+    Some(flags)
+}
+
+/// Splits an option from the value given in the same word, e.g. `--output=file.txt`, or `-n5`
+/// and `-ooutput.txt` for the short name of an argument among `args` taking a value.
+/// # Returns
+/// The option and its value, or `None` if `word` holds no value or is the name of an argument
+/// itself.
+pub(crate) fn inline_value<A: Known>(args: &[A], word: &str) -> Option<(String, String)> {
+    if word.starts_with("--") {
+        let (option, value) = word.split_once('=')?;
+        return Some((option.to_owned(), value.to_owned()));
+    }
+    let names = word.strip_prefix('-')?;
+    let c = names.chars().next()?;
+    let value = &names[c.len_utf8()..];
+    args.iter()
+        .find(|inner| inner.answers_to_short(c) && !inner.is_positional() && !inner.is_flag())
+        .filter(|_| !value.is_empty() && !args.iter().any(|arg| arg.answers_to(names)))
+        .map(|_| (format!("-{c}"), value.to_owned()))
+}
+
+/// Takes the value of the option `arg` from the next word of `words`, unless `stop` tells the
+/// word is not for it.
+/// # Errors
+/// `None` when there is no value, or the word when it is not a valid string value as it looks
+/// like an option.
+pub(crate) fn next_value<A, I>(
+    arg: &A,
+    words: &mut Peekable<I>,
+    stop: impl Fn(&str) -> bool,
+) -> Result<String, Option<String>>
+where
+    A: Known,
+    I: Iterator<Item = String>,
+{
+    let value = words.next_if(|word| !stop(word)).ok_or(None)?;
+    // strings are not converted, but must not look like an option.
+    if arg.value_kind() == ArgKind::String && !arg.takes_token(&value) {
+        return Err(Some(value));
+    }
+    Ok(value)
+}
+
+/// Takes one more value of the multi-value argument `arg` from `words`, up to the next option.
+pub(crate) fn next_item<A, I>(arg: &A, words: &mut Peekable<I>) -> Option<String>
+where
+    A: Known,
+    I: Iterator<Item = String>,
+{
+    words.next_if(|word| arg.takes_token(word))
+}
+
+/// Whether a word following an argument of `kind` is one of its values rather than an option,
+/// negative numbers like `-5` being values of integer and float arguments.
+pub(crate) fn takes(kind: ArgKind, word: &str) -> bool {
+    (kind.is_number() && word.parse::<f64>().is_ok()) || !word.starts_with('-')
+}
+
+/// Checks whether `value` is valid for the kind of the argument `name`, e.g. a number for
+/// [`ArgKind::Integer`], integers taking up to 128 bits when `wide`. The value is masked in the
+/// error when `sensitive`, which then doesn't point at the character at fault either.
+/// # Errors
+/// A [`ParseError::TypeMismatch`].
+pub(crate) fn check(
+    name: &str,
+    kind: ArgKind,
+    value: &str,
+    wide: bool,
+    sensitive: bool,
+) -> Result<(), ParseError> {
+    let Some((description, code)) = mismatch(kind, value, wide) else {
+        return Ok(());
+    };
+    let shown = if sensitive { MASK } else { value };
+    let detail = match kind {
+        ArgKind::Integer => numbers::explain(value, Number::Integer),
+        ArgKind::Unsigned | ArgKind::Count => numbers::explain(value, Number::Unsigned),
+        ArgKind::Float => numbers::explain(value, Number::Float),
+        _ => None,
+    }
+    .filter(|_| !sensitive);
+    let message = match (kind, detail) {
+        (
+            ArgKind::IpAddr
+            | ArgKind::SocketAddr
+            | ArgKind::Regex
+            | ArgKind::Url
+            | ArgKind::SemVer
+            | ArgKind::Uuid,
+            _,
+        ) => format!("`{shown}` is not a valid {description} for --{name}."),
+        (_, Some(detail)) => format!("Cannot convert `{shown}` into {description}: {detail}."),
+        (_, None) => format!("Cannot convert `{shown}` into {description}."),
+    };
+    Err(ParseError::TypeMismatch {
+        arg: name.to_owned(),
+        value: shown.to_owned(),
+        code,
+        message,
+    })
+}
+
+/// What `value` fails to be for `kind`, and the code of the error.
+/// # Returns
+/// The description and code, e.g. `("integer", "E_INT_INVALID")`, or `None` when `value` is valid.
+fn mismatch(kind: ArgKind, value: &str, wide: bool) -> Option<(&'static str, &'static str)> {
+    // nothing is allocated for valid values, which is most of them on long command lines.
+    let valid = match kind {
+        ArgKind::Integer => value.parse::<i64>().is_ok() || (wide && value.parse::<i128>().is_ok()),
+        ArgKind::Unsigned => {
+            value.parse::<u64>().is_ok() || (wide && value.parse::<u128>().is_ok())
+        }
+        ArgKind::Float => value.parse::<f32>().is_ok(),
+        ArgKind::Boolean => value.parse::<bool>().is_ok(),
+        ArgKind::Count => value.parse::<u32>().is_ok(),
+        ArgKind::DateTime => datetime::parse(value).is_ok(),
+        ArgKind::Duration => units::duration(value).is_ok(),
+        ArgKind::Size => units::size(value).is_ok(),
+        ArgKind::IpAddr => value.parse::<core::net::IpAddr>().is_ok(),
+        ArgKind::SocketAddr => value.parse::<core::net::SocketAddr>().is_ok(),
+        ArgKind::Regex => formats::is_regex(value),
+        ArgKind::Url => formats::is_url(value),
+        ArgKind::SemVer => formats::is_semver(value),
+        ArgKind::Uuid => formats::is_uuid(value),
+        ArgKind::String | ArgKind::Path => true,
+    };
+    if valid {
+        return None;
+    }
+    Some(match kind {
+        ArgKind::Integer => ("integer", "E_INT_INVALID"),
+        ArgKind::Unsigned => ("unsigned integer", "E_UINT_INVALID"),
+        ArgKind::Float => ("floating point number", "E_FLOAT_INVALID"),
+        ArgKind::Boolean => ("boolean", "E_BOOL_INVALID"),
+        ArgKind::Count => ("count", "E_COUNT_INVALID"),
+        ArgKind::DateTime => ("date and time", "E_DATETIME_INVALID"),
+        ArgKind::Duration => (
+            "duration like `30s` or `2h30m` (units: ms, s, m, h, d)",
+            "E_DURATION_INVALID",
+        ),
+        ArgKind::Size => (
+            "size like `10MB` or `1GiB` (units: B, KB, MB, GB, TB, KiB, MiB, GiB, TiB, PiB)",
+            "E_SIZE_INVALID",
+        ),
+        ArgKind::IpAddr => ("IP address", "E_IP_INVALID"),
+        ArgKind::SocketAddr => ("socket address (HOST:PORT)", "E_SOCKET_ADDR_INVALID"),
+        ArgKind::Regex => ("regular expression", "E_REGEX_INVALID"),
+        ArgKind::Url => ("URL", "E_URL_INVALID"),
+        ArgKind::SemVer => ("version (MAJOR.MINOR.PATCH)", "E_SEMVER_INVALID"),
+        ArgKind::Uuid => ("UUID", "E_UUID_INVALID"),
+        ArgKind::String | ArgKind::Path => return None,
+    })
+}
@@ -0,0 +1,141 @@
+//! Export of the arguments as a JSON Schema, to generate settings dialogs or web forms
+//! fronting the command line interface.
+//!
+//! Every argument is a property of an object keyed by its long name, with its type, description,
+//! default value, choices and range. Multi-value arguments are arrays, sensitive arguments are
+//! marked `writeOnly` and never expose their default value.
+//! ```
+//! # use clarg::{Arg, ArgParser};
+//! let parser = ArgParser::new("Find duplicate files.")
+//!     .arg(Arg::integer("depth", Some('d'), false, "Maximum depth").range(1..=32))
+//!     .arg(Arg::string("format", None, true, "Output format").choices(&["json", "text"]));
+//! let schema = clarg::schema::render_schema(&parser);
+//! assert!(schema.contains("\"enum\": [\"json\", \"text\"]"));
+//! ```
+use std::{fmt::Write, ops::Bound};
+
+use crate::{json, Arg, ArgKind, ArgParser};
+
+/// Renders the arguments of `parser` as a JSON Schema (draft 2020-12) describing an object.
+pub fn render_schema(parser: &ArgParser) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "{{");
+    let _ = writeln!(
+        out,
+        "  \"$schema\": \"https://json-schema.org/draft/2020-12/schema\","
+    );
+    let _ = writeln!(out, "  \"title\": {},", json::string(&parser.executable));
+    if !parser.description.is_empty() {
+        let _ = writeln!(
+            out,
+            "  \"description\": {},",
+            json::string(&parser.description)
+        );
+    }
+    let _ = writeln!(out, "  \"type\": \"object\",");
+    let properties: Vec<String> = parser
+        .args
+        .iter()
+        .map(|arg| format!("    {}: {}", json::string(&arg.long_name), property(arg)))
+        .collect();
+    let _ = writeln!(
+        out,
+        "  \"properties\": {{\n{}\n  }},",
+        properties.join(",\n")
+    );
+    let required: Vec<String> = parser
+        .args
+        .iter()
+        .filter(|arg| arg.required && arg.default_value.is_none())
+        .map(|arg| json::string(&arg.long_name))
+        .collect();
+    let _ = writeln!(out, "  \"required\": [{}]", required.join(", "));
+    let _ = writeln!(out, "}}");
+    out
+}
+
+/// Schema of a single argument.
+fn property(arg: &Arg) -> String {
+    let mut fields = vec![format!("\"type\": \"{}\"", json_type(arg.kind))];
+    if let Some(range) = &arg.range {
+        match range.start {
+            Bound::Included(min) => fields.push(format!("\"minimum\": {min}")),
+            Bound::Excluded(min) => fields.push(format!("\"exclusiveMinimum\": {min}")),
+            Bound::Unbounded => {}
+        }
+        match range.end {
+            Bound::Included(max) => fields.push(format!("\"maximum\": {max}")),
+            Bound::Excluded(max) => fields.push(format!("\"exclusiveMaximum\": {max}")),
+            Bound::Unbounded => {}
+        }
+    } else if matches!(arg.kind, ArgKind::Unsigned | ArgKind::Count) {
+        fields.push("\"minimum\": 0".to_owned());
+    }
+    let format = match arg.kind {
+        ArgKind::Regex => Some("regex"),
+        ArgKind::Url => Some("uri"),
+        ArgKind::Uuid => Some("uuid"),
+        _ => None,
+    };
+    if let Some(format) = format {
+        fields.push(format!("\"format\": \"{format}\""));
+    }
+    if !arg.choices.is_empty() {
+        let choices: Vec<String> = arg
+            .choices
+            .iter()
+            .map(|c| json::value(arg.kind, c))
+            .collect();
+        fields.push(format!("\"enum\": [{}]", choices.join(", ")));
+    }
+    if arg.multiple {
+        let mut array = vec![
+            "\"type\": \"array\"".to_owned(),
+            format!("\"items\": {{{}}}", fields.join(", ")),
+        ];
+        if arg.deny_duplicates || arg.dedup {
+            array.push("\"uniqueItems\": true".to_owned());
+        }
+        fields = array;
+    }
+    if !arg.description.is_empty() {
+        fields.push(format!(
+            "\"description\": {}",
+            json::string(&arg.description)
+        ));
+    }
+    match &arg.default_value {
+        Some(_) if arg.sensitive => {}
+        Some(default) if arg.multiple => {
+            fields.push(format!("\"default\": [{}]", json::value(arg.kind, default)))
+        }
+        Some(default) => fields.push(format!("\"default\": {}", json::value(arg.kind, default))),
+        None => {}
+    }
+    if arg.sensitive {
+        fields.push("\"writeOnly\": true".to_owned());
+    }
+    if arg.positional {
+        fields.push("\"x-positional\": true".to_owned());
+    }
+    format!("{{{}}}", fields.join(", "))
+}
+
+fn json_type(kind: ArgKind) -> &'static str {
+    match kind {
+        ArgKind::String
+        | ArgKind::Path
+        | ArgKind::DateTime
+        | ArgKind::Duration
+        | ArgKind::Size
+        | ArgKind::IpAddr
+        | ArgKind::SocketAddr
+        | ArgKind::Regex
+        | ArgKind::Url
+        | ArgKind::SemVer
+        | ArgKind::Uuid => "string",
+        ArgKind::Integer | ArgKind::Unsigned | ArgKind::Count => "integer",
+        ArgKind::Float => "number",
+        ArgKind::Boolean => "boolean",
+    }
+}
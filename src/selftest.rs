@@ -0,0 +1,201 @@
+//! Consistency checks of a parser definition, to run from a unit test or behind a hidden
+//! `self-test cli` command of the application.
+//!
+//! [`run`] goes over the definition the way a reviewer would: names used twice and relations to
+//! unknown arguments, arguments that can never be given, defaults contradicting requirements
+//! and help lines too wide for the terminal.
+//! ```
+//! # use clarg::{Arg, ArgParser};
+//! let parser = ArgParser::new("Find duplicate files.")
+//!     .arg(Arg::boolean("json", Some('j'), "Format output as JSON"))
+//!     .arg(Arg::boolean("jobs", Some('j'), "Number of threads"));
+//! let report = clarg::selftest::run(&parser);
+//! assert!(!report.passed());
+//! assert!(report.to_string().contains("Short option `-j` of argument `jobs`"));
+//! ```
+use std::fmt;
+
+use crate::{ArgKind, ArgParser};
+
+/// Widths the help page is rendered at.
+const WIDTHS: [usize; 3] = [60, 80, 120];
+
+/// Outcome of one check of a [`Report`].
+#[derive(Debug, Clone)]
+pub struct Check {
+    name: &'static str,
+    problems: Vec<String>,
+}
+
+impl Check {
+    /// Name of the check, e.g. "unreachable arguments".
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Problems found, one sentence each.
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    /// Whether no problem was found.
+    pub fn passed(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Result of [`run`], printed as one line per check followed by its problems.
+#[derive(Debug, Clone)]
+pub struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    /// The checks run, in order.
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    /// Whether every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(Check::passed)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = if check.passed() { "ok" } else { "FAILED" };
+            writeln!(f, "{}: {status}", check.name)?;
+            for problem in &check.problems {
+                writeln!(f, "    {problem}")?;
+            }
+        }
+        let failed = self.checks.iter().filter(|check| !check.passed()).count();
+        match failed {
+            0 => writeln!(f, "All {} checks passed.", self.checks.len()),
+            _ => writeln!(f, "{failed} of {} checks failed.", self.checks.len()),
+        }
+    }
+}
+
+/// Runs every check on the definition of `parser`.
+/// # Example
+/// ```no_run
+/// # use clarg::ArgParser;
+/// # let parser = ArgParser::new("Find duplicate files.");
+/// let report = clarg::selftest::run(&parser);
+/// print!("{report}");
+/// std::process::exit(if report.passed() { 0 } else { 1 });
+/// ```
+pub fn run(parser: &ArgParser) -> Report {
+    let definition = match parser.validate() {
+        Ok(()) => Vec::new(),
+        Err(problems) => problems.lines().map(str::to_owned).collect(),
+    };
+    Report {
+        checks: vec![
+            Check {
+                name: "definition",
+                problems: definition,
+            },
+            Check {
+                name: "unreachable arguments",
+                problems: unreachable(parser),
+            },
+            Check {
+                name: "defaults and requirements",
+                problems: defaults(parser),
+            },
+            Check {
+                name: "help rendering",
+                problems: help(parser),
+            },
+        ],
+    }
+}
+
+/// Arguments that no command line can give: positionals behind one taking every remaining value
+/// and arguments conflicting with a required argument without default.
+fn unreachable(parser: &ArgParser) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut greedy: Option<&str> = None;
+    for arg in parser.args.iter().filter(|arg| arg.positional) {
+        match greedy {
+            Some(name) => problems.push(format!(
+                "Positional argument `{}` comes after `{name}`, which takes every remaining value.",
+                arg.long_name
+            )),
+            None if arg.multiple => greedy = Some(&arg.long_name),
+            None => {}
+        }
+    }
+    for arg in &parser.args {
+        let always_given = |name: &String| {
+            parser.args.iter().any(|other| {
+                &other.long_name == name && other.required && other.default_value.is_none()
+            })
+        };
+        if let Some(name) = arg.conflicts_with.iter().find(|name| always_given(name)) {
+            problems.push(format!(
+                "Argument `{}` conflicts with the required argument `{name}`.",
+                arg.long_name
+            ));
+        }
+    }
+    problems
+}
+
+/// Required arguments that have a default anyway, and defaults their own argument rejects.
+/// Defaults of paths are not checked, they may only exist where the application runs.
+fn defaults(parser: &ArgParser) -> Vec<String> {
+    let mut problems = Vec::new();
+    for arg in &parser.args {
+        if arg.required && arg.default_value.is_some() {
+            problems.push(format!(
+                "Argument `{}` is required but has a default value, so it is never missing.",
+                arg.long_name
+            ));
+        }
+        if arg.kind == ArgKind::Path {
+            continue;
+        }
+        let defaults = arg
+            .default_value
+            .iter()
+            .chain(arg.default_if.iter().map(|(_, _, fallback)| fallback));
+        for default in defaults {
+            if let Err(error) = arg.check_value(default) {
+                problems.push(format!(
+                    "Default value of argument `{}` is invalid: {error}",
+                    arg.long_name
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Lines of the help page, short and verbose, wider than the terminal at each of [`WIDTHS`].
+/// A line holding a single word too long to wrap is not counted.
+fn help(parser: &ArgParser) -> Vec<String> {
+    let mut problems = Vec::new();
+    for width in WIDTHS {
+        for verbose in [false, true] {
+            let text = parser.help_text(None, verbose, width).to_string();
+            let overflow = text.lines().enumerate().find(|(_, line)| {
+                line.chars().count() > width && line.trim().contains(char::is_whitespace)
+            });
+            if let Some((at, line)) = overflow {
+                let page = if verbose { "long help" } else { "help" };
+                problems.push(format!(
+                    "At {width} columns, line {} of the {page} is {} characters wide: `{}`",
+                    at + 1,
+                    line.chars().count(),
+                    line.trim()
+                ));
+            }
+        }
+    }
+    problems
+}
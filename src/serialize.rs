@@ -0,0 +1,90 @@
+//! [`serde::Serialize`] implementations, behind the `serde` feature.
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{
+    definition::{self, Node},
+    ArgKind, ArgMap, ArgParser,
+};
+
+/// Serializes the definition like [`ArgParser::to_json`]: the program, every argument with its
+/// kind, default, choices and relations, the groups and the examples.
+impl Serialize for ArgParser {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        definition::describe(self).serialize(serializer)
+    }
+}
+
+impl Serialize for Node {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Node::Null => serializer.serialize_none(),
+            Node::Bool(flag) => serializer.serialize_bool(*flag),
+            Node::Count(count) => serializer.serialize_u64(*count as u64),
+            Node::Text(text) => serializer.serialize_str(text),
+            Node::Value(kind, value) => Typed(*kind, value).serialize(serializer),
+            Node::List(items) => items.serialize(serializer),
+            Node::Object(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (key, node) in fields {
+                    map.serialize_entry(key, node)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Serializes the values like [`ArgMap::to_json`]: a map keyed by long name in declaration
+/// order, numbers and booleans as such, multi-value arguments as sequences, sensitive values
+/// masked and the trailing arguments left out.
+impl Serialize for ArgMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (arg, values) in self.entries() {
+            let kind = match self.is_sensitive(&arg.name) {
+                true => ArgKind::String,
+                false => arg.kind,
+            };
+            let values: Vec<Typed> = self
+                .shown_values(&arg.name, values)
+                .into_iter()
+                .map(|value| Typed(kind, value))
+                .collect();
+            match arg.multiple {
+                true => map.serialize_entry(&arg.name, &values)?,
+                false => map.serialize_entry(&arg.name, &values.last())?,
+            }
+        }
+        map.end()
+    }
+}
+
+/// A raw value serialized as the kind of its argument, like [`crate::json::value`].
+pub(crate) struct Typed<'a>(pub(crate) ArgKind, pub(crate) &'a str);
+
+impl Serialize for Typed<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Typed(kind, value) = *self;
+        if matches!(kind, ArgKind::Integer | ArgKind::Unsigned | ArgKind::Count) {
+            if let Ok(number) = value.parse::<i64>() {
+                return serializer.serialize_i64(number);
+            }
+            if let Ok(number) = value.parse::<u64>() {
+                return serializer.serialize_u64(number);
+            }
+        }
+        match kind {
+            ArgKind::Integer | ArgKind::Unsigned | ArgKind::Float | ArgKind::Count => {
+                match value.parse::<f64>() {
+                    Ok(number) if number.is_finite() => serializer.serialize_f64(number),
+                    _ => serializer.serialize_str(value),
+                }
+            }
+            ArgKind::Boolean => match value.parse::<bool>() {
+                Ok(flag) => serializer.serialize_bool(flag),
+                Err(_) => serializer.serialize_str(value),
+            },
+            _ => serializer.serialize_str(value),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Shell-like splitting of command lines into arguments.
+
+/// Splits a command line into arguments following POSIX shell quoting rules:
+/// whitespace separates arguments, single quotes preserve their content literally,
+/// double quotes allow `\` to escape `"`, `\` and `$`, and outside quotes `\` escapes any character.
+/// # Errors
+/// If a quote is left unterminated or the line ends with a lone `\`.
+pub(crate) fn split(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // whether a (possibly empty) argument is being built, `''` is a valid empty argument.
+    let mut in_arg = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err(format!("Unterminated single quote in `{line}`.")),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            // a backslash before a newline continues the line.
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err(format!("Unterminated double quote in `{line}`.")),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err(format!("Unterminated double quote in `{line}`.")),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => {
+                    in_arg = true;
+                    current.push(c);
+                }
+                None => return Err(format!("Trailing backslash in `{line}`.")),
+            },
+            c => {
+                in_arg = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
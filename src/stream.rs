@@ -0,0 +1,158 @@
+//! Parsing of command lines too long to hold at once, see [`crate::ArgParser::scan`].
+use std::{collections::VecDeque, iter::Peekable};
+
+use crate::{
+    scan::{self, Known},
+    Arg, ArgKind, ArgMap, ArgParser, ParseError,
+};
+
+/// Values of the last positional argument, read one at a time once the options ahead of them
+/// are parsed, e.g. the files given by `xargs`. Returned by [`ArgParser::scan`].
+///
+/// Every value of the argument is yielded, checked against its kind, followed by the words after
+/// `--` as they are. Limits on the number of values, like [`crate::Arg::max_values`], are not
+/// checked, since the values are never counted up front.
+pub struct ArgStream<'a> {
+    options: ArgMap,
+    arg: Option<&'a Arg>,
+    /// values parsed along with the options, yielded first.
+    pending: VecDeque<String>,
+    rest: Peekable<Box<dyn Iterator<Item = String> + 'a>>,
+    trailing: bool,
+}
+
+impl ArgStream<'_> {
+    /// Arguments parsed ahead of the stream, holding the first value of the streamed argument.
+    pub fn options(&self) -> &ArgMap {
+        &self.options
+    }
+}
+
+impl Iterator for ArgStream<'_> {
+    type Item = Result<String, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.pending.pop_front() {
+            return Some(Ok(value));
+        }
+        let word = self.rest.next()?;
+        if self.trailing {
+            return Some(Ok(word));
+        }
+        if word == "--" {
+            self.trailing = true;
+            return self.rest.next().map(Ok);
+        }
+        let Some(arg) = self.arg else {
+            return Some(Ok(word));
+        };
+        if !arg.takes_token(&word) {
+            return Some(Err(ParseError::InvalidArguments {
+                message: format!(
+                    "Option `{word}` must come before the values of `{}`, which are read one at a time.",
+                    arg.long_name
+                ),
+            }));
+        }
+        Some(arg.check_value(&word).map(|()| word))
+    }
+}
+
+/// Parses the options of `args` up to the first value of the last positional argument, when it
+/// takes multiple values, and leaves the other words to the returned stream.
+pub(crate) fn scan<'a>(
+    parser: &'a ArgParser,
+    args: Box<dyn Iterator<Item = String> + 'a>,
+) -> Result<ArgStream<'a>, ParseError> {
+    let positionals: Vec<&Arg> = parser.args.iter().filter(|arg| arg.positional).collect();
+    let streamed = positionals.last().copied().filter(|arg| arg.multiple);
+    let mut rest = args.peekable();
+    let mut head = Vec::new();
+    let mut seen = 0;
+    let mut trailing = false;
+    while streamed.is_none() || seen < positionals.len() {
+        let Some(word) = rest.next() else {
+            break;
+        };
+        if word == "--" && streamed.is_some() {
+            trailing = true;
+            break;
+        }
+        // negative numbers are values of numeric positional arguments.
+        if !scan::is_option(&word, positionals.get(seen).copied()) {
+            seen += 1;
+            head.push(word);
+            continue;
+        }
+        let option = valued_option(parser, &word);
+        head.push(word);
+        let Some(option) = option else {
+            continue;
+        };
+        // the words taken by the option, as the parser would.
+        match &option.optional_value {
+            Some(_) if option.require_equals => {}
+            Some(_) => head.extend(rest.next_if(|value| option.choice(value).is_some())),
+            None => {
+                head.extend(rest.next());
+                if option.multiple {
+                    while let Some(value) = scan::next_item(option, &mut rest) {
+                        head.push(value);
+                    }
+                }
+            }
+        }
+    }
+    let options = parser.try_parse_from(&head)?;
+    let pending = streamed
+        .and_then(|arg| options.inner.get(&arg.long_name))
+        .cloned()
+        .unwrap_or_default();
+    Ok(ArgStream {
+        options,
+        arg: streamed,
+        pending: pending.into(),
+        rest,
+        trailing,
+    })
+}
+
+/// Option named by `word` whose value is the next word, e.g. `--output` or `-vo`, but not
+/// `--color=never` or `-ofile.txt`.
+fn valued_option<'a>(parser: &'a ArgParser, word: &str) -> Option<&'a Arg> {
+    let takes_value =
+        |arg: &&Arg| !arg.positional && !matches!(arg.kind, ArgKind::Boolean | ArgKind::Count);
+    if let Some(name) = word.strip_prefix("--") {
+        if name.contains('=') {
+            return None;
+        }
+        return parser
+            .args
+            .iter()
+            .filter(takes_value)
+            .find(|arg| arg.answers_to(name));
+    }
+    // in a cluster like `-vo`, the value follows the last short name only.
+    let names = &word[1..];
+    if parser.args.iter().any(|arg| arg.answers_to(names)) {
+        return parser
+            .args
+            .iter()
+            .filter(takes_value)
+            .find(|arg| arg.answers_to(names));
+    }
+    let last = names.chars().last()?;
+    let valued = parser
+        .args
+        .iter()
+        .filter(takes_value)
+        .find(|arg| arg.answers_to_short(last))?;
+    let attached = names.chars().take(names.chars().count() - 1).any(|c| {
+        parser
+            .args
+            .iter()
+            .filter(takes_value)
+            .any(|arg| arg.answers_to_short(c))
+    });
+    (!attached).then_some(valued)
+}
@@ -0,0 +1,143 @@
+//! Text made of spans with a semantic style, built once by the help, usage and error printers
+//! and rendered for each output target: [`Styled`] displays as plain text, and
+//! [`Styled::ansi`] renders it for terminals.
+use std::fmt;
+
+/// Role of a span of text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Style {
+    /// Ordinary text, like descriptions.
+    Plain,
+    /// Text typed as is on the command line, like the program and option names.
+    Literal,
+    /// Stand-in for a value, like `<PATH>`.
+    Placeholder,
+    /// Title of a section, like `options:`.
+    Heading,
+    /// Description of a problem.
+    Error,
+}
+
+/// Sequence of styled spans, adjacent spans of the same style are merged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Styled {
+    spans: Vec<(Style, String)>,
+}
+
+impl Styled {
+    pub(crate) fn new() -> Styled {
+        Styled::default()
+    }
+
+    /// Text made of a single span.
+    pub(crate) fn span(style: Style, text: impl Into<String>) -> Styled {
+        let mut styled = Styled::new();
+        styled.push(style, text);
+        styled
+    }
+
+    /// Appends `text` in `style`.
+    pub(crate) fn push(&mut self, style: Style, text: impl Into<String>) -> &mut Styled {
+        let text = text.into();
+        match self.spans.last_mut() {
+            _ if text.is_empty() => {}
+            Some((last, span)) if *last == style => span.push_str(&text),
+            _ => self.spans.push((style, text)),
+        }
+        self
+    }
+
+    pub(crate) fn plain(&mut self, text: impl Into<String>) -> &mut Styled {
+        self.push(Style::Plain, text)
+    }
+
+    pub(crate) fn literal(&mut self, text: impl Into<String>) -> &mut Styled {
+        self.push(Style::Literal, text)
+    }
+
+    pub(crate) fn placeholder(&mut self, text: impl Into<String>) -> &mut Styled {
+        self.push(Style::Placeholder, text)
+    }
+
+    pub(crate) fn heading(&mut self, text: impl Into<String>) -> &mut Styled {
+        self.push(Style::Heading, text)
+    }
+
+    pub(crate) fn error(&mut self, text: impl Into<String>) -> &mut Styled {
+        self.push(Style::Error, text)
+    }
+
+    /// Appends the spans of `other`.
+    pub(crate) fn append(&mut self, other: Styled) -> &mut Styled {
+        for (style, text) in other.spans {
+            self.push(style, text);
+        }
+        self
+    }
+
+    /// Appends `other` followed by spaces up to `width` characters, like `{:<width$}`.
+    pub(crate) fn padded(&mut self, other: Styled, width: usize) -> &mut Styled {
+        let padding = width.saturating_sub(other.width());
+        self.append(other).plain(" ".repeat(padding))
+    }
+
+    /// Text with ANSI escape sequences: headings bold, literals cyan and errors red.
+    pub(crate) fn ansi(&self) -> String {
+        let mut out = String::new();
+        for (style, text) in &self.spans {
+            let code = match style {
+                Style::Plain | Style::Placeholder => {
+                    out.push_str(text);
+                    continue;
+                }
+                Style::Literal => "36",
+                Style::Heading => "1",
+                Style::Error => "31",
+            };
+            // escapes are closed before line breaks so pagers and `head` never leak a style.
+            for (i, line) in text.split('\n').enumerate() {
+                if i > 0 {
+                    out.push('\n');
+                }
+                if !line.is_empty() {
+                    out.push_str(&format!("\x1b[{code}m{line}\x1b[0m"));
+                }
+            }
+        }
+        out
+    }
+
+    /// The text without its trailing line breaks.
+    pub(crate) fn trim_end(mut self) -> Styled {
+        while let Some((_, text)) = self.spans.last_mut() {
+            let trimmed = text.trim_end_matches('\n').len();
+            text.truncate(trimmed);
+            if !text.is_empty() {
+                break;
+            }
+            self.spans.pop();
+        }
+        self
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Number of characters of the text.
+    pub(crate) fn width(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|(_, text)| text.chars().count())
+            .sum()
+    }
+}
+
+impl fmt::Display for Styled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (_, text) in &self.spans {
+            f.write_str(text)?;
+        }
+        Ok(())
+    }
+}
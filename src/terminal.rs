@@ -0,0 +1,141 @@
+//! Size of the terminal, to wrap the help page, and hidden input for secret prompts.
+use std::{
+    io::{self, IsTerminal},
+    sync::Mutex,
+};
+
+/// Number of columns of the terminal on stdout, from the `COLUMNS` environment variable or
+/// asking the terminal itself. `None` when stdout is not a terminal or the size is unknown.
+pub(crate) fn width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|&columns| columns > 0)
+    {
+        return Some(columns);
+    }
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    query()
+}
+
+#[cfg(unix)]
+fn query() -> Option<usize> {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: `winsize` is plain data, all zeroes is a valid value.
+    let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+    // SAFETY: TIOCGWINSZ only writes a `winsize` to the pointer given.
+    let status = unsafe { libc::ioctl(io::stdout().as_raw_fd(), libc::TIOCGWINSZ, &mut size) };
+    (status == 0 && size.ws_col > 0).then_some(usize::from(size.ws_col))
+}
+
+/// Other platforms rely on `COLUMNS`.
+#[cfg(not(unix))]
+fn query() -> Option<usize> {
+    None
+}
+
+/// Settings of the terminal while the input is not echoed. They are restored by whichever comes
+/// first of the read returning and [`restore_echo`], since a prompt that timed out leaves its
+/// read blocked on another thread.
+static SAVED: Mutex<Option<echo::Settings>> = Mutex::new(None);
+
+/// Runs `read` with the input typed on stdin not echoed, e.g. to read a password.
+/// The echo is restored afterwards, the line break typed by the user being left to the caller.
+/// # Returns
+/// The result of `read`, or `None` without running it when the echo can't be disabled, as
+/// secrets are never echoed.
+pub(crate) fn without_echo<T>(read: impl FnOnce() -> T) -> Option<T> {
+    let saved = echo::disable()?;
+    *SAVED.lock().unwrap_or_else(|e| e.into_inner()) = Some(saved);
+    let value = read();
+    restore_echo();
+    Some(value)
+}
+
+/// Restores the echo disabled by [`without_echo`], if not done yet.
+pub(crate) fn restore_echo() {
+    if let Some(saved) = SAVED.lock().unwrap_or_else(|e| e.into_inner()).take() {
+        echo::restore(&saved);
+    }
+}
+
+#[cfg(unix)]
+mod echo {
+    pub(super) type Settings = libc::termios;
+
+    /// Disables the echo of stdin.
+    /// # Returns
+    /// The settings to restore, or `None` when stdin is not a terminal.
+    pub(super) fn disable() -> Option<Settings> {
+        // SAFETY: `termios` is plain data, filled by `tcgetattr` before being read.
+        unsafe {
+            let mut saved: Settings = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut saved) != 0 {
+                return None;
+            }
+            let mut silent = saved;
+            silent.c_lflag &= !libc::ECHO;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &silent) != 0 {
+                return None;
+            }
+            Some(saved)
+        }
+    }
+
+    pub(super) fn restore(saved: &Settings) {
+        // SAFETY: `saved` was filled by `tcgetattr`.
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, saved);
+        }
+    }
+}
+
+#[cfg(windows)]
+mod echo {
+    use windows_sys::Win32::System::Console::{
+        GetConsoleMode, GetStdHandle, SetConsoleMode, CONSOLE_MODE, ENABLE_ECHO_INPUT,
+        STD_INPUT_HANDLE,
+    };
+
+    /// Mode of the console, the handle being looked up again to restore it.
+    pub(super) type Settings = CONSOLE_MODE;
+
+    /// Disables the echo of stdin.
+    /// # Returns
+    /// The settings to restore, or `None` when stdin is not a console.
+    pub(super) fn disable() -> Option<Settings> {
+        let mut saved: Settings = 0;
+        // SAFETY: the mode is only written to `saved`, the handle is checked by the functions.
+        unsafe {
+            let input = GetStdHandle(STD_INPUT_HANDLE);
+            if GetConsoleMode(input, &mut saved) == 0
+                || SetConsoleMode(input, saved & !ENABLE_ECHO_INPUT) == 0
+            {
+                return None;
+            }
+        }
+        Some(saved)
+    }
+
+    pub(super) fn restore(saved: &Settings) {
+        // SAFETY: see `disable`.
+        unsafe {
+            SetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), *saved);
+        }
+    }
+}
+
+/// Other platforms can't hide the input.
+#[cfg(not(any(unix, windows)))]
+mod echo {
+    pub(super) type Settings = ();
+
+    pub(super) fn disable() -> Option<Settings> {
+        None
+    }
+
+    pub(super) fn restore(_: &Settings) {}
+}
@@ -0,0 +1,196 @@
+//! Helpers to verify a command line interface from unit tests.
+//!
+//! ```
+//! # use clarg::{Arg, ArgParser};
+//! let parser = ArgParser::new("Find duplicate files.")
+//!     .arg(Arg::string("path", Some('p'), true, "Directory to examine"))
+//!     .arg(Arg::boolean("json", None, "Format output as JSON"))
+//!     .example("--path /tmp --json");
+//! clarg::testing::check_examples(&parser).unwrap();
+//! ```
+use std::{collections::VecDeque, ffi::OsString, io, sync::Mutex};
+
+use crate::{
+    host::{Host, Stream},
+    shell, ArgMap, ArgParser,
+};
+
+/// Tokenizes and validates every example registered with [`ArgParser::example`],
+/// so documented examples can never drift out of sync with the real interface.
+/// Examples are split following POSIX shell quoting rules and checked with [`ArgParser::check`].
+/// # Errors
+/// A report listing every failing example along with its problems.
+pub fn check_examples(parser: &ArgParser) -> Result<(), String> {
+    let mut report = String::new();
+    for example in &parser.examples {
+        let problems = match shell::split(example) {
+            Ok(args) => match parser.check(args) {
+                Ok(()) => continue,
+                Err(errors) => errors.iter().map(|e| e.to_string()).collect(),
+            },
+            Err(e) => vec![e],
+        };
+        report.push_str(&format!("Example `{example}` is invalid:\n"));
+        for problem in problems {
+            report.push_str(&format!("\t{problem}\n"));
+        }
+    }
+    if report.is_empty() {
+        Ok(())
+    } else {
+        Err(report)
+    }
+}
+
+/// Parses `args` with [`ArgParser::try_parse_from`] and hands the arguments to `check`, so a test
+/// reads as the command line and what it should mean.
+/// # Panics
+/// When `args` fail to parse, with the command line and the error.
+/// # Example
+/// ```
+/// # use clarg::{Arg, ArgParser, testing::assert_parses};
+/// let parser = ArgParser::new("Find duplicate files.")
+///     .arg(Arg::string("path", Some('p'), true, "Directory to examine"));
+/// assert_parses(&parser, &["--path", "/tmp"], |args| {
+///     assert_eq!(args.get::<String>("path").unwrap(), "/tmp");
+/// });
+/// ```
+#[track_caller]
+pub fn assert_parses<F>(parser: &ArgParser, args: &[&str], check: F)
+where
+    F: FnOnce(&ArgMap),
+{
+    match parser.try_parse_from(args) {
+        Ok(map) => check(&map),
+        Err(error) => panic!("Command line `{}` failed to parse: {error}", args.join(" ")),
+    }
+}
+
+/// Parses `args` with [`ArgParser::try_parse_from`] and checks that they are rejected with an
+/// error whose message contains `text`, or whose code is `text`, e.g. `E_MISSING_REQUIRED`.
+/// # Panics
+/// When `args` parse, or fail with another error.
+/// # Example
+/// ```
+/// # use clarg::{Arg, ArgParser, testing::assert_error_contains};
+/// let parser = ArgParser::new("Find duplicate files.")
+///     .arg(Arg::string("path", Some('p'), true, "Directory to examine"));
+/// assert_error_contains(&parser, &[], "Missing required");
+/// ```
+#[track_caller]
+pub fn assert_error_contains(parser: &ArgParser, args: &[&str], text: &str) {
+    match parser.try_parse_from(args) {
+        Ok(_) => panic!(
+            "Command line `{}` parsed, expected an error containing `{text}`",
+            args.join(" ")
+        ),
+        Err(error) => {
+            let message = error.to_string();
+            if !message.contains(text) && error.code() != text {
+                panic!(
+                    "Command line `{}` failed with `{message}` ({}), expected an error containing `{text}`",
+                    args.join(" "),
+                    error.code()
+                );
+            }
+        }
+    }
+}
+
+/// Stand-in for the process given to [`ArgParser::host`], so tests control the environment
+/// variables, terminals, prompt answers and stdin the parser sees. By default nothing is set,
+/// no stream is a terminal and stdin is closed and empty.
+///
+/// ```
+/// # use std::sync::{Arc, Mutex};
+/// # use clarg::{Arg, ArgParser, testing::FakeHost};
+/// let shown = Arc::new(Mutex::new(String::new()));
+/// let output = Arc::clone(&shown);
+/// let parser = ArgParser::new("Connect to the database.")
+///     .arg(Arg::string("host", None, true, "Database host").prompt("Database host"))
+///     .host(FakeHost::new().terminal(true).answer("db.local"))
+///     .on_output(move |_, text| output.lock().unwrap().push_str(text));
+/// let args = parser.try_parse_from::<_, &str>([]).unwrap();
+/// assert_eq!(args.get::<String>("host").unwrap(), "db.local");
+/// assert_eq!(*shown.lock().unwrap(), "Database host: ");
+/// ```
+#[derive(Debug, Default)]
+pub struct FakeHost {
+    vars: Vec<(String, String)>,
+    terminal: bool,
+    width: Option<usize>,
+    answers: Mutex<VecDeque<String>>,
+    stdin: String,
+}
+
+impl FakeHost {
+    pub fn new() -> FakeHost {
+        FakeHost::default()
+    }
+
+    /// Set the environment variable `name` to `value`.
+    pub fn var(mut self, name: &str, value: &str) -> FakeHost {
+        self.vars.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Make stdin, stdout and stderr terminals, or not.
+    pub fn terminal(mut self, terminal: bool) -> FakeHost {
+        self.terminal = terminal;
+        self
+    }
+
+    /// Set the number of columns of the terminal.
+    pub fn width(mut self, columns: usize) -> FakeHost {
+        self.width = Some(columns);
+        self
+    }
+
+    /// Set the content of stdin read by arguments set with [`crate::Arg::allow_stdin`].
+    pub fn stdin(mut self, content: &str) -> FakeHost {
+        self.stdin = content.to_owned();
+        self
+    }
+
+    /// Queue `line` as the answer to the next prompt. Prompts beyond the queued answers find
+    /// stdin closed.
+    pub fn answer(self, line: &str) -> FakeHost {
+        if let Ok(mut answers) = self.answers.lock() {
+            answers.push_back(line.to_owned());
+        }
+        self
+    }
+}
+
+impl Host for FakeHost {
+    fn var(&self, name: &str) -> Option<OsString> {
+        self.vars
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into())
+    }
+
+    fn vars(&self) -> Vec<(OsString, OsString)> {
+        self.vars
+            .iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect()
+    }
+
+    fn is_terminal(&self, _stream: Stream) -> bool {
+        self.terminal
+    }
+
+    fn terminal_width(&self) -> Option<usize> {
+        self.width
+    }
+
+    fn read_line(&self) -> Option<String> {
+        self.answers.lock().ok()?.pop_front()
+    }
+
+    fn read_stdin(&self) -> io::Result<String> {
+        Ok(self.stdin.clone())
+    }
+}
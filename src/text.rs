@@ -0,0 +1,37 @@
+//! Word wrapping used by the help page, for documentation generated alongside it.
+//!
+//! Lines are filled greedily: each one takes as many words as fit, looking neither ahead nor
+//! back. Editing a word only changes the line holding it and the following lines of the same
+//! paragraph, and those settle back on the previous breaks as soon as a break falls at the same
+//! word again. Regenerated man pages or Markdown files committed to version control therefore
+//! show small diffs when a description changes, unlike wrapping that balances line lengths.
+//! ```
+//! let lines = clarg::text::wrap("Directory to examine, searched recursively", 20);
+//! assert_eq!(lines, ["Directory to", "examine, searched", "recursively"]);
+//! ```
+
+/// Wraps `text` at word boundaries into lines of at most `width` characters, unless a single
+/// word is longer. Line breaks of `text` are kept, runs of spaces collapse into one and no line
+/// ends with a space.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut length = 0;
+        for word in paragraph.split_whitespace() {
+            let word_length = word.chars().count();
+            if length > 0 && length + 1 + word_length > width {
+                lines.push(std::mem::take(&mut line));
+                length = 0;
+            }
+            if length > 0 {
+                line.push(' ');
+                length += 1;
+            }
+            line.push_str(word);
+            length += word_length;
+        }
+        lines.push(line);
+    }
+    lines
+}
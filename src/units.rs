@@ -0,0 +1,88 @@
+//! Durations like `2h30m` and sizes like `10MB` or `1GiB` given on the command line.
+use core::time::Duration;
+
+/// Units of durations with their length in seconds.
+const DURATION_UNITS: [(&str, f64); 5] = [
+    ("ms", 0.001),
+    ("s", 1.0),
+    ("m", 60.0),
+    ("h", 3600.0),
+    ("d", 86_400.0),
+];
+
+/// Units of sizes with their number of bytes, compared regardless of case.
+const SIZE_UNITS: [(&str, u64); 10] = [
+    ("b", 1),
+    ("kb", 1_000),
+    ("mb", 1_000_000),
+    ("gb", 1_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("kib", 1 << 10),
+    ("mib", 1 << 20),
+    ("gib", 1 << 30),
+    ("tib", 1 << 40),
+    ("pib", 1 << 50),
+];
+
+/// Parses a duration made of amounts followed by their unit, e.g. `30s`, `5m`, `2h30m` or `1.5h`.
+/// # Errors
+/// If an amount has no unit or an unknown one, or the duration doesn't fit in a [`Duration`].
+pub(crate) fn duration(value: &str) -> Result<Duration, ()> {
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return Err(());
+    }
+    let mut seconds = 0.0;
+    while !rest.is_empty() {
+        let (amount, unit) = split_amount(rest);
+        let unit_end = unit
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(unit.len());
+        let length = DURATION_UNITS
+            .iter()
+            .find(|(name, _)| *name == &unit[..unit_end])
+            .map(|(_, length)| length)
+            .ok_or(())?;
+        seconds += amount.parse::<f64>().map_err(|_| ())? * length;
+        rest = &unit[unit_end..];
+    }
+    Duration::try_from_secs_f64(seconds).map_err(|_| ())
+}
+
+/// Parses a number of bytes, e.g. `512`, `10MB`, `1.5 GiB`. Units are decimal (`KB`, `MB`, ...)
+/// or binary (`KiB`, `MiB`, ...) and ignore case.
+/// # Errors
+/// If the unit is unknown or the size doesn't fit in a `u64`.
+pub(crate) fn size(value: &str) -> Result<u64, ()> {
+    let (amount, unit) = split_amount(value.trim());
+    let unit = unit.trim();
+    let bytes = if unit.is_empty() {
+        1
+    } else {
+        SIZE_UNITS
+            .iter()
+            .find(|(name, _)| unit.eq_ignore_ascii_case(name))
+            .map(|(_, bytes)| *bytes)
+            .ok_or(())?
+    };
+    // whole amounts are multiplied exactly, large sizes don't fit in a `f64`.
+    if let Ok(amount) = amount.parse::<u64>() {
+        return amount.checked_mul(bytes).ok_or(());
+    }
+    let size = amount.parse::<f64>().map_err(|_| ())? * bytes as f64;
+    if size.is_finite() && size >= 0.0 && size < u64::MAX as f64 {
+        // rounded half away from zero, without the float functions of `std`.
+        let whole = size as u64;
+        Ok(whole + u64::from(size - whole as f64 >= 0.5))
+    } else {
+        Err(())
+    }
+}
+
+/// Splits `value` after its leading amount, e.g. `1.5` and `GiB` for `1.5GiB`.
+fn split_amount(value: &str) -> (&str, &str) {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    value.split_at(end)
+}
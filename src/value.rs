@@ -0,0 +1,102 @@
+//! Values of the arguments, converted once according to their kind when parsing.
+use std::{
+    any::Any,
+    ffi::{OsStr, OsString},
+    path::PathBuf,
+};
+
+use crate::{units, ArgKind};
+
+/// Value of an argument, stored by [`crate::ArgMap`] next to the raw value when it is not text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    /// Value of integer and count arguments.
+    Int(i64),
+    /// Value of unsigned integer arguments, and of size arguments in bytes.
+    UInt(u64),
+    /// Value of float arguments.
+    Float(f64),
+    /// Value of boolean arguments.
+    Bool(bool),
+    /// Values of arguments accepting multiple values, in the order given.
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Converts a raw value already validated against `kind`.
+    /// # Returns
+    /// `None` for text, which is read from the raw value rather than copied.
+    pub(crate) fn parse(kind: ArgKind, raw: &str) -> Option<Value> {
+        match kind {
+            ArgKind::Integer | ArgKind::Count => raw.parse().ok().map(Value::Int),
+            ArgKind::Unsigned => raw.parse().ok().map(Value::UInt),
+            ArgKind::Size => units::size(raw).ok().map(Value::UInt),
+            ArgKind::Float => raw.parse().ok().map(Value::Float),
+            ArgKind::Boolean => raw.parse().ok().map(Value::Bool),
+            _ => None,
+        }
+    }
+
+    /// Converts the raw values of an argument, `multiple` telling whether it takes several.
+    /// # Returns
+    /// `None` when any of them is text.
+    pub(crate) fn parse_all(kind: ArgKind, multiple: bool, raw: &[String]) -> Option<Value> {
+        match multiple {
+            true => raw
+                .iter()
+                .map(|raw| Value::parse(kind, raw))
+                .collect::<Option<_>>()
+                .map(Value::List),
+            false => Value::parse(kind, raw.last()?),
+        }
+    }
+
+    /// The value itself when `T` is the type it is stored as, e.g. `i64` for [`Value::Int`],
+    /// or any primitive integer type holding it, sparing a conversion from its text.
+    /// Nothing is allocated: the value is written into an `Option<T>` seen as `dyn Any`.
+    pub(crate) fn downcast<T: 'static>(&self) -> Option<T> {
+        let mut out: Option<T> = None;
+        let slot = &mut out as &mut dyn Any;
+        match *self {
+            Value::Int(value) => integer(slot, i128::from(value)),
+            Value::UInt(value) => integer(slot, i128::from(value)),
+            Value::Float(value) => put::<f64, _>(slot, value),
+            Value::Bool(value) => put::<bool, _>(slot, value),
+            Value::List(_) => {}
+        }
+        out
+    }
+}
+
+/// `original` as `T` when it is a `PathBuf` or an `OsString`, for values that are not valid UTF-8.
+pub(crate) fn from_os<T: 'static>(original: &OsStr) -> Option<T> {
+    let mut out: Option<T> = None;
+    let slot = &mut out as &mut dyn Any;
+    put::<PathBuf, _>(slot, original);
+    put::<OsString, _>(slot, original);
+    out
+}
+
+/// Writes `value` into `slot` when it is an `Option<V>` for a primitive integer type `V`,
+/// leaving `None` when it doesn't fit, in which case converting the text fails as well.
+fn integer(slot: &mut dyn Any, value: i128) {
+    put::<i8, _>(slot, value);
+    put::<i16, _>(slot, value);
+    put::<i32, _>(slot, value);
+    put::<i64, _>(slot, value);
+    put::<i128, _>(slot, value);
+    put::<isize, _>(slot, value);
+    put::<u8, _>(slot, value);
+    put::<u16, _>(slot, value);
+    put::<u32, _>(slot, value);
+    put::<u64, _>(slot, value);
+    put::<u128, _>(slot, value);
+    put::<usize, _>(slot, value);
+}
+
+/// Writes `value` converted into `slot` when it is an `Option<V>`.
+fn put<V: TryFrom<X> + 'static, X>(slot: &mut dyn Any, value: X) {
+    if let Some(slot) = slot.downcast_mut::<Option<V>>() {
+        *slot = V::try_from(value).ok();
+    }
+}
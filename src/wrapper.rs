@@ -0,0 +1,104 @@
+//! Shell functions wrapping the program, for what a process cannot do on its own.
+//!
+//! The function generated by [`ArgParser::generate_wrapper`] is named after the program and
+//! sourced from the shell startup file, e.g. `eval "$(myapp --wrapper bash)"`. It:
+//! * prepends the options of `<PREFIX>_DEFAULT_ARGS`, so users set their preferred flags once,
+//! * changes the directory of the calling shell when the program asks for it with
+//!   [`change_directory`], for `myapp cd`-style commands.
+//!
+//! `<PREFIX>` is the [`ArgParser::env_prefix`], or the program name in upper case, e.g.
+//! `MYAPP_DEFAULT_ARGS`. Default options are split on spaces, without quoting.
+//! ```
+//! # use clarg::{completions::Shell, ArgParser};
+//! let parser = ArgParser::new("Jump to bookmarked directories.").executable_name("jump");
+//! let mut script = Vec::new();
+//! parser.generate_wrapper(Shell::Bash, &mut script).unwrap();
+//! assert!(String::from_utf8(script).unwrap().starts_with("jump() {"));
+//! ```
+use std::{fs, io, path::Path};
+
+use crate::{completions::Shell, ArgParser};
+
+/// Writes the wrapper function of the program run by `parser` for `shell`.
+/// # Errors
+/// If `shell` is not bash, zsh or fish, or writing to `out` fails.
+pub(crate) fn generate(
+    parser: &ArgParser,
+    shell: Shell,
+    out: &mut impl io::Write,
+) -> io::Result<()> {
+    let program = &parser.executable;
+    let prefix = prefix(parser);
+    match shell {
+        Shell::Bash | Shell::Zsh => {
+            // zsh doesn't split unquoted variables unless asked to.
+            let defaults = match shell {
+                Shell::Zsh => format!("${{={prefix}_DEFAULT_ARGS}}"),
+                _ => format!("${prefix}_DEFAULT_ARGS"),
+            };
+            writeln!(out, "{program}() {{")?;
+            writeln!(out, "    local cd_file code")?;
+            writeln!(out, "    cd_file=\"$(mktemp)\" || return")?;
+            writeln!(
+                out,
+                "    {prefix}_CD_FILE=\"$cd_file\" command {program} {defaults} \"$@\""
+            )?;
+            writeln!(out, "    code=$?")?;
+            writeln!(out, "    if [ -s \"$cd_file\" ]; then")?;
+            writeln!(out, "        cd -- \"$(cat -- \"$cd_file\")\" || code=$?")?;
+            writeln!(out, "    fi")?;
+            writeln!(out, "    rm -f -- \"$cd_file\"")?;
+            writeln!(out, "    return $code")?;
+            writeln!(out, "}}")
+        }
+        Shell::Fish => {
+            writeln!(out, "function {program} --wraps {program}")?;
+            writeln!(out, "    set -l cd_file (mktemp); or return")?;
+            writeln!(
+                out,
+                "    {prefix}_CD_FILE=$cd_file command {program} (string split -n ' ' -- \"${prefix}_DEFAULT_ARGS\") $argv"
+            )?;
+            writeln!(out, "    set -l code $status")?;
+            writeln!(out, "    if test -s $cd_file")?;
+            writeln!(out, "        cd (cat $cd_file); or set code $status")?;
+            writeln!(out, "    end")?;
+            writeln!(out, "    rm -f $cd_file")?;
+            writeln!(out, "    return $code")?;
+            writeln!(out, "end")
+        }
+        shell => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Wrapper functions are not supported for {shell}."),
+        )),
+    }
+}
+
+/// Asks the wrapper function to change the directory of the calling shell to `dir` once the
+/// program exits, e.g. for `myapp cd <bookmark>`.
+/// # Returns
+/// Whether the program was run through the wrapper, otherwise nothing is done and the program
+/// may print the directory instead.
+/// # Errors
+/// If the wrapper's file cannot be written.
+pub fn change_directory(parser: &ArgParser, dir: &Path) -> io::Result<bool> {
+    let Some(file) = parser.host.var(&format!("{}_CD_FILE", prefix(parser))) else {
+        return Ok(false);
+    };
+    fs::write(file, dir.as_os_str().as_encoded_bytes())?;
+    Ok(true)
+}
+
+/// Prefix of the variables read by the wrapper, e.g. `MYAPP`.
+fn prefix(parser: &ArgParser) -> String {
+    match &parser.env_prefix {
+        Some(prefix) => prefix.clone(),
+        None => parser
+            .executable
+            .chars()
+            .map(|c| match c {
+                c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+                _ => '_',
+            })
+            .collect(),
+    }
+}